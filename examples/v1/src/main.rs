@@ -15,7 +15,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         commitment,
         market_id,
         true,
-        1000
     ).await?;
 
     println!("Initialized OpenBook V1 Client: {:?}", ob_client);
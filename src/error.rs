@@ -0,0 +1,277 @@
+//! This module contains the typed errors returned by the OpenBook clients.
+//!
+//! Client methods return `anyhow::Result`, so callers can recover the variant with
+//! `err.downcast_ref::<OpenBookError>()`. The market lookups return a `MarketError` instead,
+//! which callers can match on directly.
+
+use solana_client::client_error::ClientError;
+use solana_sdk::{
+    program_error::ProgramError,
+    pubkey::{ParsePubkeyError, Pubkey},
+    signature::Signature,
+};
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// Errors surfaced by the OpenBook clients that callers may want to handle explicitly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenBookError {
+    /// The cached book is crossed or locked (best bid >= best ask); refresh it before trading.
+    CrossedBook,
+    /// No order with the given client order id was found in the cached book.
+    ClientOrderIdNotFound(u64),
+    /// The RPC node rate limited the request (HTTP 429).
+    RateLimited {
+        /// How long the node asked to wait before retrying, when it said so.
+        retry_after: Option<Duration>,
+    },
+    /// The signer's SOL balance cannot cover the fees and rent the transaction needs.
+    InsufficientSol {
+        /// The signer's balance, in lamports.
+        have: u64,
+        /// The lamports required, in lamports.
+        need: u64,
+    },
+    /// The loaded market state does not belong to the requested market address.
+    MarketAddressMismatch {
+        /// The market address that was requested.
+        expected: Pubkey,
+        /// The own address stored in the loaded market state.
+        found: Pubkey,
+    },
+    /// The market's event queue is full and must be cranked before new orders can be placed.
+    EventQueueFull,
+    /// The fee discount account holds neither SRM nor MSRM; the mint it holds is given.
+    InvalidFeeDiscountMint(Pubkey),
+    /// A cached bids or asks address differs from the one stored in the market state.
+    BookAddressMismatch {
+        /// The address stored in the market state.
+        expected: Pubkey,
+        /// The cached address.
+        found: Pubkey,
+    },
+    /// The mint account does not exist on chain, or the owner holds no token account for it.
+    MintNotFound(Pubkey),
+    /// The token symbol is neither a well-known symbol nor a mint address.
+    UnknownMintSymbol(String),
+    /// An account is not owned by the program expected to own it.
+    InvalidProgramOwner {
+        /// The program expected to own the account.
+        expected: Pubkey,
+        /// The account's actual owner.
+        found: Pubkey,
+    },
+    /// The client was loaded without a signing keypair and cannot send transactions.
+    ReadOnly,
+    /// The open orders account still holds orders or unsettled funds and cannot be closed.
+    OpenOrdersNotEmpty(Pubkey),
+    /// The account is not an initialized OpenBook market.
+    NotAMarket(Pubkey),
+    /// The transaction with the given signature did not confirm before the timeout elapsed.
+    ConfirmationTimeout(Signature),
+    /// The client order id lies outside the client's `client_id_prefix` namespace.
+    ClientOrderIdOutsideNamespace {
+        /// The client order id.
+        client_order_id: u64,
+        /// The namespace prefix of the client.
+        prefix: u32,
+    },
+    /// Bids or asks account data of the given length is not a slab header followed by whole nodes.
+    InvalidSlabAccount(usize),
+}
+
+impl Display for OpenBookError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenBookError::CrossedBook => {
+                write!(f, "the cached book is crossed, refresh it before trading")
+            }
+            OpenBookError::ClientOrderIdNotFound(client_order_id) => write!(
+                f,
+                "client order id {} not found in the cached book",
+                client_order_id
+            ),
+            OpenBookError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "rate limited by the RPC node, retry after {:?}",
+                retry_after
+            ),
+            OpenBookError::RateLimited { retry_after: None } => {
+                write!(f, "rate limited by the RPC node")
+            }
+            OpenBookError::InsufficientSol { have, need } => write!(
+                f,
+                "insufficient SOL for fees and rent: have {} lamports, need {}",
+                have, need
+            ),
+            OpenBookError::MarketAddressMismatch { expected, found } => write!(
+                f,
+                "market state belongs to {}, expected {}",
+                found, expected
+            ),
+            OpenBookError::EventQueueFull => {
+                write!(f, "the event queue is full, crank it before placing orders")
+            }
+            OpenBookError::InvalidFeeDiscountMint(mint) => write!(
+                f,
+                "fee discount account mint {} is neither SRM nor MSRM",
+                mint
+            ),
+            OpenBookError::BookAddressMismatch { expected, found } => write!(
+                f,
+                "cached book address {} does not match the market state's {}",
+                found, expected
+            ),
+            OpenBookError::MintNotFound(mint) => write!(f, "mint {} not found", mint),
+            OpenBookError::UnknownMintSymbol(symbol) => {
+                write!(f, "unknown token symbol {:?}", symbol)
+            }
+            OpenBookError::InvalidProgramOwner { expected, found } => write!(
+                f,
+                "account is owned by {}, expected program {}",
+                found, expected
+            ),
+            OpenBookError::ReadOnly => {
+                write!(f, "the client is read-only and cannot sign transactions")
+            }
+            OpenBookError::OpenOrdersNotEmpty(oo_key) => write!(
+                f,
+                "open orders account {} still holds orders or unsettled funds, cancel and settle them first",
+                oo_key
+            ),
+            OpenBookError::NotAMarket(address) => {
+                write!(f, "account {} is not an initialized OpenBook market", address)
+            }
+            OpenBookError::ConfirmationTimeout(signature) => write!(
+                f,
+                "transaction {} was not confirmed before the timeout",
+                signature
+            ),
+            OpenBookError::ClientOrderIdOutsideNamespace {
+                client_order_id,
+                prefix,
+            } => write!(
+                f,
+                "client order id {} is outside the namespace of prefix {}",
+                client_order_id, prefix
+            ),
+            OpenBookError::InvalidSlabAccount(len) => write!(
+                f,
+                "slab account data of {} bytes is not a header followed by whole nodes",
+                len
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OpenBookError {}
+
+/// Errors returned by the market lookups of the client, such as `OBClient::get_mint_address`.
+///
+/// Unlike `OpenBookError`, which is carried inside an `anyhow::Error`, a `MarketError` is returned
+/// as is, so a missing mint can be told apart from an RPC failure with a plain `match`.
+#[derive(Debug)]
+pub enum MarketError {
+    /// The RPC request failed.
+    Rpc(ClientError),
+    /// The mint account does not exist on chain, or the owner holds no token account for it.
+    MintNotFound(Pubkey),
+    /// An account is not owned by the program expected to own it.
+    InvalidProgramOwner {
+        /// The program expected to own the account.
+        expected: Pubkey,
+        /// The account's actual owner.
+        found: Pubkey,
+    },
+    /// An order id does not fit in 128 bits.
+    OrderIdOverflow,
+    /// Account data or an address could not be deserialized.
+    Deserialize(String),
+    /// Any other OpenBook error, e.g. `OpenBookError::RateLimited`.
+    OpenBook(OpenBookError),
+    /// An error that has no dedicated variant, e.g. a failed transaction.
+    Other(anyhow::Error),
+}
+
+impl Display for MarketError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketError::Rpc(err) => write!(f, "RPC request failed: {}", err),
+            MarketError::MintNotFound(mint) => write!(f, "mint {} not found", mint),
+            MarketError::InvalidProgramOwner { expected, found } => write!(
+                f,
+                "account is owned by {}, expected program {}",
+                found, expected
+            ),
+            MarketError::OrderIdOverflow => write!(f, "order id does not fit in 128 bits"),
+            MarketError::Deserialize(message) => write!(f, "failed to deserialize: {}", message),
+            MarketError::OpenBook(err) => write!(f, "{}", err),
+            MarketError::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for MarketError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MarketError::Rpc(err) => Some(err),
+            MarketError::OpenBook(err) => Some(err),
+            MarketError::Other(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ClientError> for MarketError {
+    fn from(err: ClientError) -> Self {
+        MarketError::Rpc(err)
+    }
+}
+
+impl From<OpenBookError> for MarketError {
+    fn from(err: OpenBookError) -> Self {
+        match err {
+            OpenBookError::MintNotFound(mint) => MarketError::MintNotFound(mint),
+            OpenBookError::InvalidProgramOwner { expected, found } => {
+                MarketError::InvalidProgramOwner { expected, found }
+            }
+            err => MarketError::OpenBook(err),
+        }
+    }
+}
+
+impl From<ProgramError> for MarketError {
+    fn from(err: ProgramError) -> Self {
+        MarketError::Deserialize(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for MarketError {
+    fn from(err: std::io::Error) -> Self {
+        MarketError::Deserialize(err.to_string())
+    }
+}
+
+impl From<ParsePubkeyError> for MarketError {
+    fn from(err: ParsePubkeyError) -> Self {
+        MarketError::Deserialize(err.to_string())
+    }
+}
+
+/// Recovers the typed error carried by an `anyhow::Error`, as returned by `Rpc`.
+impl From<anyhow::Error> for MarketError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ClientError>() {
+            Ok(err) => return MarketError::Rpc(err),
+            Err(err) => err,
+        };
+        match err.downcast::<OpenBookError>() {
+            Ok(err) => err.into(),
+            Err(err) => MarketError::Other(err),
+        }
+    }
+}
@@ -3,6 +3,7 @@
 
 #[cfg(feature = "cli")]
 pub mod cli;
+pub mod error;
 pub mod rpc;
 #[cfg(feature = "cli")]
 pub mod tui;
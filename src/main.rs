@@ -49,7 +49,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     CommitmentConfig::confirmed(),
                     cmd.market_id.parse().unwrap(),
                     true,
-                    123456789,
                 )
                 .await?;
                 match cmd.command {
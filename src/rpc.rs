@@ -2,26 +2,46 @@
 
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::OpenBookError;
+use crate::utils::{is_compute_unit_limit_instruction, is_compute_unit_price_instruction};
 
 use anyhow::Result;
 use backon::ExponentialBuilder;
 use backon::Retryable;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use solana_account_decoder::{parse_token::UiTokenAmount, UiAccountEncoding};
 use solana_client::rpc_config::RpcSendTransactionConfig;
-use solana_client::rpc_request::RpcError;
+use solana_client::rpc_request::{RpcError, TokenAccountsFilter};
 use solana_client::{
     client_error::ClientError,
     nonblocking::rpc_client::RpcClient,
     rpc_client::GetConfirmedSignaturesForAddress2Config,
-    rpc_config::{RpcAccountInfoConfig, RpcTransactionConfig},
-    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig,
+        RpcTransactionConfig,
+    },
+    rpc_filter::RpcFilterType,
+    rpc_response::{
+        RpcConfirmedTransactionStatusWithSignature, RpcKeyedAccount, RpcPrioritizationFee,
+        RpcSimulateTransactionResult,
+    },
 };
 use solana_rpc_client_api::client_error::ErrorKind;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::signature::Signer;
 use solana_sdk::signer::keypair::Keypair;
 use solana_sdk::transaction::Transaction;
 use solana_sdk::{account::Account, pubkey::Pubkey, signature::Signature};
-use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiTransactionEncoding,
+};
+use tokio::sync::Mutex;
 
 #[cfg(feature = "v2")]
 use anchor_lang::{AccountDeserialize, Discriminator};
@@ -30,19 +50,73 @@ use anchor_lang::{AccountDeserialize, Discriminator};
 use openbookdex_v2::state::OpenOrdersAccount;
 
 #[cfg(feature = "v2")]
-use solana_client::{
-    rpc_config::RpcProgramAccountsConfig,
-    rpc_filter::{Memcmp, RpcFilterType},
-};
-
-#[cfg(feature = "v2")]
-use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_filter::Memcmp;
 
 /// Wrapper type for RpcClient providing additional functionality and enabling Debug trait implementation.
 ///
 /// This struct holds an `Arc` of `RpcClient` to ensure thread safety and efficient resource sharing.
 #[derive(Clone)]
-pub struct Rpc(Arc<RpcClient>);
+pub struct Rpc {
+    client: Arc<RpcClient>,
+    send_config: SendConfig,
+    commitment: CommitmentConfig,
+    blockhash_cache: Arc<Mutex<Option<(Instant, Hash)>>>,
+    last_compute_units: Arc<Mutex<Option<u64>>>,
+}
+
+/// Interval between two signature status polls while waiting for a confirmation.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Maximum number of transactions fetched concurrently by [`Rpc::total_fees_paid`].
+const TRANSACTION_FETCH_CONCURRENCY: usize = 8;
+
+/// Options applied to every transaction sent through [`Rpc::send_and_confirm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SendConfig {
+    /// Whether to skip the preflight simulation of the transaction.
+    pub skip_preflight: bool,
+    /// How many times the RPC node rebroadcasts the transaction; `None` uses the node default.
+    pub max_retries: Option<usize>,
+    /// Percentile (0-100) of recent prioritization fees used as the compute-unit price of every
+    /// send; `None` leaves the instructions untouched.
+    pub priority_fee_percentile: Option<u8>,
+    /// Fixed compute-unit price, in micro-lamports, of every send; takes precedence over
+    /// `priority_fee_percentile`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// Compute-unit limit requested by every send; `None` keeps the runtime default. When set,
+    /// the compute units consumed by each confirmed send are read back, see
+    /// [`Rpc::last_compute_units`].
+    pub compute_unit_limit: Option<u32>,
+    /// How long a fetched blockhash is reused for new transactions; `None` fetches one per send.
+    pub blockhash_ttl: Option<Duration>,
+    /// How long a send polls the signature status for the client's commitment before failing
+    /// with `OpenBookError::ConfirmationTimeout`; `None` returns once the node accepts it.
+    pub confirm_timeout: Option<Duration>,
+}
+
+impl SendConfig {
+    /// Builds the `RpcSendTransactionConfig` sent along with the transaction.
+    ///
+    /// # Parameters
+    ///
+    /// - `preflight_commitment`: The commitment level used by the preflight simulation.
+    ///
+    /// # Returns
+    ///
+    /// The `RpcSendTransactionConfig` carrying these options.
+    pub fn rpc_send_config(
+        &self,
+        preflight_commitment: CommitmentLevel,
+    ) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            skip_preflight: self.skip_preflight,
+            max_retries: self.max_retries,
+            preflight_commitment: Some(preflight_commitment),
+            encoding: None,
+            min_context_slot: None,
+        }
+    }
+}
 
 impl Rpc {
     /// Constructs a new Rpc wrapper around the provided RpcClient instance.
@@ -72,12 +146,202 @@ impl Rpc {
     /// }
     /// ```
     pub fn new(rpc_client: RpcClient) -> Self {
-        Rpc(Arc::new(rpc_client))
+        Rpc {
+            commitment: rpc_client.commitment(),
+            client: Arc::new(rpc_client),
+            send_config: SendConfig::default(),
+            blockhash_cache: Arc::new(Mutex::new(None)),
+            last_compute_units: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Sets the commitment used by the reads and sends of this client.
+    ///
+    /// The inner `RpcClient` is shared, so a clone can read at `processed` while the original
+    /// keeps settling at `confirmed` or `finalized`.
+    ///
+    /// # Parameters
+    ///
+    /// - `commitment`: The commitment level of account fetches, blockhashes, preflight and
+    ///   confirmation.
+    ///
+    /// # Returns
+    ///
+    /// The Rpc wrapper using the given commitment.
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Returns the commitment used by the reads and sends of this client.
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment
+    }
+
+    /// Fetches an account at the commitment of this client.
+    ///
+    /// # Parameters
+    ///
+    /// - `address`: The address of the account.
+    ///
+    /// # Returns
+    ///
+    /// The account, or an error if it does not exist or the request fails.
+    pub async fn fetch_account(&self, address: &Pubkey) -> Result<Account> {
+        self.get_account(address)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", address))
+    }
+
+    /// Fetches an account at the commitment of this client, if it exists.
+    ///
+    /// Unlike `fetch_account`, a missing account is not an error, so callers can tell it apart
+    /// from a failed request.
+    ///
+    /// # Parameters
+    ///
+    /// - `address`: The address of the account.
+    ///
+    /// # Returns
+    ///
+    /// The account, `None` if it does not exist, or an error if the request fails.
+    pub async fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self
+            .inner()
+            .get_account_with_commitment(address, self.commitment)
+            .await
+            .map_err(with_rate_limit)?
+            .value)
+    }
+
+    /// Fetches the lamport balance of an account at the commitment of this client.
+    ///
+    /// # Parameters
+    ///
+    /// - `address`: The address of the account.
+    ///
+    /// # Returns
+    ///
+    /// The balance in lamports, or an error if the request fails.
+    pub async fn fetch_balance(&self, address: &Pubkey) -> Result<u64> {
+        Ok(self
+            .inner()
+            .get_balance_with_commitment(address, self.commitment)
+            .await
+            .map_err(with_rate_limit)?
+            .value)
+    }
+
+    /// Fetches the current slot at the commitment of this client.
+    ///
+    /// # Returns
+    ///
+    /// The slot, or an error if the request fails.
+    pub async fn fetch_slot(&self) -> Result<u64> {
+        self.inner()
+            .get_slot_with_commitment(self.commitment)
+            .await
+            .map_err(with_rate_limit)
+    }
+
+    /// Fetches the lamports an account of `space` bytes needs to be rent exempt.
+    ///
+    /// # Parameters
+    ///
+    /// - `space`: The size of the account data, in bytes.
+    ///
+    /// # Returns
+    ///
+    /// The minimum balance in lamports, or an error if the request fails.
+    pub async fn fetch_rent_exemption(&self, space: usize) -> Result<u64> {
+        self.inner()
+            .get_minimum_balance_for_rent_exemption(space)
+            .await
+            .map_err(with_rate_limit)
+    }
+
+    /// Fetches the balance of a token account at the commitment of this client.
+    ///
+    /// # Parameters
+    ///
+    /// - `address`: The address of the token account.
+    ///
+    /// # Returns
+    ///
+    /// The token amount, or an error if the request fails.
+    pub async fn fetch_token_account_balance(&self, address: &Pubkey) -> Result<UiTokenAmount> {
+        Ok(self
+            .inner()
+            .get_token_account_balance_with_commitment(address, self.commitment)
+            .await
+            .map_err(with_rate_limit)?
+            .value)
+    }
+
+    /// Fetches the token accounts of an owner at the commitment of this client.
+    ///
+    /// # Parameters
+    ///
+    /// - `owner`: The owner of the token accounts.
+    /// - `filter`: The mint or token program the accounts must match.
+    ///
+    /// # Returns
+    ///
+    /// The matching token accounts, or an error if the request fails.
+    pub async fn fetch_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        filter: TokenAccountsFilter,
+    ) -> Result<Vec<RpcKeyedAccount>> {
+        Ok(self
+            .inner()
+            .get_token_accounts_by_owner_with_commitment(owner, filter, self.commitment)
+            .await
+            .map_err(with_rate_limit)?
+            .value)
+    }
+
+    /// Fetches the prioritization fees paid in recent slots by transactions writing `accounts`.
+    ///
+    /// # Parameters
+    ///
+    /// - `accounts`: The writable accounts to look up; empty for the fees of any transaction.
+    ///
+    /// # Returns
+    ///
+    /// The recent prioritization fees, or an error if the request fails.
+    pub async fn fetch_recent_prioritization_fees(
+        &self,
+        accounts: &[Pubkey],
+    ) -> Result<Vec<RpcPrioritizationFee>> {
+        self.inner()
+            .get_recent_prioritization_fees(accounts)
+            .await
+            .map_err(with_rate_limit)
+    }
+
+    /// Sets the options applied to every transaction sent by this client.
+    ///
+    /// # Parameters
+    ///
+    /// - `send_config`: The `SendConfig` to use, e.g. to let the RPC node rebroadcast transactions.
+    ///
+    /// # Returns
+    ///
+    /// The Rpc wrapper using the given send options.
+    pub fn with_send_config(mut self, send_config: SendConfig) -> Self {
+        self.send_config = send_config;
+        self
+    }
+
+    /// Returns the options applied to every transaction sent by this client.
+    pub fn send_config(&self) -> &SendConfig {
+        &self.send_config
     }
 
     /// Returns a reference to the inner RpcClient instance wrapped by this wrapper.
     pub fn inner(&self) -> &RpcClient {
-        &self.0
+        &self.client
     }
 
     /// Retrieves a transaction with the specified signature.
@@ -115,16 +379,17 @@ impl Rpc {
     pub async fn fetch_transaction(
         &self,
         signature: &Signature,
-    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
         let config = RpcTransactionConfig {
             encoding: Some(UiTransactionEncoding::Base64),
             max_supported_transaction_version: Some(0),
-            commitment: Some(self.inner().commitment()),
+            commitment: Some(self.commitment),
         };
 
         self.inner()
             .get_transaction_with_config(signature, config)
             .await
+            .map_err(with_rate_limit)
     }
 
     /// Retrieves confirmed transaction signatures associated with a specific address.
@@ -167,12 +432,12 @@ impl Rpc {
         pubkey: &Pubkey,
         before: Option<Signature>,
         until: Option<Signature>,
-    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, ClientError> {
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
         (|| async {
             let config = GetConfirmedSignaturesForAddress2Config {
                 before,
                 until,
-                commitment: Some(self.inner().commitment()),
+                commitment: Some(self.commitment),
                 ..GetConfirmedSignaturesForAddress2Config::default()
             };
             self.inner()
@@ -180,7 +445,47 @@ impl Rpc {
                 .await
         })
         .retry(&ExponentialBuilder::default())
+        .when(is_retryable)
         .await
+        .map_err(with_rate_limit)
+    }
+
+    /// Sums the lamport fees (base and priority) paid by an address over its recent
+    /// transactions.
+    ///
+    /// Only transactions the address paid for, i.e. whose first account key it is, are counted.
+    /// The transactions are fetched at most `TRANSACTION_FETCH_CONCURRENCY` at a time.
+    ///
+    /// # Parameters
+    ///
+    /// - `pubkey`: The fee payer whose recent transactions are inspected.
+    /// - `since`: Optional. Only count transactions with a block time at or after this unix
+    ///   timestamp; transactions without a block time are skipped when set.
+    ///
+    /// # Returns
+    ///
+    /// The total fees in lamports, or an error otherwise.
+    pub async fn total_fees_paid(&self, pubkey: &Pubkey, since: Option<i64>) -> Result<u64> {
+        let signatures = self
+            .fetch_signatures_for_address(pubkey, None, None)
+            .await?
+            .into_iter()
+            .filter(|status| match since {
+                Some(since) => status
+                    .block_time
+                    .map_or(false, |block_time| block_time >= since),
+                None => true,
+            })
+            .map(|status| status.signature.parse::<Signature>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let transactions: Vec<_> = stream::iter(signatures.iter())
+            .map(|signature| self.fetch_transaction(signature))
+            .buffer_unordered(TRANSACTION_FETCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(sum_transaction_fees(pubkey, &transactions))
     }
 
     /// Retrieves multiple accounts associated with specified public keys.
@@ -219,10 +524,10 @@ impl Rpc {
     pub async fn fetch_multiple_accounts(
         &self,
         pubkeys: &[Pubkey],
-    ) -> Result<Vec<Option<Account>>, ClientError> {
+    ) -> Result<Vec<Option<Account>>> {
         Ok((|| async {
             let config = RpcAccountInfoConfig {
-                commitment: Some(self.inner().commitment()),
+                commitment: Some(self.commitment),
                 ..RpcAccountInfoConfig::default()
             };
 
@@ -231,16 +536,210 @@ impl Rpc {
                 .await
         })
         .retry(&ExponentialBuilder::default())
-        .await?
+        .when(is_retryable)
+        .await
+        .map_err(with_rate_limit)?
         .value)
     }
 
+    /// Retrieves multiple accounts observed at a single slot.
+    ///
+    /// All accounts are read in a single request, so they share the same context slot. When
+    /// `min_context_slot` is set, responses from nodes lagging behind that slot are retried.
+    ///
+    /// # Parameters
+    ///
+    /// - `pubkeys`: An array of public keys.
+    /// - `min_context_slot`: Optional. The minimum slot the response must be observed at.
+    ///
+    /// # Returns
+    ///
+    /// The slot the accounts were read at and the list of optional accounts, or an error otherwise.
+    pub async fn fetch_multiple_accounts_at_slot(
+        &self,
+        pubkeys: &[Pubkey],
+        min_context_slot: Option<u64>,
+    ) -> Result<(u64, Vec<Option<Account>>)> {
+        (|| async {
+            let config = RpcAccountInfoConfig {
+                commitment: Some(self.commitment),
+                min_context_slot,
+                ..RpcAccountInfoConfig::default()
+            };
+
+            let response = self
+                .inner()
+                .get_multiple_accounts_with_config(pubkeys, config)
+                .await?;
+            ensure_min_context_slot(response.context.slot, min_context_slot)?;
+
+            Ok::<_, ClientError>((response.context.slot, response.value))
+        })
+        .retry(&ExponentialBuilder::default())
+        .when(is_retryable)
+        .await
+        .map_err(with_rate_limit)
+    }
+
+    /// Simulates the given instructions against the latest blockhash.
+    ///
+    /// # Parameters
+    ///
+    /// - `payer`: The fee payer of the simulated transaction.
+    /// - `instructions`: The instructions to simulate.
+    ///
+    /// # Returns
+    ///
+    /// The simulation result, or an error if the simulation could not run or the transaction
+    /// failed.
+    pub async fn simulate(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<RpcSimulateTransactionResult> {
+        let txn = Transaction::new_with_payer(instructions, Some(payer));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.commitment),
+            ..RpcSimulateTransactionConfig::default()
+        };
+
+        let result = self
+            .inner()
+            .simulate_transaction_with_config(&txn, config)
+            .await
+            .map_err(with_rate_limit)?
+            .value;
+        if let Some(err) = result.err {
+            anyhow::bail!("transaction simulation failed: {:?}", err);
+        }
+
+        Ok(result)
+    }
+
+    /// Simulates the given instructions and returns the compute units they consume.
+    ///
+    /// # Parameters
+    ///
+    /// - `payer`: The fee payer of the simulated transaction.
+    /// - `instructions`: The instructions to simulate.
+    ///
+    /// # Returns
+    ///
+    /// The number of compute units consumed by the simulation, or an error if the simulation fails.
+    pub async fn simulate_compute_units(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<u64> {
+        self.simulate(payer, instructions)
+            .await?
+            .units_consumed
+            .ok_or_else(|| anyhow::anyhow!("simulation did not report consumed units"))
+    }
+
+    /// Prepends the compute budget instructions configured in the `SendConfig`.
+    ///
+    /// A `compute_unit_limit` prepends a compute-unit limit instruction. The compute-unit price is
+    /// `priority_fee_micro_lamports` when set; otherwise recent fees are queried with
+    /// `getRecentPrioritizationFees` for the writable accounts of `instructions`, so they reflect
+    /// contention on the market being traded, and the price is the `priority_fee_percentile` of
+    /// those fees. Instructions that already set a compute-unit limit or price keep their own.
+    ///
+    /// # Parameters
+    ///
+    /// - `instructions`: The instructions about to be sent.
+    ///
+    /// # Returns
+    ///
+    /// The instructions with the compute budget instructions prepended, or an error if the
+    /// fees could not be fetched.
+    pub async fn with_priority_fee(
+        &self,
+        mut instructions: Vec<Instruction>,
+    ) -> Result<Vec<Instruction>> {
+        if let Some(limit) = self.send_config.compute_unit_limit {
+            if !instructions.iter().any(is_compute_unit_limit_instruction) {
+                instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(limit));
+            }
+        }
+        if instructions.iter().any(is_compute_unit_price_instruction) {
+            return Ok(instructions);
+        }
+        if let Some(price) = self.send_config.priority_fee_micro_lamports {
+            instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(price));
+            return Ok(instructions);
+        }
+        let percentile = match self.send_config.priority_fee_percentile {
+            Some(percentile) => percentile,
+            None => return Ok(instructions),
+        };
+
+        let mut accounts: Vec<Pubkey> = instructions
+            .iter()
+            .flat_map(|ix| ix.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        accounts.sort();
+        accounts.dedup();
+
+        let fees: Vec<u64> = self
+            .fetch_recent_prioritization_fees(&accounts)
+            .await?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        let price = fee_percentile(&fees, percentile);
+        tracing::debug!("using compute unit price: {:?}", price);
+
+        instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(price));
+        Ok(instructions)
+    }
+
+    /// Retrieves the accounts owned by `program` matching the given filters.
+    ///
+    /// # Parameters
+    ///
+    /// - `program`: The program owning the accounts.
+    /// - `filters`: The `RpcFilterType` filters the accounts must match.
+    ///
+    /// # Returns
+    ///
+    /// The list of matching account addresses and accounts, or an error otherwise.
+    pub async fn fetch_program_accounts(
+        &self,
+        program: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        (|| async {
+            self.inner()
+                .get_program_accounts_with_config(program, config.clone())
+                .await
+        })
+        .retry(&ExponentialBuilder::default())
+        .when(is_retryable)
+        .await
+        .map_err(with_rate_limit)
+    }
+
     #[cfg(feature = "v2")]
     pub async fn fetch_anchor_account<T: AccountDeserialize>(
         &self,
         address: &Pubkey,
     ) -> anyhow::Result<T> {
-        let account = self.inner().get_account(address).await?;
+        let account = self.fetch_account(address).await?;
         Ok(T::try_deserialize(&mut (&account.data as &[u8]))?)
     }
 
@@ -266,7 +765,8 @@ impl Rpc {
         };
         self.inner()
             .get_program_accounts_with_config(&program, config)
-            .await?
+            .await
+            .map_err(with_rate_limit)?
             .into_iter()
             .map(|(key, account)| {
                 Ok((
@@ -294,49 +794,180 @@ impl Rpc {
         };
         self.inner()
             .get_program_accounts_with_config(&program, config)
-            .await?
+            .await
+            .map_err(with_rate_limit)?
             .into_iter()
             .map(|(key, account)| Ok((key, T::try_deserialize(&mut (&account.data as &[u8]))?)))
             .collect()
     }
 
+    /// Fetches the latest blockhash and stores it in the blockhash cache.
+    ///
+    /// # Returns
+    ///
+    /// The fetched blockhash, or an error if the request fails.
+    pub async fn refresh_blockhash(&self) -> Result<Hash> {
+        let blockhash = self
+            .inner()
+            .get_latest_blockhash_with_commitment(self.commitment)
+            .await
+            .map_err(with_rate_limit)?
+            .0;
+        *self.blockhash_cache.lock().await = Some((Instant::now(), blockhash));
+
+        Ok(blockhash)
+    }
+
+    /// Returns the blockhash new transactions are signed against.
+    ///
+    /// The cached blockhash is reused while it is younger than the `blockhash_ttl` of the
+    /// `SendConfig`; a stale or missing one is refreshed first.
+    ///
+    /// # Returns
+    ///
+    /// The blockhash, or an error if it had to be fetched and the request failed.
+    pub async fn latest_blockhash(&self) -> Result<Hash> {
+        if let Some(ttl) = self.send_config.blockhash_ttl {
+            if let Some((fetched_at, blockhash)) = *self.blockhash_cache.lock().await {
+                if fetched_at.elapsed() < ttl {
+                    return Ok(blockhash);
+                }
+            }
+        }
+
+        self.refresh_blockhash().await
+    }
+
+    /// Builds a transaction for the instructions, signed against the latest blockhash.
+    ///
+    /// # Parameters
+    ///
+    /// - `signers`: The keypairs signing the transaction; the first one pays the fees.
+    /// - `instructions`: The instructions of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// The signed transaction, or an error if `signers` is empty or the blockhash could not be
+    /// fetched.
+    pub async fn sign_transaction(
+        &self,
+        signers: &[&Keypair],
+        instructions: &[Instruction],
+    ) -> Result<Transaction> {
+        let payer = signers
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("a transaction needs at least one signer"))?;
+        let recent_hash = self.latest_blockhash().await?;
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            signers,
+            recent_hash,
+        ))
+    }
+
+    /// Builds an unsigned transaction for the instructions against the latest blockhash.
+    ///
+    /// The transaction can be signed and sent by an external signer, such as a hardware wallet.
+    ///
+    /// # Parameters
+    ///
+    /// - `payer`: The account paying the fees, which must sign the transaction.
+    /// - `instructions`: The instructions of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// The unsigned transaction, or an error if the blockhash could not be fetched.
+    pub async fn unsigned_transaction(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<Transaction> {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(payer));
+        transaction.message.recent_blockhash = self.latest_blockhash().await?;
+        Ok(transaction)
+    }
+
     pub async fn send_and_confirm(
         &self,
         owner: Keypair,
         instructions: Vec<Instruction>,
     ) -> anyhow::Result<(bool, Signature)> {
+        self.send_and_confirm_with_signers(&[&owner], instructions)
+            .await
+    }
+
+    /// Sends a transaction signed by several keypairs like [`Rpc::send_and_confirm`], e.g. one
+    /// creating an account whose keypair must sign too.
+    ///
+    /// # Parameters
+    ///
+    /// - `signers`: The keypairs signing the transaction; the first one pays the fees.
+    /// - `instructions`: The instructions to send.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed and its signature, the default signature if sending
+    /// failed.
+    pub async fn send_and_confirm_with_signers(
+        &self,
+        signers: &[&Keypair],
+        instructions: Vec<Instruction>,
+    ) -> anyhow::Result<(bool, Signature)> {
+        let (confirmed, signature, _) = self
+            .send_and_confirm_with_units(signers, instructions)
+            .await?;
+        Ok((confirmed, signature))
+    }
+
+    /// Sends a transaction like [`Rpc::send_and_confirm_with_signers`] and reports the compute
+    /// units it used. Every transaction sent by `Rpc`, `OBClient` and `OpenOrders` goes through
+    /// this method.
+    ///
+    /// When `SendConfig::compute_unit_limit` is set, the confirmed transaction is fetched again
+    /// to read `compute_units_consumed` from its status meta, so callers can tune their limit.
+    /// The value is also kept for [`Rpc::last_compute_units`].
+    ///
+    /// # Parameters
+    ///
+    /// - `signers`: The keypairs signing the transaction; the first one pays the fees.
+    /// - `instructions`: The instructions to send.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, its signature, and the compute units it consumed
+    /// when a limit is set and the confirmed transaction could be fetched.
+    pub async fn send_and_confirm_with_units(
+        &self,
+        signers: &[&Keypair],
+        instructions: Vec<Instruction>,
+    ) -> anyhow::Result<(bool, Signature, Option<u64>)> {
         let confirmed;
         let mut sig = Signature::default();
-        let recent_hash = self
-            .inner()
-            .get_latest_blockhash_with_commitment(self.inner().commitment())
-            .await?
-            .0;
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&owner.pubkey()),
-            &[&owner],
-            recent_hash,
-        );
+        let instructions = self.with_priority_fee(instructions).await?;
+        let txn = self.sign_transaction(signers, &instructions).await?;
 
-        match self
+        let result = self
             .inner()
             .send_transaction_with_config(
                 &txn,
-                RpcSendTransactionConfig {
-                    skip_preflight: false,
-                    max_retries: None,
-                    preflight_commitment: Some(self.inner().commitment().commitment),
-                    encoding: None,
-                    min_context_slot: None,
-                },
+                self.send_config.rpc_send_config(self.commitment.commitment),
             )
-            .await
-        {
-            Ok(signature) => {
-                match (|| async { self.inner().confirm_transaction(&signature).await })
-                    .retry(&ExponentialBuilder::default())
-                    .await
+            .await;
+        match (result, self.send_config.confirm_timeout) {
+            (Ok(signature), Some(timeout)) => {
+                self.wait_for_confirmation(&signature, timeout).await?;
+                confirmed = true;
+                sig = signature;
+            }
+            (Ok(signature), None) => {
+                match (|| async {
+                    self.inner()
+                        .confirm_transaction_with_commitment(&signature, self.commitment)
+                        .await
+                })
+                .retry(&ExponentialBuilder::default())
+                .await
                 {
                     Ok(_ret) => {
                         // Hack: We have received a signature. We assume it is confirmed due to the Solana network/Crank delay to get confirmation.
@@ -385,7 +1016,10 @@ impl Rpc {
                     }
                 }
             }
-            Err(err) => {
+            (Err(err), _) => {
+                if let Some(rate_limited) = rate_limit_error(&err) {
+                    return Err(rate_limited.into());
+                }
                 match err.kind() {
                     ErrorKind::Reqwest(reqwest_error) => {
                         if reqwest_error.is_timeout() {
@@ -426,7 +1060,90 @@ impl Rpc {
             }
         };
 
-        Ok((confirmed, sig))
+        if !confirmed || self.send_config.compute_unit_limit.is_none() {
+            return Ok((confirmed, sig, None));
+        }
+        let units = match self.fetch_transaction(&sig).await {
+            Ok(transaction) => compute_units_consumed(&transaction),
+            Err(err) => {
+                tracing::debug!("could not fetch {} for its compute units: {}", sig, err);
+                None
+            }
+        };
+        *self.last_compute_units.lock().await = units;
+
+        Ok((confirmed, sig, units))
+    }
+
+    /// Returns the compute units consumed by the last confirmed send of this client, or of any
+    /// of its clones.
+    ///
+    /// Only recorded while `SendConfig::compute_unit_limit` is set.
+    ///
+    /// # Returns
+    ///
+    /// The consumed compute units, or `None` if no send recorded them.
+    pub async fn last_compute_units(&self) -> Option<u64> {
+        *self.last_compute_units.lock().await
+    }
+
+    /// Polls the status of a transaction until it reaches the commitment of this client.
+    ///
+    /// # Parameters
+    ///
+    /// - `signature`: The signature of the transaction.
+    /// - `timeout`: How long to poll before giving up.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` once the transaction is confirmed, `OpenBookError::ConfirmationTimeout` if it is
+    /// still unconfirmed when the timeout elapses, or an error if it failed on chain.
+    pub async fn wait_for_confirmation(
+        &self,
+        signature: &Signature,
+        timeout: Duration,
+    ) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            let status = self
+                .inner()
+                .get_signature_status_with_commitment(signature, self.commitment)
+                .await
+                .map_err(with_rate_limit)?;
+            match status {
+                Some(Ok(())) => return Ok(()),
+                Some(Err(err)) => {
+                    anyhow::bail!("transaction {} failed: {}", signature, err)
+                }
+                None if started.elapsed() >= timeout => {
+                    return Err(OpenBookError::ConfirmationTimeout(*signature).into())
+                }
+                None => tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await,
+            }
+        }
+    }
+}
+
+/// Checks that a response was observed at or after the requested minimum context slot.
+///
+/// # Parameters
+///
+/// - `slot`: The context slot of the response.
+/// - `min_context_slot`: Optional. The minimum slot the response must be observed at.
+///
+/// # Returns
+///
+/// `Ok(())` if the response is recent enough, or a `ClientError` describing the stale slot.
+pub fn ensure_min_context_slot(
+    slot: u64,
+    min_context_slot: Option<u64>,
+) -> Result<(), ClientError> {
+    match min_context_slot {
+        Some(min_slot) if slot < min_slot => Err(ErrorKind::Custom(format!(
+            "stale response at slot {slot}, expected at least {min_slot}"
+        ))
+        .into()),
+        _ => Ok(()),
     }
 }
 
@@ -438,7 +1155,172 @@ impl fmt::Debug for Rpc {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Include relevant information about RpcClient
         f.debug_struct("RpcClient")
-            .field("commitment", &self.inner().commitment())
+            .field("commitment", &self.commitment)
+            .field("send_config", &self.send_config)
             .finish()
     }
 }
+
+/// Sums the fees recorded in the status meta of the confirmed transactions paid by `payer`.
+///
+/// # Parameters
+///
+/// - `payer`: The fee payer; transactions whose first account key differs are skipped.
+/// - `transactions`: The confirmed transactions; those without a status meta or that cannot be
+///   decoded count as zero.
+///
+/// # Returns
+///
+/// The total fees in lamports.
+pub fn sum_transaction_fees(
+    payer: &Pubkey,
+    transactions: &[EncodedConfirmedTransactionWithStatusMeta],
+) -> u64 {
+    transactions
+        .iter()
+        .filter(|transaction| {
+            transaction
+                .transaction
+                .transaction
+                .decode()
+                .map_or(false, |decoded| {
+                    decoded.message.static_account_keys().first() == Some(payer)
+                })
+        })
+        .filter_map(|transaction| transaction.transaction.meta.as_ref())
+        .map(|meta| meta.fee)
+        .sum()
+}
+
+/// Reads the compute units a confirmed transaction consumed from its status meta.
+///
+/// # Parameters
+///
+/// - `transaction`: The confirmed transaction.
+///
+/// # Returns
+///
+/// The consumed compute units, or `None` if the node did not record them.
+pub fn compute_units_consumed(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<u64> {
+    match transaction
+        .transaction
+        .meta
+        .as_ref()?
+        .compute_units_consumed
+    {
+        OptionSerializer::Some(units) => Some(units),
+        _ => None,
+    }
+}
+
+/// Picks the given percentile of prioritization fees using the nearest-rank method.
+///
+/// # Parameters
+///
+/// - `fees`: The recent prioritization fees, in micro-lamports per compute unit.
+/// - `percentile`: The percentile to pick, clamped to 100.
+///
+/// # Returns
+///
+/// The fee at that percentile, or zero when `fees` is empty.
+pub fn fee_percentile(fees: &[u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    let mut sorted = fees.to_vec();
+    sorted.sort_unstable();
+    let percentile = percentile.min(100) as usize;
+    let rank = (percentile * sorted.len() + 99) / 100;
+    sorted[rank.saturating_sub(1)]
+}
+
+/// Detects RPC rate limiting (HTTP 429) in a `ClientError`.
+///
+/// # Parameters
+///
+/// - `err`: The error returned by the RPC client.
+///
+/// # Returns
+///
+/// `Some(OpenBookError::RateLimited)` with the retry-after delay advertised by the node, if any,
+/// or `None` if the error is not a rate limit.
+pub fn rate_limit_error(err: &ClientError) -> Option<OpenBookError> {
+    let message = match err.kind() {
+        ErrorKind::Reqwest(reqwest_error) => {
+            if reqwest_error.status().map(|status| status.as_u16()) != Some(429) {
+                return None;
+            }
+            reqwest_error.to_string()
+        }
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. })
+            if *code == 429 || is_rate_limit_message(message) =>
+        {
+            message.clone()
+        }
+        ErrorKind::RpcError(RpcError::RpcRequestError(message))
+        | ErrorKind::RpcError(RpcError::ForUser(message))
+        | ErrorKind::Custom(message)
+            if is_rate_limit_message(message) =>
+        {
+            message.clone()
+        }
+        _ => return None,
+    };
+
+    Some(OpenBookError::RateLimited {
+        retry_after: parse_retry_after(&message),
+    })
+}
+
+/// Tells whether a failed request is worth retrying; rate limits are surfaced to the caller with
+/// their retry-after delay instead of being retried right away.
+fn is_retryable(err: &ClientError) -> bool {
+    rate_limit_error(err).is_none()
+}
+
+/// Converts a `ClientError` into an `anyhow::Error`, surfacing rate limits as
+/// `OpenBookError::RateLimited`.
+pub fn with_rate_limit(err: ClientError) -> anyhow::Error {
+    match rate_limit_error(&err) {
+        Some(rate_limited) => rate_limited.into(),
+        None => err.into(),
+    }
+}
+
+fn is_rate_limit_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("429")
+        || message.contains("too many requests")
+        || message.contains("rate limit")
+}
+
+/// Parses a retry-after delay such as `retry after 2s`, `Retry-After: 3` or `retry after 500ms`.
+///
+/// # Parameters
+///
+/// - `message`: The error message returned by the node.
+///
+/// # Returns
+///
+/// The advertised delay, seconds being assumed when no unit is given, or `None` if absent.
+pub fn parse_retry_after(message: &str) -> Option<Duration> {
+    let message = message.to_lowercase();
+    let start = ["retry-after", "retry after"]
+        .iter()
+        .find_map(|needle| message.find(needle).map(|idx| idx + needle.len()))?;
+
+    let rest = message[start..].trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+    let digits_len = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let value: u64 = rest[..digits_len].parse().ok()?;
+
+    if rest[digits_len..].trim_start().starts_with("ms") {
+        Some(Duration::from_millis(value))
+    } else {
+        Some(Duration::from_secs(value))
+    }
+}
@@ -265,7 +265,6 @@ async fn run_app<B: Backend>(
                                         commitment_config,
                                         market_id,
                                         true,
-                                        123456789,
                                     )
                                     .await?;
                                     ob_client.rpc_client = Rpc::new(rpc_client);
@@ -349,11 +348,11 @@ async fn run_app<B: Backend>(
                                     );
                                     app.wallet_info.insert(
                                         "Base ATA".to_string(),
-                                        ob_client.base_ata.to_string(),
+                                        ob_client.base_ata().to_string(),
                                     );
                                     app.wallet_info.insert(
                                         "Quote ATA".to_string(),
-                                        ob_client.quote_ata.to_string(),
+                                        ob_client.quote_ata().to_string(),
                                     );
                                     app.wallet_info.insert(
                                         "Open Order Account".to_string(),
@@ -470,11 +469,11 @@ async fn run_app<B: Backend>(
                                     );
                                     app.wallet_info.insert(
                                         "Base ATA".to_string(),
-                                        ob_client.base_ata.to_string(),
+                                        ob_client.base_ata().to_string(),
                                     );
                                     app.wallet_info.insert(
                                         "Quote ATA".to_string(),
-                                        ob_client.quote_ata.to_string(),
+                                        ob_client.quote_ata().to_string(),
                                     );
                                     app.wallet_info.insert(
                                         "Index Account".to_string(),
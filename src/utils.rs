@@ -1,7 +1,16 @@
 //! This module contains utility functions related openbook.
 
 use crate::{bs58, keypair::Keypair};
-use solana_sdk::{account::Account, account_info::AccountInfo, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+    message::Message,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use std::{fs, time::SystemTime, time::UNIX_EPOCH};
 
 /// Converts a slice of `u64` values into a fixed-size byte array.
@@ -31,6 +40,53 @@ pub fn u64_slice_to_pubkey(array: [u64; 4]) -> [u8; 32] {
     result
 }
 
+/// Derives the websocket endpoint of an RPC node from its HTTP endpoint.
+///
+/// Like the Solana CLI, an explicit port is incremented: a validator serves pubsub on the port
+/// after its RPC port, e.g. 8900 for a local RPC on 8899.
+///
+/// # Arguments
+///
+/// * `rpc_url` - The HTTP(S) URL of the RPC node.
+///
+/// # Returns
+///
+/// The matching WS(S) URL.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::ws_url_from_rpc_url;
+///
+/// assert_eq!(
+///     ws_url_from_rpc_url("https://api.mainnet-beta.solana.com"),
+///     "wss://api.mainnet-beta.solana.com"
+/// );
+/// assert_eq!(
+///     ws_url_from_rpc_url("http://127.0.0.1:8899"),
+///     "ws://127.0.0.1:8900"
+/// );
+/// ```
+#[cfg(feature = "ws")]
+pub fn ws_url_from_rpc_url(rpc_url: &str) -> String {
+    let (scheme, rest) = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        ("wss://", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        ("ws://", rest)
+    } else {
+        return rpc_url.to_string();
+    };
+
+    let (authority, path) = rest.split_at(rest.find(['/', '?']).unwrap_or(rest.len()));
+    match authority
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?.checked_add(1)?)))
+    {
+        Some((host, port)) => format!("{}{}:{}{}", scheme, host, port, path),
+        None => format!("{}{}", scheme, rest),
+    }
+}
+
 /// Reads a keypair from a file.
 ///
 /// # Arguments
@@ -86,6 +142,26 @@ pub fn get_unix_secs() -> u64 {
         .as_secs()
 }
 
+/// Gets the current UNIX timestamp in milliseconds.
+///
+/// # Returns
+///
+/// The current UNIX timestamp in milliseconds.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::get_unix_millis;
+///
+/// let timestamp = get_unix_millis();
+/// ```
+pub fn get_unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+}
+
 /// Creates an `AccountInfo` instance from an `Account`.
 ///
 /// # Arguments
@@ -152,3 +228,127 @@ pub fn create_account_info_from_account<'a>(
         account.rent_epoch,
     )
 }
+
+/// Builds an instruction creating the associated token account of `wallet` for `mint`.
+///
+/// The idempotent variant is used so that concurrent creations of the same account (e.g.
+/// several strategy instances starting at once) do not fail when the account already exists.
+///
+/// # Arguments
+///
+/// * `payer` - The account paying for the rent of the new token account.
+/// * `wallet` - The owner of the associated token account.
+/// * `mint` - The mint of the associated token account.
+/// * `token_program_id` - The token program owning the mint.
+///
+/// # Returns
+///
+/// The `Instruction` creating the associated token account if it does not exist yet.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::utils::create_associated_token_account_instruction;
+///
+/// let wallet = Pubkey::new_unique();
+/// let mint = Pubkey::new_unique();
+/// let token_program_id = Pubkey::new_unique();
+///
+/// let ix = create_associated_token_account_instruction(&wallet, &wallet, &mint, &token_program_id);
+/// ```
+pub fn create_associated_token_account_instruction(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    create_associated_token_account_idempotent(payer, wallet, mint, token_program_id)
+}
+
+/// Returns `true` if `ix` is a compute budget `SetComputeUnitLimit` instruction.
+pub fn is_compute_unit_limit_instruction(ix: &Instruction) -> bool {
+    // `SetComputeUnitLimit` is the third variant of the borsh-encoded `ComputeBudgetInstruction`.
+    ix.program_id == compute_budget::id() && ix.data.first() == Some(&2)
+}
+
+/// Returns `true` if `ix` is a compute budget `SetComputeUnitPrice` instruction.
+pub fn is_compute_unit_price_instruction(ix: &Instruction) -> bool {
+    // `SetComputeUnitPrice` is the fourth variant of the borsh-encoded `ComputeBudgetInstruction`.
+    ix.program_id == compute_budget::id() && ix.data.first() == Some(&3)
+}
+
+/// Returns the serialized size in bytes of a transaction holding `instructions`.
+///
+/// The compute-unit limit and price instructions that `Rpc::with_priority_fee` may prepend when
+/// sending are counted unless `instructions` already hold them, so a transaction sized here
+/// still fits once they are added.
+///
+/// # Arguments
+///
+/// * `instructions` - The instructions of the transaction.
+/// * `payer` - The fee payer of the transaction.
+///
+/// # Returns
+///
+/// The size of the signed transaction, including its signatures.
+pub fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let mut sized = Vec::with_capacity(instructions.len() + 2);
+    // The encoded size of both instructions does not depend on their values.
+    if !instructions.iter().any(is_compute_unit_limit_instruction) {
+        sized.push(ComputeBudgetInstruction::set_compute_unit_limit(0));
+    }
+    if !instructions.iter().any(is_compute_unit_price_instruction) {
+        sized.push(ComputeBudgetInstruction::set_compute_unit_price(0));
+    }
+    sized.extend_from_slice(instructions);
+
+    let message = Message::new(&sized, Some(payer));
+    // compact-u16 signature count followed by one 64 bytes signature per required signer.
+    1 + 64 * message.header.num_required_signatures as usize + message.serialize().len()
+}
+
+/// Splits instructions into the minimum number of consecutive transactions that fit the packet
+/// size limit, as measured by `transaction_size`, preserving their order.
+///
+/// An instruction too large to fit any transaction is kept alone in its own group.
+///
+/// # Arguments
+///
+/// * `instructions` - The instructions to group.
+/// * `payer` - The fee payer of the transactions.
+///
+/// # Returns
+///
+/// The instruction groups, one per transaction.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::utils::split_into_transactions;
+///
+/// let payer = Pubkey::new_unique();
+/// let groups = split_into_transactions(vec![], &payer);
+/// assert!(groups.is_empty());
+/// ```
+pub fn split_into_transactions(
+    instructions: Vec<Instruction>,
+    payer: &Pubkey,
+) -> Vec<Vec<Instruction>> {
+    let mut groups: Vec<Vec<Instruction>> = Vec::new();
+    let mut current: Vec<Instruction> = Vec::new();
+
+    for ix in instructions {
+        current.push(ix);
+        if current.len() > 1 && transaction_size(&current, payer) > PACKET_DATA_SIZE {
+            let ix = current.pop().unwrap();
+            groups.push(std::mem::replace(&mut current, vec![ix]));
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    groups
+}
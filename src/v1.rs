@@ -1,4 +1,7 @@
+pub mod events;
+pub mod geyser;
 pub mod market;
 pub mod ob_client;
 pub mod orders;
+pub mod position;
 pub mod traits;
@@ -0,0 +1,357 @@
+//! This module contains structs and functions related to the openbook market event queue.
+
+use crate::v1::market::Market;
+use anyhow::Result;
+use openbook_dex::matching::Side;
+use solana_sdk::pubkey::Pubkey;
+
+/// Length of the `serum` padding at the start of every dex account.
+pub const ACCOUNT_HEAD_PADDING: usize = 5;
+
+/// Length of the `padding` bytes at the end of every dex account.
+pub const ACCOUNT_TAIL_PADDING: usize = 7;
+
+/// Length of the event queue header (account flags, head, count and sequence number).
+pub const EVENT_QUEUE_HEADER_LEN: usize = 32;
+
+/// Length of a single encoded event.
+pub const EVENT_LEN: usize = 88;
+
+/// Event flag set on fill events.
+pub const EVENT_FLAG_FILL: u8 = 0x1;
+
+/// Event flag set on out events.
+pub const EVENT_FLAG_OUT: u8 = 0x2;
+
+/// Event flag set when the event belongs to the bid side.
+pub const EVENT_FLAG_BID: u8 = 0x4;
+
+/// Event flag set when the event belongs to the maker of a trade.
+pub const EVENT_FLAG_MAKER: u8 = 0x8;
+
+/// Header of the event queue account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventQueueHeader {
+    /// The account flags of the event queue.
+    pub account_flags: u64,
+    /// Index of the first unconsumed event in the ring buffer.
+    pub head: u64,
+    /// Number of unconsumed events in the queue.
+    pub count: u64,
+    /// Sequence number that will be assigned to the next pushed event.
+    pub seq_num: u64,
+}
+
+/// A single event read from the event queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Event {
+    /// Sequence number of the event.
+    pub seq_num: u64,
+    /// Raw event flags (fill, out, bid, maker, release funds).
+    pub event_flags: u8,
+    /// Slot of the order inside the owner's open orders account.
+    pub owner_slot: u8,
+    /// Fee tier of the owner.
+    pub fee_tier: u8,
+    /// Native quantity released to the owner.
+    pub native_qty_released: u64,
+    /// Native quantity paid by the owner.
+    pub native_qty_paid: u64,
+    /// Native fee paid or rebate received.
+    pub native_fee_or_rebate: u64,
+    /// The order id the event refers to.
+    pub order_id: u128,
+    /// The open orders account the event refers to.
+    pub owner: Pubkey,
+    /// The client order id of the order.
+    pub client_order_id: u64,
+}
+
+impl Event {
+    /// Decodes an event from its 88 bytes representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `seq_num` - The sequence number of the event in the queue.
+    /// * `data` - The raw event, of at least `EVENT_LEN` bytes.
+    ///
+    /// # Returns
+    ///
+    /// The decoded `Event`, or an error if the data is shorter than `EVENT_LEN`.
+    pub fn parse(seq_num: u64, data: &[u8]) -> Result<Self> {
+        if data.len() < EVENT_LEN {
+            anyhow::bail!("event is too small: {} bytes", data.len());
+        }
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let mut owner = [0u8; 32];
+        owner.copy_from_slice(&data[48..80]);
+
+        Ok(Self {
+            seq_num,
+            event_flags: data[0],
+            owner_slot: data[1],
+            fee_tier: data[2],
+            native_qty_released: u64_at(8),
+            native_qty_paid: u64_at(16),
+            native_fee_or_rebate: u64_at(24),
+            order_id: u128::from_le_bytes(data[32..48].try_into().unwrap()),
+            owner: Pubkey::new_from_array(owner),
+            client_order_id: u64_at(80),
+        })
+    }
+
+    /// Returns `true` if the event is a fill.
+    pub fn is_fill(&self) -> bool {
+        self.event_flags & EVENT_FLAG_FILL != 0
+    }
+
+    /// Returns `true` if the event is an out event.
+    pub fn is_out(&self) -> bool {
+        self.event_flags & EVENT_FLAG_OUT != 0
+    }
+
+    /// Returns `true` if the event belongs to the bid side.
+    pub fn is_bid(&self) -> bool {
+        self.event_flags & EVENT_FLAG_BID != 0
+    }
+
+    /// Returns `true` if the event belongs to the maker side of a trade.
+    pub fn is_maker(&self) -> bool {
+        self.event_flags & EVENT_FLAG_MAKER != 0
+    }
+
+    /// Returns the trade described by a maker fill event, or `None` for any other event.
+    ///
+    /// Only the maker side is used so that each trade is counted once.
+    pub fn trade(&self) -> Option<Trade> {
+        if !self.is_fill() || !self.is_maker() {
+            return None;
+        }
+
+        let (base_native, quote_native) = if self.is_bid() {
+            (self.native_qty_released, self.native_qty_paid)
+        } else {
+            (self.native_qty_paid, self.native_qty_released)
+        };
+
+        Some(Trade {
+            seq_num: self.seq_num,
+            maker_is_bid: self.is_bid(),
+            price_lots: (self.order_id >> 64) as u64,
+            base_native,
+            quote_native,
+        })
+    }
+
+    /// Returns the fill described by a fill event in UI units, or `None` for any other event.
+    ///
+    /// A maker fill executes at the maker's limit price. A taker fill may sweep several levels,
+    /// so its price is the average of the exchanged quantities, before the taker fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market of the event queue, used to convert native amounts to UI units.
+    pub fn fill(&self, market: &Market) -> Option<FillEvent> {
+        if !self.is_fill() {
+            return None;
+        }
+
+        let (base_native, quote_native) = match (self.is_bid(), self.is_maker()) {
+            (true, true) => (self.native_qty_released, self.native_qty_paid),
+            (false, true) => (self.native_qty_paid, self.native_qty_released),
+            // The taker fee is included in the quote paid by a bid and deducted from the quote
+            // released to an ask.
+            (true, false) => (
+                self.native_qty_released,
+                self.native_qty_paid
+                    .saturating_sub(self.native_fee_or_rebate),
+            ),
+            (false, false) => (
+                self.native_qty_paid,
+                self.native_qty_released + self.native_fee_or_rebate,
+            ),
+        };
+        let size = base_native as f64 / 10f64.powi(market.coin_decimals as i32);
+        let price = if self.is_maker() {
+            market.price_lots_to_number((self.order_id >> 64) as u64)
+        } else if base_native > 0 {
+            quote_native as f64 / 10f64.powi(market.pc_decimals as i32) / size
+        } else {
+            0.0
+        };
+
+        Some(FillEvent {
+            side: if self.is_bid() { Side::Bid } else { Side::Ask },
+            price,
+            size,
+            client_order_id: self.client_order_id,
+            maker: self.is_maker(),
+        })
+    }
+}
+
+/// A fill of an order read from the event queue, in UI units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillEvent {
+    /// The side of the filled order.
+    pub side: Side,
+    /// The price the fill executed at.
+    pub price: f64,
+    /// The base quantity filled.
+    pub size: f64,
+    /// The client order id of the filled order.
+    pub client_order_id: u64,
+    /// Whether the filled order was the resting (maker) side of the trade.
+    pub maker: bool,
+}
+
+/// A trade read from a maker fill event of the event queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Trade {
+    /// Sequence number of the fill event.
+    pub seq_num: u64,
+    /// Whether the resting (maker) order was a bid.
+    pub maker_is_bid: bool,
+    /// The maker's limit price in lots, at which the trade executed.
+    pub price_lots: u64,
+    /// Native base quantity exchanged.
+    pub base_native: u64,
+    /// Native quote quantity exchanged, before fees.
+    pub quote_native: u64,
+}
+
+/// What order placement does when the market's event queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventQueueFullPolicy {
+    /// Send the order anyway; it fails on chain if it needs to push an event.
+    #[default]
+    Ignore,
+    /// Refuse to send the order with `OpenBookError::EventQueueFull`.
+    Error,
+    /// Crank the head of the queue, without settling, before sending the order.
+    Crank,
+}
+
+/// Decoded content of an event queue account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventQueue {
+    /// The event queue header.
+    pub header: EventQueueHeader,
+    /// The capacity of the ring buffer.
+    pub capacity: usize,
+    /// The unconsumed events, ordered from the head of the queue.
+    pub events: Vec<Event>,
+}
+
+impl EventQueue {
+    /// Decodes the raw data of an event queue account.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The raw account data, including the `serum` and `padding` markers.
+    ///
+    /// # Returns
+    ///
+    /// The decoded `EventQueue`, or an error if the data is too short.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let min_len = ACCOUNT_HEAD_PADDING + EVENT_QUEUE_HEADER_LEN + ACCOUNT_TAIL_PADDING;
+        if data.len() < min_len {
+            anyhow::bail!("event queue account is too small: {} bytes", data.len());
+        }
+        let data = &data[ACCOUNT_HEAD_PADDING..data.len() - ACCOUNT_TAIL_PADDING];
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        let header = EventQueueHeader {
+            account_flags: u64_at(0),
+            head: u64_at(8),
+            count: u64_at(16),
+            seq_num: u64_at(24),
+        };
+
+        let buf = &data[EVENT_QUEUE_HEADER_LEN..];
+        let capacity = buf.len() / EVENT_LEN;
+        if capacity == 0 || header.count as usize > capacity {
+            anyhow::bail!(
+                "invalid event queue: count {} with capacity {}",
+                header.count,
+                capacity
+            );
+        }
+
+        let first_seq = header.seq_num.wrapping_sub(header.count);
+        let events = (0..header.count as usize)
+            .map(|i| {
+                let idx = (header.head as usize + i) % capacity;
+                Event::parse(
+                    first_seq.wrapping_add(i as u64),
+                    &buf[idx * EVENT_LEN..(idx + 1) * EVENT_LEN],
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            header,
+            capacity,
+            events,
+        })
+    }
+
+    /// Returns `true` if every slot of the ring buffer holds an unconsumed event, in which case
+    /// new orders cannot match or be cancelled until the queue is cranked.
+    pub fn is_full(&self) -> bool {
+        self.header.count as usize >= self.capacity
+    }
+
+    /// Returns the sequence number of the first unconsumed event.
+    pub fn head_seq_num(&self) -> u64 {
+        self.header.seq_num.wrapping_sub(self.header.count)
+    }
+
+    /// Returns the unconsumed events whose sequence number is at or after `start_seq`.
+    pub fn events_from(&self, start_seq: u64) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(move |e| e.seq_num >= start_seq)
+    }
+
+    /// Returns the open orders accounts referenced by the first `limit` unconsumed events.
+    ///
+    /// The accounts are deduplicated and sorted by their key read as four little endian u64
+    /// words, the order in which the program binary searches them when consuming events.
+    pub fn crank_accounts(&self, limit: u16) -> Vec<Pubkey> {
+        let mut accounts: Vec<Pubkey> = self
+            .events
+            .iter()
+            .take(limit as usize)
+            .map(|event| event.owner)
+            .collect();
+        accounts.sort_by_key(|key| {
+            let bytes = key.to_bytes();
+            let words: [u64; 4] = std::array::from_fn(|i| {
+                u64::from_le_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap())
+            });
+            words
+        });
+        accounts.dedup();
+        accounts
+    }
+
+    /// Returns the trades of the unconsumed fill events, oldest first.
+    pub fn recent_trades(&self) -> Vec<Trade> {
+        self.events.iter().filter_map(Event::trade).collect()
+    }
+
+    /// Returns the unconsumed fills of the open orders account `owner`, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The open orders account whose fills are returned.
+    /// * `market` - The market of the queue, used to convert native amounts to UI units.
+    pub fn fills(&self, owner: &Pubkey, market: &Market) -> Vec<FillEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.owner == *owner)
+            .filter_map(|event| event.fill(market))
+            .collect()
+    }
+}
@@ -0,0 +1,134 @@
+//! This module contains an adapter turning raw market account updates, e.g. from a Geyser plugin,
+//! into typed market updates without any RPC round trip.
+
+use crate::{
+    utils::create_account_info_from_account,
+    v1::{
+        events::{Event, EventQueue},
+        market::Market,
+        orders::{parse_slab_orders, Order},
+    },
+};
+use anyhow::Result;
+use openbook_dex::{matching::Side, state::MarketState};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+/// A typed update decoded from a raw market account update.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketUpdate {
+    /// The market account changed.
+    Market {
+        /// The slot of the update.
+        slot: u64,
+        /// The total base deposited in the market vault, in native units.
+        coin_deposits_total: u64,
+        /// The total quote deposited in the market vault, in native units.
+        pc_deposits_total: u64,
+        /// The base fees accrued by the market, in native units.
+        coin_fees_accrued: u64,
+        /// The quote fees accrued by the market, in native units.
+        pc_fees_accrued: u64,
+    },
+    /// One side of the book changed.
+    Book {
+        /// The slot of the update.
+        slot: u64,
+        /// The side of the book that changed.
+        side: Side,
+        /// The full ladder of that side, best price first.
+        orders: Vec<Order>,
+    },
+    /// The event queue changed.
+    Events {
+        /// The slot of the update.
+        slot: u64,
+        /// The unconsumed events, ordered from the head of the queue.
+        events: Vec<Event>,
+    },
+}
+
+/// Routes raw account updates of a market to the matching parser.
+#[derive(Debug, Clone)]
+pub struct GeyserAdapter {
+    /// The market whose accounts are decoded.
+    pub market: Market,
+}
+
+impl GeyserAdapter {
+    /// Creates an adapter for the accounts of `market`.
+    pub fn new(market: Market) -> Self {
+        Self { market }
+    }
+
+    /// Decodes a raw account update.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkey` - The address of the updated account.
+    /// * `slot` - The slot of the update.
+    /// * `data` - The raw account data.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the typed update, or `None` if the account does not belong to the
+    /// market.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded as the expected account.
+    pub fn on_account_update(
+        &self,
+        pubkey: &Pubkey,
+        slot: u64,
+        data: &[u8],
+    ) -> Result<Option<MarketUpdate>> {
+        let update = if *pubkey == self.market.market_address {
+            self.parse_market(slot, data)?
+        } else if *pubkey == self.market.bids_address {
+            MarketUpdate::Book {
+                slot,
+                side: Side::Bid,
+                orders: parse_slab_orders(data, Side::Bid)?,
+            }
+        } else if *pubkey == self.market.asks_address {
+            MarketUpdate::Book {
+                slot,
+                side: Side::Ask,
+                orders: parse_slab_orders(data, Side::Ask)?,
+            }
+        } else if *pubkey == self.market.event_queue {
+            MarketUpdate::Events {
+                slot,
+                events: EventQueue::parse(data)?.events,
+            }
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(update))
+    }
+
+    fn parse_market(&self, slot: u64, data: &[u8]) -> Result<MarketUpdate> {
+        let mut account = Account {
+            data: data.to_vec(),
+            owner: self.market.program_id,
+            ..Account::default()
+        };
+        let account_info = create_account_info_from_account(
+            &mut account,
+            &self.market.market_address,
+            &self.market.program_id,
+            false,
+            false,
+        );
+        let market_state = MarketState::load(&account_info, &self.market.program_id, false)?;
+
+        Ok(MarketUpdate::Market {
+            slot,
+            coin_deposits_total: market_state.coin_deposits_total,
+            pc_deposits_total: market_state.pc_deposits_total,
+            coin_fees_accrued: market_state.coin_fees_accrued,
+            pc_fees_accrued: market_state.pc_fees_accrued,
+        })
+    }
+}
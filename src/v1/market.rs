@@ -1,21 +1,38 @@
 //! This module contains structs and functions related to the openbook market.
 use crate::{
+    error::OpenBookError,
     rpc::Rpc,
     utils::{create_account_info_from_account, u64_slice_to_pubkey},
-    v1::traits::MarketInfo,
+    v1::{
+        events::Trade,
+        orders::{parse_slab_orders, OpenOrders},
+        traits::MarketInfo,
+    },
 };
 use anyhow::{Error, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
-use openbook_dex::state::{gen_vault_signer_key, MarketState};
+use openbook_dex::{
+    fees::{msrm_token, srm_token, FeeTier},
+    matching::Side,
+    state::{gen_vault_signer_key, MarketState},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::UiAccount;
 use solana_sdk::{
-    pubkey::Pubkey,
-    sysvar::slot_history::{AccountInfo, ProgramError},
+    pubkey::Pubkey, system_instruction::MAX_PERMITTED_DATA_LENGTH,
+    sysvar::slot_history::AccountInfo,
 };
+use spl_associated_token_account::get_associated_token_address;
 
-use std::fmt::{Debug, Formatter};
+use std::{
+    fmt::{Debug, Formatter},
+    io::Read,
+};
 
 /// Struct representing a market with associated state and information.
 #[derive(Clone, Default, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Market {
     /// The public key of the program associated with the market.
     pub program_id: Pubkey,
@@ -67,6 +84,241 @@ pub struct Market {
 
     /// The public key of the events authority used for consume transactions.
     pub events_authority: Pubkey,
+
+    /// Whether the lot sizes and decimals come from a registry and must not be overwritten by
+    /// `load`.
+    ///
+    /// Kept out of the Borsh layout, so persisted markets stay readable; it is `false` once
+    /// deserialized.
+    #[borsh_skip]
+    pub sizes_from_registry: bool,
+}
+
+/// Static lot sizes and decimals of a market, e.g. from a deployment's market registry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RegistryEntry {
+    /// The lot size for the base currency (coin) in the market.
+    pub coin_lot_size: u64,
+    /// The lot size for the quote currency (pc) in the market.
+    pub pc_lot_size: u64,
+    /// The number of decimal places for the base currency (coin) in the market.
+    pub coin_decimals: u8,
+    /// The number of decimal places for the quote currency (pc) in the market.
+    pub pc_decimals: u8,
+}
+
+/// Owned snapshot of the immutable market parameters, e.g. for off-chain matching engines.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MarketParameters {
+    /// The public key of the program associated with the market.
+    pub program_id: Pubkey,
+    /// The public key of the market.
+    pub market_address: Pubkey,
+    /// The lot size for the base currency (coin) in the market.
+    pub coin_lot_size: u64,
+    /// The lot size for the quote currency (pc) in the market.
+    pub pc_lot_size: u64,
+    /// The number of decimal places for the base currency (coin) in the market.
+    pub coin_decimals: u8,
+    /// The number of decimal places for the quote currency (pc) in the market.
+    pub pc_decimals: u8,
+    /// The public key of the market base mint.
+    pub base_mint: Pubkey,
+    /// The public key of the market quote mint.
+    pub quote_mint: Pubkey,
+    /// The public key of the vault holding base currency (coin) tokens.
+    pub coin_vault: Pubkey,
+    /// The public key of the vault holding quote currency (pc) tokens.
+    pub pc_vault: Pubkey,
+    /// The public key of the vault signer key associated with the market.
+    pub vault_signer_key: Pubkey,
+    /// The public key of the event queue associated with the market.
+    pub event_queue: Pubkey,
+    /// The public key of the request queue associated with the market.
+    pub request_queue: Pubkey,
+    /// The public key of the bids associated with the market.
+    pub bids_address: Pubkey,
+    /// The public key of the asks associated with the market.
+    pub asks_address: Pubkey,
+}
+
+/// Raw content of the accounts of a market, as fetched in a single request, e.g. to attach to a
+/// bug report and replay the parsing offline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MarketAccountsDump {
+    /// The slot all accounts were read at.
+    pub slot: u64,
+    /// The dumped accounts, in the order they were requested.
+    pub accounts: Vec<AccountDump>,
+}
+
+/// A single account of a `MarketAccountsDump`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountDump {
+    /// What the account is to the market, e.g. `"bids"`.
+    pub name: String,
+    /// The address of the account.
+    pub address: Pubkey,
+    /// The account with its data base64-encoded, or `None` if it does not exist.
+    pub account: Option<UiAccount>,
+}
+
+/// An exact price: an integer number of price lots together with the market's lot scale.
+///
+/// The UI price is `lots * numerator / denominator`. Unlike the `f64` prices, it does not lose
+/// precision on markets with many decimals or very large prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FixedPrice {
+    /// The price expressed in lots.
+    pub lots: u64,
+    /// Numerator of the lot scale, `pc_lot_size * 10^coin_decimals`.
+    pub numerator: u128,
+    /// Denominator of the lot scale, `coin_lot_size * 10^pc_decimals`.
+    pub denominator: u128,
+}
+
+impl FixedPrice {
+    /// Returns the UI price as a reduced fraction `(numerator, denominator)`.
+    pub fn rational(&self) -> (u128, u128) {
+        let numerator = self.lots as u128 * self.numerator;
+        let divisor = gcd(numerator, self.denominator).max(1);
+        (numerator / divisor, self.denominator / divisor)
+    }
+
+    /// Converts the price into a (possibly rounded) UI price.
+    pub fn to_f64(&self) -> f64 {
+        let (numerator, denominator) = self.rational();
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// Reads one length-prefixed account of a book archive.
+fn read_archive_account(reader: &mut impl Read) -> Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_le_bytes(len_bytes) as usize;
+    if len as u64 > MAX_PERMITTED_DATA_LENGTH {
+        anyhow::bail!("archived account is too large: {} bytes", len);
+    }
+
+    let mut data = vec![0u8; len];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Length of an SPL token mint account.
+const MINT_ACCOUNT_LEN: usize = 82;
+
+/// Offset of the `decimals` field within an SPL token mint account.
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Reads the `decimals` field of an SPL token mint account.
+///
+/// # Arguments
+///
+/// * `data` - The raw mint account data.
+///
+/// # Returns
+///
+/// The number of decimals of the mint, or an error if `data` is not a mint account.
+pub fn mint_decimals(data: &[u8]) -> Result<u8> {
+    if data.len() != MINT_ACCOUNT_LEN {
+        anyhow::bail!("invalid mint account size: {} bytes", data.len());
+    }
+    Ok(data[MINT_DECIMALS_OFFSET])
+}
+
+/// Native quote amount fee rates are sampled on, making them parts per billion.
+pub const FEE_RATE_SCALE: u64 = 1_000_000_000;
+
+/// Computes the taker fee the program charges on a native quote amount.
+///
+/// The program ignores the legacy `fee_rate_bps` of the market state; fees only depend on the
+/// fee tier of the order. The rate is sampled from the tier rounded up, like the program rounds
+/// fees, and applied in 128-bit arithmetic so large amounts cannot overflow.
+///
+/// # Arguments
+///
+/// * `fee_tier` - The fee tier of the order.
+/// * `pc_qty` - The native quote amount exchanged.
+///
+/// # Returns
+///
+/// The fee in native quote units, rounded up.
+pub fn taker_fee(fee_tier: FeeTier, pc_qty: u64) -> u64 {
+    let rate = fee_tier.taker_fee(FEE_RATE_SCALE) as u128;
+    let fee = (pc_qty as u128 * rate).div_ceil(FEE_RATE_SCALE as u128);
+
+    fee.min(u64::MAX as u128) as u64
+}
+
+/// Computes the rebate the program credits a maker on a native quote amount.
+///
+/// # Arguments
+///
+/// * `fee_tier` - The fee tier of the maker order.
+/// * `pc_qty` - The native quote amount exchanged.
+///
+/// # Returns
+///
+/// The rebate in native quote units, rounded down.
+pub fn maker_rebate(fee_tier: FeeTier, pc_qty: u64) -> u64 {
+    let rate = fee_tier.maker_rebate(FEE_RATE_SCALE) as u128;
+
+    (pc_qty as u128 * rate / FEE_RATE_SCALE as u128) as u64
+}
+
+/// Returns the taker fee rate of a fee tier, as a fraction of the quote exchanged.
+pub fn taker_fee_rate(fee_tier: FeeTier) -> f64 {
+    fee_tier.taker_fee(FEE_RATE_SCALE) as f64 / FEE_RATE_SCALE as f64
+}
+
+/// Returns the maker rebate rate of a fee tier, as a fraction of the quote exchanged.
+pub fn maker_rebate_rate(fee_tier: FeeTier) -> f64 {
+    fee_tier.maker_rebate(FEE_RATE_SCALE) as f64 / FEE_RATE_SCALE as f64
+}
+
+/// Offset of the account flags within an OpenBook account, after the `serum` marker.
+const ACCOUNT_FLAGS_OFFSET: usize = 5;
+
+/// Account flag set on every initialized OpenBook account.
+const ACCOUNT_FLAG_INITIALIZED: u64 = 1 << 0;
+
+/// Account flag set on market accounts.
+const ACCOUNT_FLAG_MARKET: u64 = 1 << 1;
+
+/// Checks that raw account data is an initialized OpenBook market.
+///
+/// # Arguments
+///
+/// * `address` - The address of the account, reported in the error.
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+///
+/// # Errors
+///
+/// Returns `OpenBookError::NotAMarket` if the `Initialized` and `Market` account flags are not
+/// both set, or if the data is too short to hold them.
+pub fn ensure_market_account(address: &Pubkey, data: &[u8]) -> Result<()> {
+    let required = ACCOUNT_FLAG_INITIALIZED | ACCOUNT_FLAG_MARKET;
+    let flags = data
+        .get(ACCOUNT_FLAGS_OFFSET..ACCOUNT_FLAGS_OFFSET + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+
+    match flags {
+        Some(flags) if flags & required == required => Ok(()),
+        _ => Err(OpenBookError::NotAMarket(*address).into()),
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
 impl Debug for Market {
@@ -89,10 +341,307 @@ impl Debug for Market {
         writeln!(f, "        bids_address: {:?}", self.bids_address)?;
         writeln!(f, "        asks_address: {:?}", self.asks_address)?;
         writeln!(f, "        events_authority: {:?}", self.events_authority)?;
+        writeln!(
+            f,
+            "        sizes_from_registry: {:?}",
+            self.sizes_from_registry
+        )?;
         writeln!(f, "    }}")
     }
 }
 
+impl Market {
+    /// Converts a quantity of base lots into base UI units.
+    ///
+    /// # Arguments
+    ///
+    /// * `lots` - The quantity expressed in base lots.
+    ///
+    /// # Returns
+    ///
+    /// The quantity expressed in base UI units.
+    pub fn base_size_lots_to_number(&self, lots: u64) -> f64 {
+        (lots as f64 * self.coin_lot_size as f64) / 10f64.powi(self.coin_decimals as i32)
+    }
+
+    /// Converts a quantity in base UI units into base lots, truncating like order placement does.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The quantity expressed in base UI units.
+    ///
+    /// # Returns
+    ///
+    /// The quantity expressed in base lots.
+    pub fn base_size_number_to_lots(&self, size: f64) -> u64 {
+        (size * 10f64.powi(self.coin_decimals as i32) / self.coin_lot_size as f64) as u64
+    }
+
+    /// Converts a UI price into price lots, truncating like order placement does.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price in quote UI units per base UI unit.
+    ///
+    /// # Returns
+    ///
+    /// The price expressed in lots.
+    pub fn price_number_to_lots(&self, price: f64) -> u64 {
+        let base_d_factor = 10f64.powi(self.coin_decimals as i32);
+        let quote_d_factor = 10f64.powi(self.pc_decimals as i32);
+        let price_factor =
+            quote_d_factor * self.coin_lot_size as f64 / base_d_factor / self.pc_lot_size as f64;
+
+        (price * price_factor) as u64
+    }
+
+    /// Converts a price expressed in lots into a UI price.
+    ///
+    /// # Arguments
+    ///
+    /// * `lots` - The price expressed in lots.
+    ///
+    /// # Returns
+    ///
+    /// The price in quote UI units per base UI unit.
+    pub fn price_lots_to_number(&self, lots: u64) -> f64 {
+        let base_d_factor = 10f64.powi(self.coin_decimals as i32);
+        let quote_d_factor = 10f64.powi(self.pc_decimals as i32);
+        let price_factor =
+            quote_d_factor * self.coin_lot_size as f64 / base_d_factor / self.pc_lot_size as f64;
+
+        lots as f64 / price_factor
+    }
+
+    /// Computes the volume-weighted average price of `trades`.
+    ///
+    /// # Arguments
+    ///
+    /// * `trades` - The trades to average, e.g. from `EventQueue::recent_trades`.
+    ///
+    /// # Returns
+    ///
+    /// The VWAP in UI units, or `None` if the trades carry no base volume.
+    pub fn trades_vwap(&self, trades: &[Trade]) -> Option<f64> {
+        let (notional, volume) = trades.iter().fold((0.0, 0.0), |(notional, volume), trade| {
+            let size = trade.base_native as f64 / 10f64.powi(self.coin_decimals as i32);
+            (
+                notional + self.price_lots_to_number(trade.price_lots) * size,
+                volume + size,
+            )
+        });
+
+        (volume > 0.0).then(|| notional / volume)
+    }
+
+    /// Reconstructs the order book at past slots from an archive of bids and asks snapshots.
+    ///
+    /// The archive is a sequence of records, each laid out as:
+    ///
+    /// ```text
+    /// slot: u64 LE | bids_len: u32 LE | bids: [u8; bids_len] | asks_len: u32 LE | asks: [u8; asks_len]
+    /// ```
+    ///
+    /// where `bids` and `asks` are the raw bids and asks account data at `slot`. The archive ends
+    /// at the end of the stream, on a record boundary.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The archive to read.
+    ///
+    /// # Returns
+    ///
+    /// The `(slot, book)` series in archive order, or an error if a record is truncated or holds
+    /// invalid account data.
+    pub fn book_from_archive(&self, mut reader: impl Read) -> Result<Vec<(u64, OpenOrders)>> {
+        let mut series = Vec::new();
+        loop {
+            let mut slot_bytes = [0u8; 8];
+            if reader.read(&mut slot_bytes[..1])? == 0 {
+                break;
+            }
+            reader.read_exact(&mut slot_bytes[1..])?;
+            let slot = u64::from_le_bytes(slot_bytes);
+
+            let bids = parse_slab_orders(&read_archive_account(&mut reader)?, Side::Bid)?;
+            let asks = parse_slab_orders(&read_archive_account(&mut reader)?, Side::Ask)?;
+
+            let book = OpenOrders {
+                max_bid: bids.first().map(|order| order.price).unwrap_or(0),
+                min_ask: asks.first().map(|order| order.price).unwrap_or(0),
+                bids_address: self.bids_address,
+                asks_address: self.asks_address,
+                bids,
+                asks,
+                slot,
+                ..Default::default()
+            };
+            series.push((slot, book));
+        }
+
+        Ok(series)
+    }
+
+    /// Converts a price expressed in lots into an exact `FixedPrice`.
+    ///
+    /// # Arguments
+    ///
+    /// * `lots` - The price expressed in lots.
+    ///
+    /// # Returns
+    ///
+    /// The price together with the lot scale of the market.
+    pub fn price_lots_to_fixed(&self, lots: u64) -> FixedPrice {
+        FixedPrice {
+            lots,
+            numerator: self.pc_lot_size as u128 * 10u128.pow(self.coin_decimals as u32),
+            denominator: self.coin_lot_size as u128 * 10u128.pow(self.pc_decimals as u32),
+        }
+    }
+
+    /// Returns the base (coin) mint, read from the market state's `coin_mint` on load.
+    pub fn coin_mint(&self) -> Pubkey {
+        self.base_mint
+    }
+
+    /// Returns the quote (pc) mint, read from the market state's `pc_mint` on load.
+    pub fn pc_mint(&self) -> Pubkey {
+        self.quote_mint
+    }
+
+    /// Derives the associated token accounts of `owner` for the base and quote mints.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The wallet owning the token accounts.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(base_ata, quote_ata)`.
+    pub fn owner_atas(&self, owner: &Pubkey) -> (Pubkey, Pubkey) {
+        (
+            get_associated_token_address(owner, &self.base_mint),
+            get_associated_token_address(owner, &self.quote_mint),
+        )
+    }
+
+    /// Checks that the cached bids and asks addresses are the ones stored in the market state.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_info` - The freshly fetched market account.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::BookAddressMismatch` for the first cached address that differs
+    /// from the market state, or an error if the market state cannot be loaded.
+    pub fn check_book_addresses(&self, account_info: &AccountInfo<'_>) -> Result<()> {
+        let market_state = MarketState::load(account_info, &self.program_id, false)?;
+        let bids = Pubkey::new_from_array(u64_slice_to_pubkey(market_state.bids));
+        let asks = Pubkey::new_from_array(u64_slice_to_pubkey(market_state.asks));
+
+        for (expected, found) in [(bids, self.bids_address), (asks, self.asks_address)] {
+            if expected != found {
+                return Err(OpenBookError::BookAddressMismatch { expected, found }.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the base and quote decimals from the market's mint accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - RPC client used to fetch both mints in a single request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::MintNotFound` if a mint account does not exist, or an error if the
+    /// mints cannot be fetched or are not mint accounts.
+    pub async fn load_mint_decimals(&mut self, rpc_client: &Rpc) -> Result<()> {
+        let mints = [self.base_mint, self.quote_mint];
+        let accounts = rpc_client.fetch_multiple_accounts(&mints).await?;
+        let decimals = |index: usize| -> Result<u8> {
+            let account = accounts
+                .get(index)
+                .and_then(Option::as_ref)
+                .ok_or(OpenBookError::MintNotFound(mints[index]))?;
+            mint_decimals(&account.data)
+        };
+
+        self.coin_decimals = decimals(0)?;
+        self.pc_decimals = decimals(1)?;
+        Ok(())
+    }
+
+    /// Sets the lot sizes and decimals from a registry entry instead of the market account.
+    ///
+    /// Later calls to `load` keep these values and only refresh the rest of the market state.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The registry entry of this market.
+    pub fn apply_registry(&mut self, entry: RegistryEntry) {
+        self.coin_lot_size = entry.coin_lot_size;
+        self.pc_lot_size = entry.pc_lot_size;
+        self.coin_decimals = entry.coin_decimals;
+        self.pc_decimals = entry.pc_decimals;
+        self.sizes_from_registry = true;
+    }
+
+    /// Maps the holdings of a fee discount account to the fee tier it earns on this market.
+    ///
+    /// # Arguments
+    ///
+    /// * `mint` - The mint of the discount account, SRM or MSRM.
+    /// * `amount` - The balance of the discount account, in native units.
+    ///
+    /// # Returns
+    ///
+    /// The fee tier granted by the holdings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InvalidFeeDiscountMint` if `mint` is neither SRM nor MSRM.
+    pub fn fee_discount_tier(&self, mint: &Pubkey, amount: u64) -> Result<FeeTier> {
+        let (srm_held, msrm_held) = if *mint == srm_token::ID {
+            (amount, 0)
+        } else if *mint == msrm_token::ID {
+            (0, amount)
+        } else {
+            return Err(OpenBookError::InvalidFeeDiscountMint(*mint).into());
+        };
+
+        Ok(FeeTier::from_srm_and_msrm_balances(
+            &self.market_address,
+            srm_held,
+            msrm_held,
+        ))
+    }
+
+    /// Returns an owned snapshot of the immutable market parameters.
+    pub fn parameters(&self) -> MarketParameters {
+        MarketParameters {
+            program_id: self.program_id,
+            market_address: self.market_address,
+            coin_lot_size: self.coin_lot_size,
+            pc_lot_size: self.pc_lot_size,
+            coin_decimals: self.coin_decimals,
+            pc_decimals: self.pc_decimals,
+            base_mint: self.base_mint,
+            quote_mint: self.quote_mint,
+            coin_vault: self.coin_vault,
+            pc_vault: self.pc_vault,
+            vault_signer_key: self.vault_signer_key,
+            event_queue: self.event_queue,
+            request_queue: self.request_queue,
+            bids_address: self.bids_address,
+            asks_address: self.asks_address,
+        }
+    }
+}
+
 impl MarketInfo for Market {
     /// Initializes a new instance of the `Market` struct.
     ///
@@ -192,19 +741,22 @@ impl MarketInfo for Market {
             event_queue: Default::default(),
             request_queue: Default::default(),
             account_flags: 0,
+            sizes_from_registry: false,
         };
 
         if load {
             market.load(&rpc_client).await?;
+        } else {
+            market.init_vault_signer_key().await?;
         }
 
-        market.init_vault_signer_key().await?;
-
         Ok(market)
     }
 
     /// Loads market information from the provided RPC client.
     ///
+    /// The decimals are read from the base and quote mints, unless a registry entry was applied.
+    ///
     /// # Arguments
     ///
     /// * `rpc_client` - RPC client for interacting with the Solana blockchain.
@@ -217,7 +769,8 @@ impl MarketInfo for Market {
     /// # Errors
     ///
     /// This function returns an error if loading the market fails for any reason,
-    /// such as the absence of the market account or permission issues.
+    /// such as the absence of the market account, or `OpenBookError::InvalidProgramOwner` if
+    /// the market account is not owned by `program_id`.
     ///
     /// # Examples
     ///
@@ -261,7 +814,7 @@ impl MarketInfo for Market {
     /// }
     /// ```
     async fn load(&mut self, rpc_client: &Rpc) -> Result<(), Error> {
-        let mut account = rpc_client.inner().get_account(&self.market_address).await?;
+        let mut account = rpc_client.fetch_account(&self.market_address).await?;
         let owner = account.owner;
         let program_id_binding = self.program_id;
         let market_account_binding = self.market_address;
@@ -276,10 +829,17 @@ impl MarketInfo for Market {
             );
         }
         if self.program_id != owner {
-            return Err(ProgramError::InvalidArgument.into());
+            return Err(OpenBookError::InvalidProgramOwner {
+                expected: self.program_id,
+                found: owner,
+            }
+            .into());
         }
 
         self.load_market_state_info(&account_info).await?;
+        if !self.sizes_from_registry {
+            self.load_mint_decimals(rpc_client).await?;
+        }
 
         Ok(())
     }
@@ -297,7 +857,10 @@ impl MarketInfo for Market {
     /// # Errors
     ///
     /// This function returns an error if loading the market state fails for any reason,
-    /// such as invalid account data or parsing issues.
+    /// such as invalid account data or parsing issues. It returns
+    /// `OpenBookError::NotAMarket` if the account flags do not mark an initialized market, and
+    /// `OpenBookError::MarketAddressMismatch` if the address stored in the market state is not
+    /// `market_address`, e.g. when the wrong account was loaded.
     ///
     /// # Examples
     ///
@@ -360,6 +923,7 @@ impl MarketInfo for Market {
         &mut self,
         account_info: &AccountInfo<'_>,
     ) -> Result<(), Error> {
+        ensure_market_account(&self.market_address, &account_info.data.borrow())?;
         let market_state = MarketState::load(account_info, &self.program_id, false)?;
 
         // Extract relevant information from the loaded market state.
@@ -370,6 +934,17 @@ impl MarketInfo for Market {
         let own_address_array: [u8; 32] = u64_slice_to_pubkey(market_state.own_address);
         let bids_array: [u8; 32] = u64_slice_to_pubkey(market_state.bids);
         let asks_array: [u8; 32] = u64_slice_to_pubkey(market_state.asks);
+        let coin_mint_array: [u8; 32] = u64_slice_to_pubkey(market_state.coin_mint);
+        let pc_mint_array: [u8; 32] = u64_slice_to_pubkey(market_state.pc_mint);
+
+        let own_address = Pubkey::new_from_array(own_address_array);
+        if self.market_address != own_address {
+            return Err(OpenBookError::MarketAddressMismatch {
+                expected: self.market_address,
+                found: own_address,
+            }
+            .into());
+        }
 
         self.coin_vault = Pubkey::new_from_array(coin_vault_array);
         self.pc_vault = Pubkey::new_from_array(pc_vault_array);
@@ -377,19 +952,29 @@ impl MarketInfo for Market {
         self.event_queue = Pubkey::new_from_array(event_queue_array);
         self.bids_address = Pubkey::new_from_array(bids_array);
         self.asks_address = Pubkey::new_from_array(asks_array);
-
-        let own_address = Pubkey::new_from_array(own_address_array);
-        assert_eq!(self.market_address, own_address);
+        self.base_mint = Pubkey::new_from_array(coin_mint_array);
+        self.quote_mint = Pubkey::new_from_array(pc_mint_array);
+        self.vault_signer_key = gen_vault_signer_key(
+            market_state.vault_signer_nonce,
+            &self.market_address,
+            &self.program_id,
+        )?;
 
         self.account_flags = market_state.account_flags;
-        self.coin_lot_size = market_state.coin_lot_size;
-        self.pc_lot_size = market_state.pc_lot_size;
+        if !self.sizes_from_registry {
+            self.coin_lot_size = market_state.coin_lot_size;
+            self.pc_lot_size = market_state.pc_lot_size;
+        }
 
         Ok(())
     }
 
     /// Initializes the vault signer key.
     ///
+    /// Without a loaded market state the nonce is unknown, so the first nonce yielding a valid
+    /// key is used; loading the market replaces it with the key of the stored
+    /// `vault_signer_nonce`.
+    ///
     /// # Returns
     ///
     /// Returns `Ok(())` if initialization is successful, or an error otherwise.
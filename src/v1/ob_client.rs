@@ -1,46 +1,108 @@
 use crate::v1::{
-    market::Market,
-    orders::{OpenOrders, OpenOrdersCacheEntry, OrderReturnType},
+    events::{Event, EventQueue, EventQueueFullPolicy, FillEvent, Trade},
+    market::{
+        maker_rebate_rate, taker_fee, taker_fee_rate, AccountDump, Market, MarketAccountsDump,
+        MarketParameters,
+    },
+    orders::{
+        aggregate_free_balances, clamp_reduce_only, client_order_id_in_namespace,
+        create_open_orders_instructions, drain_slab, dump_open_orders_cache,
+        fetch_open_orders_accounts, find_existing_open_orders_account, known_mint,
+        load_open_orders_cache, namespaced_client_order_id, open_orders_account_space,
+        open_orders_balances, open_orders_free_balances, open_orders_is_empty,
+        open_orders_live_orders, open_orders_seeded_address, order_id_side, quote_action, sol_plan,
+        Balances, OpenOrders, OpenOrdersCacheEntry, Order, OrderBook, OrderReturnType, OrderSpec,
+        PlacedOrder, QueuePosition, QuoteAction, SimResult, SolPlan, TakerOrder, TakerPreview,
+        TakerResult, OPEN_ORDERS_ACCOUNT_LEN, OPEN_ORDERS_SLOTS, WSOL_MINT,
+    },
 };
 use crate::{
-    rpc::Rpc,
+    error::{MarketError, OpenBookError},
+    rpc::{Rpc, SendConfig},
     rpc_client::RpcClient,
-    utils::{create_account_info_from_account, get_unix_secs, read_keypair, u64_slice_to_pubkey},
+    utils::{
+        create_account_info_from_account, create_associated_token_account_instruction,
+        get_unix_millis, get_unix_secs, read_keypair, split_into_transactions, transaction_size,
+        u64_slice_to_pubkey,
+    },
     v1::traits::{MarketInfo, OpenOrdersT},
 };
 
 use anyhow::{Error, Result};
 use openbook_dex::{
     critbit::Slab,
+    fees::FeeTier,
     instruction::SelfTradeBehavior,
     matching::{OrderType, Side},
     state::{Market as MarketAuth, MarketState},
 };
 use rand::random;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::rpc_request::TokenAccountsFilter;
 use solana_sdk::{
+    account::Account,
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
     signature::{Signature, Signer},
     signer::keypair::Keypair,
     sysvar::{rent, slot_history::ProgramError},
+    transaction::Transaction,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionStatusMeta, UiTransactionTokenBalance,
 };
 use spl_associated_token_account::get_associated_token_address;
 use std::{
     cell::RefMut,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{Debug, Formatter},
     num::NonZeroU64,
     str::FromStr,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
 };
+use tokio::sync::Mutex;
 
 use tracing::debug;
 
+#[cfg(feature = "ws")]
+use crate::{
+    utils::ws_url_from_rpc_url,
+    v1::orders::{open_orders_balance_updates, open_orders_updates},
+};
+#[cfg(feature = "ws")]
+use futures_util::{stream, Stream, StreamExt};
+#[cfg(feature = "ws")]
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+#[cfg(feature = "ws")]
+use tokio::sync::mpsc;
+#[cfg(feature = "ws")]
+use tracing::error;
+
 pub static SPL_TOKEN_ID: &'static str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 pub static SRM_PROGRAM_ID: &'static str = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX";
 
+/// How long a successful pre-simulation covers orders with the same account set.
+pub const PRE_SIMULATION_WINDOW: Duration = Duration::from_secs(2);
+
+/// How many head events the `Crank` full queue policy consumes before an order is sent.
+///
+/// Each event may reference a distinct open orders account, so the limit keeps the crank
+/// transaction within the packet size.
+pub const FULL_QUEUE_CRANK_LIMIT: u16 = 16;
+
+/// How many cancels are packed into one transaction when cancelling all orders.
+///
+/// The packet size alone would allow more, but the cancels must also fit the compute budget of
+/// the transaction.
+pub const MAX_CANCELS_PER_TRANSACTION: usize = 10;
+
+/// The program and account metas of each instruction of a transaction.
+pub type InstructionAccounts = Vec<(Pubkey, Vec<AccountMeta>)>;
+
 /// OpenBook v1 Client to interact with the OpenBook market and perform actions.
 #[derive(Clone)]
 pub struct OBClient {
@@ -48,16 +110,44 @@ pub struct OBClient {
     pub owner: Arc<Keypair>,
     /// The RPC client for interacting with the Solana blockchain.
     pub rpc_client: Rpc,
-    /// The public key of the associated account holding the quote tokens.
-    pub quote_ata: Pubkey,
-    /// The public key of the associated account holding the base tokens.
-    pub base_ata: Pubkey,
+    /// The account holding the quote tokens, either set explicitly or derived by `quote_ata()`
+    /// on first use.
+    pub quote_ata: OnceLock<Pubkey>,
+    /// The account holding the base tokens, either set explicitly or derived by `base_ata()` on
+    /// first use.
+    pub base_ata: OnceLock<Pubkey>,
     /// Account info of the wallet on the market (e.g., open orders).
     pub open_orders: OpenOrders,
     /// Information about the OpenBook market.
     pub market_info: Market,
     /// A HashMap containing open orders cache entries associated with their public keys.
     pub open_orders_cache: HashMap<Pubkey, OpenOrdersCacheEntry>,
+    /// Namespace prefix stored in the high bits of every generated client order id; cancels by
+    /// client order id only accept ids inside it. 0 disables namespacing.
+    pub client_id_prefix: u32,
+    /// Whether order placement refuses to trade while the cached book is crossed.
+    pub crossed_book_guard: bool,
+    /// Minimum signer SOL balance, in lamports, that order placement requires before sending.
+    pub min_fee_balance: Option<u64>,
+    /// The last market state served by `cached_market_info`, with the instant it was fetched.
+    pub market_info_cache: Arc<Mutex<Option<(Instant, Market)>>>,
+    /// What order placement does when the market's event queue is full.
+    pub event_queue_full_policy: EventQueueFullPolicy,
+    /// Whether order placement simulates the order before sending it.
+    pub pre_simulate: bool,
+    /// The account set of the last successful pre-simulation, with the instant it ran.
+    pub pre_simulation_cache: Arc<Mutex<Option<(Instant, InstructionAccounts)>>>,
+    /// The SRM or MSRM token account passed to new orders to earn a fee discount.
+    pub fee_discount_account: Option<Pubkey>,
+    /// The fee tier the program applies to the owner's orders, from the fee discount account.
+    pub fee_tier: FeeTier,
+    /// The keypair paying for and signing consume events cranks; `None` uses the owner.
+    pub crank_payer: Option<Arc<Keypair>>,
+    /// Whether the client was loaded without a signing keypair; sending methods then fail.
+    pub read_only: bool,
+    /// The base holdings reduce-only orders reduce toward, in UI units. Holdings above it are a
+    /// long position reduced by asks; holdings below it are a short one reduced by bids.
+    pub base_position_target: f64,
 }
 
 impl Debug for OBClient {
@@ -65,91 +155,288 @@ impl Debug for OBClient {
         writeln!(f, "OB_V1_Client {{")?;
         writeln!(f, "    owner: {:?}", self.owner.pubkey())?;
         writeln!(f, "    rpc_client: {:?}", self.rpc_client)?;
-        writeln!(f, "    quote_ata: {:?}", self.quote_ata)?;
-        writeln!(f, "    base_ata: {:?}", self.base_ata)?;
+        writeln!(f, "    quote_ata: {:?}", self.quote_ata.get())?;
+        writeln!(f, "    base_ata: {:?}", self.base_ata.get())?;
         writeln!(f, "    open_orders: {:?}", self.open_orders)?;
         writeln!(f, "    market_info: {:?}", self.market_info)?;
+        writeln!(f, "    client_id_prefix: {:?}", self.client_id_prefix)?;
+        writeln!(f, "    crossed_book_guard: {:?}", self.crossed_book_guard)?;
+        writeln!(f, "    min_fee_balance: {:?}", self.min_fee_balance)?;
+        writeln!(f, "    market_info_cache: {:?}", self.market_info_cache)?;
+        writeln!(
+            f,
+            "    event_queue_full_policy: {:?}",
+            self.event_queue_full_policy
+        )?;
+        writeln!(f, "    pre_simulate: {:?}", self.pre_simulate)?;
+        writeln!(
+            f,
+            "    pre_simulation_cache: {:?}",
+            self.pre_simulation_cache
+        )?;
+        writeln!(f, "    fee_tier: {:?}", self.fee_tier)?;
+        writeln!(
+            f,
+            "    crank_payer: {:?}",
+            self.crank_payer.as_ref().map(|payer| payer.pubkey())
+        )?;
+        writeln!(f, "    read_only: {:?}", self.read_only)?;
+        writeln!(
+            f,
+            "    base_position_target: {:?}",
+            self.base_position_target
+        )?;
         writeln!(f, "}}")
     }
 }
 
-impl OBClient {
-    /// Initializes a new instance of the `OBClient` struct, representing an OpenBook V1 program client.
-    ///
-    /// This method initializes the `OBClient` struct, containing information about the requested market id.
-    /// It fetches and stores all data about this OpenBook market. Additionally, it includes information about
-    /// the account associated with the wallet on the OpenBook market (e.g., open orders, bids, asks, etc.).
-    ///
-    /// # Arguments
+/// Builder of an [`OBClient`], configuring every input explicitly instead of through env vars.
+///
+/// Only the market id is required. The other inputs default to the mainnet RPC endpoint,
+/// the OpenBook V1 program, a fresh keypair, the owner's associated token accounts, a newly
+/// created open orders account, `confirmed` commitment and the RPC's own send options.
+///
+/// # Example
+///
+/// ```rust
+/// use openbook::commitment_config::CommitmentConfig;
+/// use openbook::utils::read_keypair;
+/// use openbook::v1::ob_client::OBClientBuilder;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let ob_client = OBClientBuilder::new()
+///         .market_id("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?)
+///         .owner(read_keypair(&std::env::var("KEY_PATH")?))
+///         .commitment(CommitmentConfig::processed())
+///         .priority_fee_micro_lamports(5_000)
+///         .load(true)
+///         .build()
+///         .await?;
+///
+///     println!("Initialized OBClient: {:?}", ob_client);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct OBClientBuilder {
+    rpc_client: Option<Rpc>,
+    rpc_url: Option<String>,
+    program_id: Option<Pubkey>,
+    market_id: Option<Pubkey>,
+    owner: Option<Keypair>,
+    base_ata: Option<Pubkey>,
+    quote_ata: Option<Pubkey>,
+    open_orders_key: Option<Pubkey>,
+    commitment: Option<CommitmentConfig>,
+    priority_fee_micro_lamports: Option<u64>,
+    create_missing_accounts: bool,
+    load: bool,
+    read_only: bool,
+}
+
+impl Debug for OBClientBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OBClientBuilder")
+            .field("rpc_url", &self.rpc_url)
+            .field("program_id", &self.program_id)
+            .field("market_id", &self.market_id)
+            .field("owner", &self.owner.as_ref().map(|owner| owner.pubkey()))
+            .field("base_ata", &self.base_ata)
+            .field("quote_ata", &self.quote_ata)
+            .field("open_orders_key", &self.open_orders_key)
+            .field("commitment", &self.commitment)
+            .field(
+                "priority_fee_micro_lamports",
+                &self.priority_fee_micro_lamports,
+            )
+            .field("create_missing_accounts", &self.create_missing_accounts)
+            .field("load", &self.load)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+impl OBClientBuilder {
+    /// Creates a builder with every input unset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder from the `RPC_URL`, `KEY_PATH`, `OOS_KEY` and
+    /// `CREATE_MISSING_ACCOUNTS` env vars, as [`OBClient::new`] does.
     ///
-    /// * `commitment` - Commitment configuration for transactions, determining the level of finality required.
-    /// * `market_id` - Public key (ID) of the market to fetch information about.
-    /// * `load` - Boolean indicating whether to load market data immediately after initialization.
-    /// * `cache_ts` - Timestamp for caching current open orders, used to manage the cache validity.
+    /// Unset or unparsable env vars leave the matching input at its default; without a readable
+    /// `KEY_PATH` no owner is set.
+    pub fn from_env() -> Self {
+        let key_path = std::env::var("KEY_PATH").unwrap_or("".to_string());
+        let oos_key_str = std::env::var("OOS_KEY").unwrap_or("".to_string());
+        let create_missing_accounts = std::env::var("CREATE_MISSING_ACCOUNTS")
+            .map(|value| value == "true" || value == "1")
+            .unwrap_or(false);
+        let owner = std::fs::read_to_string(&key_path)
+            .is_ok_and(|secret| !secret.trim().is_empty())
+            .then(|| read_keypair(&key_path));
+
+        Self {
+            rpc_url: std::env::var("RPC_URL").ok(),
+            owner,
+            open_orders_key: Pubkey::from_str(oos_key_str.as_str()).ok(),
+            create_missing_accounts,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the RPC client used to read the market and send transactions.
+    pub fn rpc_client(mut self, rpc_client: Rpc) -> Self {
+        self.rpc_client = Some(rpc_client);
+        self
+    }
+
+    /// Sets the URL of the RPC node to connect to when no RPC client is set, defaulting to
+    /// mainnet-beta.
+    pub fn rpc_url(mut self, rpc_url: impl Into<String>) -> Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// Sets the OpenBook program id, defaulting to the OpenBook V1 program.
+    pub fn program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = Some(program_id);
+        self
+    }
+
+    /// Sets the address of the market to trade on.
+    pub fn market_id(mut self, market_id: Pubkey) -> Self {
+        self.market_id = Some(market_id);
+        self
+    }
+
+    /// Sets the keypair that owns the open orders account and signs transactions.
+    pub fn owner(mut self, owner: Keypair) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Sets the base token account, instead of the owner's associated token account.
+    pub fn base_ata(mut self, base_ata: Pubkey) -> Self {
+        self.base_ata = Some(base_ata);
+        self
+    }
+
+    /// Sets the quote token account, instead of the owner's associated token account.
+    pub fn quote_ata(mut self, quote_ata: Pubkey) -> Self {
+        self.quote_ata = Some(quote_ata);
+        self
+    }
+
+    /// Sets an existing open orders account, so that none is created on build.
+    pub fn open_orders_key(mut self, open_orders_key: Pubkey) -> Self {
+        self.open_orders_key = Some(open_orders_key);
+        self
+    }
+
+    /// Sets the commitment of the client's reads and sends, defaulting to `confirmed`.
+    pub fn commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    /// Sets a fixed compute unit price, in micro-lamports, for every transaction sent.
+    pub fn priority_fee_micro_lamports(mut self, micro_lamports: u64) -> Self {
+        self.priority_fee_micro_lamports = Some(micro_lamports);
+        self
+    }
+
+    /// Sets whether the missing base and quote token accounts are created on build.
+    pub fn create_missing_accounts(mut self, create_missing_accounts: bool) -> Self {
+        self.create_missing_accounts = create_missing_accounts;
+        self
+    }
+
+    /// Sets whether the bids and asks are loaded on build.
+    pub fn load(mut self, load: bool) -> Self {
+        self.load = load;
+        self
+    }
+
+    /// Sets whether the client is built without account-creation side effects.
+    ///
+    /// A read-only client always loads the market state, never creates token or open orders
+    /// accounts, leaves unset token and open orders accounts empty, and refuses to send
+    /// transactions with [`OpenBookError::ReadOnly`]. Its owner is a throwaway keypair unless
+    /// one is set.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Builds the `OBClient`, fetching the market and preparing the owner's accounts.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` wrapping a new instance of the `OBClient` struct initialized with the provided parameters,
-    /// or an `Error` if the initialization process fails.
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
-    ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let commitment = CommitmentConfig::confirmed();
-    ///
-    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
-    ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    /// The initialized `OBClient`.
     ///
-    ///     println!("Initialized OBClient: {:?}", ob_client);
+    /// # Errors
     ///
-    ///     Ok(())
-    /// }
-    /// ```
+    /// Returns an error if no market id was set, if no owner was set for a client that is not
+    /// read-only, if the market cannot be fetched or loaded, or if creating the open orders or
+    /// token accounts fails.
     ///
     /// # Business Logic
     ///
-    /// 1. Retrieve necessary env vars, such as the `RPC_URL` and `KEY_PATH` path.
-    /// 2. Read the owner's keypair from the specified key path.
-    /// 3. Initialize the RPC client with the given commitment configuration.
-    /// 4. Fetch the market account information on chain.
-    /// 5. Load the market state and extract base and quote mints.
-    /// 6. Initialize the `Market` struct with fetched market information.
-    /// 7. Fetche associated token accounts (ATA) for the base and quote tokens.
-    /// 8. Initialize the open orders for the client.
-    /// 9. Populate the open orders cache.
-    /// 10. Load bids and asks information if the `load` parameter is set to `true`.
-    ///
-    pub async fn new(
-        commitment: CommitmentConfig,
-        market_id: Pubkey,
-        load: bool,
-        cache_ts: u128,
-    ) -> Result<Self, Error> {
-        let rpc_url =
-            std::env::var("RPC_URL").unwrap_or("https://api.mainnet-beta.solana.com".to_string());
-        let key_path = std::env::var("KEY_PATH").unwrap_or("".to_string());
-
-        let owner = read_keypair(&key_path);
-        let rpc_client = RpcClient::new_with_commitment(rpc_url, commitment);
-        let oos_key_str = std::env::var("OOS_KEY").unwrap_or("".to_string());
-
-        let orders_key = Pubkey::from_str(oos_key_str.as_str());
-
-        let pub_owner_key = owner.pubkey().clone();
+    /// 1. Connect to the RPC node at the commitment, unless an RPC client is set, and apply the
+    ///    commitment and priority fee to the RPC client.
+    /// 2. Fetch the market account information on chain.
+    /// 3. Load the market state and extract base and quote mints.
+    /// 4. Initialize the `Market` struct with fetched market information.
+    /// 5. Use the explicit token accounts, or let `base_ata()` and `quote_ata()` derive the
+    ///    owner's associated token accounts on first use unless read-only.
+    /// 6. Use the explicit open orders account, or the owner's existing one on this market, or
+    ///    create a new one unless read-only.
+    /// 7. Populate the open orders cache.
+    /// 8. Create the missing base and quote ATAs if requested.
+    /// 9. Load bids and asks information if requested.
+    pub async fn build(self) -> Result<OBClient> {
+        let market_id = self
+            .market_id
+            .ok_or_else(|| anyhow::anyhow!("a market id is required to build an OBClient"))?;
+        let program_id: Pubkey = match self.program_id {
+            Some(program_id) => program_id,
+            None => SRM_PROGRAM_ID.parse()?,
+        };
+        let owner = match self.owner {
+            Some(owner) => owner,
+            None if self.read_only => Keypair::new(),
+            None => anyhow::bail!(
+                "an owner keypair is required to build an OBClient that is not read-only"
+            ),
+        };
+        let pub_owner_key = owner.pubkey();
 
-        let rpc_client = Rpc::new(rpc_client);
+        let commitment = self.commitment.unwrap_or(CommitmentConfig::confirmed());
+        let mut rpc_client = self
+            .rpc_client
+            .unwrap_or_else(|| {
+                let rpc_url = self
+                    .rpc_url
+                    .unwrap_or("https://api.mainnet-beta.solana.com".to_string());
+                Rpc::new(RpcClient::new_with_commitment(rpc_url, commitment))
+            })
+            .with_commitment(commitment);
+        if let Some(micro_lamports) = self.priority_fee_micro_lamports {
+            let send_config = SendConfig {
+                priority_fee_micro_lamports: Some(micro_lamports),
+                ..*rpc_client.send_config()
+            };
+            rpc_client = rpc_client.with_send_config(send_config);
+        }
 
-        let mut account_1 = rpc_client.inner().get_account(&market_id).await?;
-        let mut account_2 = rpc_client.inner().get_account(&market_id).await?;
+        let mut account_1 = rpc_client.fetch_account(&market_id).await?;
+        let mut account_2 = rpc_client.fetch_account(&market_id).await?;
         let account_info_1;
         let account_info_2;
-        let program_id = SRM_PROGRAM_ID.parse().unwrap();
         {
             account_info_1 = create_account_info_from_account(
                 &mut account_1,
@@ -166,9 +453,8 @@ impl OBClient {
                 false,
             );
         }
-        let market = MarketState::load(&account_info_1, &SRM_PROGRAM_ID.parse().unwrap(), false)?;
-        let market_auth =
-            MarketAuth::load(&account_info_2, &SRM_PROGRAM_ID.parse().unwrap(), false)?;
+        let market = MarketState::load(&account_info_1, &program_id, false)?;
+        let market_auth = MarketAuth::load(&account_info_2, &program_id, false)?;
         let default_auth = Default::default();
         let events_authority = market_auth
             .consume_events_authority()
@@ -179,37 +465,63 @@ impl OBClient {
 
         let market_info = Market::new(
             rpc_client.clone(),
-            SRM_PROGRAM_ID.parse().unwrap(),
+            program_id,
             market_id,
             base_mint,
             quote_mint,
             *events_authority,
-            load,
+            self.load || self.read_only,
         )
         .await?;
 
-        let base_ata = get_associated_token_address(&pub_owner_key.clone(), &market_info.base_mint);
-        let quote_ata =
-            get_associated_token_address(&pub_owner_key.clone(), &market_info.quote_mint);
+        // Unless set explicitly, the owner's ATAs are derived on first use.
+        let (base_ata, quote_ata) = if self.read_only {
+            (
+                OnceLock::from(Pubkey::default()),
+                OnceLock::from(Pubkey::default()),
+            )
+        } else {
+            (
+                self.base_ata.map(OnceLock::from).unwrap_or_default(),
+                self.quote_ata.map(OnceLock::from).unwrap_or_default(),
+            )
+        };
 
-        let cloned_owner = owner.insecure_clone();
-        let open_orders = OpenOrders::new(
-            rpc_client.clone(),
-            SRM_PROGRAM_ID.parse().unwrap(),
-            cloned_owner,
-            market_info.market_address,
-        )
-        .await?;
+        let open_orders = match self.open_orders_key {
+            Some(oo_key) => OpenOrders {
+                oo_key,
+                ..OpenOrders::default()
+            },
+            None if self.read_only => OpenOrders::default(),
+            None => {
+                match find_existing_open_orders_account(&rpc_client, &market_info, &pub_owner_key)
+                    .await?
+                {
+                    Some(oo_key) => OpenOrders {
+                        oo_key,
+                        ..OpenOrders::default()
+                    },
+                    None => {
+                        OpenOrders::new(
+                            rpc_client.clone(),
+                            program_id,
+                            owner.insecure_clone(),
+                            market_info.market_address,
+                        )
+                        .await?
+                    }
+                }
+            }
+        };
         let mut open_orders_cache = HashMap::new();
 
-        let open_orders_cache_entry = OpenOrdersCacheEntry {
-            open_orders: open_orders.clone(),
-            ts: cache_ts,
-        };
+        let open_orders_cache_entry = OpenOrdersCacheEntry::new(open_orders.clone());
 
         open_orders_cache.insert(pub_owner_key, open_orders_cache_entry.clone());
 
-        let mut ob_client = Self {
+        let market_info_cache = Arc::new(Mutex::new(Some((Instant::now(), market_info.clone()))));
+
+        let mut ob_client = OBClient {
             rpc_client,
             market_info,
             owner: owner.into(),
@@ -217,13 +529,25 @@ impl OBClient {
             base_ata,
             open_orders,
             open_orders_cache,
+            client_id_prefix: 0,
+            crossed_book_guard: false,
+            min_fee_balance: None,
+            market_info_cache,
+            event_queue_full_policy: EventQueueFullPolicy::default(),
+            pre_simulate: false,
+            pre_simulation_cache: Arc::new(Mutex::new(None)),
+            fee_discount_account: None,
+            fee_tier: FeeTier::Base,
+            crank_payer: None,
+            read_only: self.read_only,
+            base_position_target: 0.0,
         };
 
-        if !orders_key.is_err() {
-            ob_client.open_orders.oo_key = orders_key.unwrap();
+        if self.create_missing_accounts && !self.read_only {
+            ob_client.create_missing_token_accounts().await?;
         }
 
-        if load {
+        if self.load {
             ob_client.load_bids_asks_info().await?;
         }
 
@@ -233,281 +557,298 @@ impl OBClient {
 
         Ok(ob_client)
     }
+}
 
-    /// Loads information about bids, asks, and the open orders associated with the wallet from the market state.
+impl OBClient {
+    /// Initializes a new instance of the `OBClient` struct, representing an OpenBook V1 program client.
     ///
-    /// This function fetches and processes bids information, including extracting the bids and asks addresses
-    /// and loading the open orders. It also determines the maximum bid price and minimum ask price.
+    /// This method initializes the `OBClient` struct, containing information about the requested market id.
+    /// It fetches and stores all data about this OpenBook market. Additionally, it includes information about
+    /// the account associated with the wallet on the OpenBook market (e.g., open orders, bids, asks, etc.).
     ///
     /// # Arguments
     ///
-    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `commitment` - Commitment configuration for transactions, determining the level of finality required.
+    /// * `market_id` - Public key (ID) of the market to fetch information about.
+    /// * `load` - Boolean indicating whether to load market data immediately after initialization.
     ///
     /// # Returns
     ///
-    /// A `Result` containing a tuple of `(bids_address, asks_address, open_orders)` if successful,
-    /// or an error if loading the bids information fails.
+    /// Returns a `Result` wrapping a new instance of the `OBClient` struct initialized with the provided parameters,
+    /// or an `Error` if the initialization process fails, e.g. when `KEY_PATH` does not point to a keypair.
     ///
-    /// # Errors
+    /// # Example
     ///
-    /// This function may return an error if there is an issue with fetching accounts
-    /// or processing the bids information.
-    pub async fn load_bids_asks_info(&mut self) -> Result<(Pubkey, Pubkey, OpenOrders)> {
-        let mut account = self
-            .rpc_client
-            .inner()
-            .get_account(&self.market_info.market_address)
-            .await?;
-        let program_id_binding = self.market_info.program_id;
-        let market_account_binding = self.market_info.market_address;
-        let account_info;
-        {
-            account_info = create_account_info_from_account(
-                &mut account,
-                &market_account_binding,
-                &program_id_binding,
-                false,
-                false,
-            );
-        }
-        let market_state = MarketState::load(&account_info, &self.market_info.program_id, false)?;
-
-        let bids_address = self.market_info.bids_address;
-        let asks_address = self.market_info.asks_address;
-
-        let mut bids_account = self.rpc_client.inner().get_account(&bids_address).await?;
-        let bids_info = create_account_info_from_account(
-            &mut bids_account,
-            &bids_address,
-            &self.market_info.program_id,
-            false,
-            false,
-        );
-        let mut bids = market_state.load_bids_mut(&bids_info)?;
-        let (open_bids, open_bids_prices, max_bid) = self.process_bids(&mut bids)?;
-
-        let mut asks_account = self.rpc_client.inner().get_account(&asks_address).await?;
-        let asks_info = create_account_info_from_account(
-            &mut asks_account,
-            &asks_address,
-            &self.market_info.program_id,
-            false,
-            false,
-        );
-        let mut asks = market_state.load_asks_mut(&asks_info)?;
-        let (open_asks, open_asks_prices, min_ask) = self.process_asks(&mut asks)?;
-
-        self.open_orders = OpenOrders {
-            oo_key: self.open_orders.oo_key,
-            min_ask,
-            max_bid,
-            open_asks,
-            open_bids,
-            bids_address,
-            asks_address,
-            open_asks_prices,
-            open_bids_prices,
-            base_total: 0.,
-            quote_total: 0.,
-        };
-
-        Ok((bids_address, asks_address, self.open_orders.clone()))
-    }
-
-    /// Processes bids information to find the maximum bid price.
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
     ///
-    /// This function removes bids from the provided `Slab` to find the maximum bid price.
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
     ///
-    /// # Arguments
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    /// * `&self` - A reference to the `OBClient` struct.
-    /// * `bids` - A mutable reference to the `Slab` containing bids information.
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    /// # Returns
+    ///     println!("Initialized OBClient: {:?}", ob_client);
     ///
-    /// A `Result` containing the maximum bid price if successful, or an error if processing bids fails.
+    ///     Ok(())
+    /// }
+    /// ```
     ///
-    /// # Errors
+    /// # Business Logic
     ///
-    /// This function may return an error if there is an issue with processing the bids information.
-    pub fn process_bids(&self, bids: &mut RefMut<Slab>) -> Result<(Vec<u128>, Vec<f64>, u64)> {
-        let mut max_bid = 0;
-        let mut open_bids = Vec::new();
-        let mut open_bids_prices = Vec::new();
-        loop {
-            let node = bids.remove_max();
-            match node {
-                Some(node) => {
-                    let owner = node.owner();
-                    let bytes = u64_slice_to_pubkey(owner);
-                    let owner_address = Pubkey::from(bytes);
-
-                    let order_id = node.order_id();
-                    let price_raw = node.price().get();
-                    let ui_price = price_raw as f64 / 1e4;
-
-                    debug!("[*] Bid: {price_raw}");
-
-                    if max_bid == 0 {
-                        max_bid = price_raw;
-                    }
-
-                    if owner_address == self.open_orders.oo_key {
-                        open_bids.push(order_id);
-                        open_bids_prices.push(ui_price);
-                    }
-                }
-                None => {
-                    break;
-                }
-            }
-        }
-        Ok((open_bids, open_bids_prices, max_bid))
+    /// 1. Read the `RPC_URL`, `KEY_PATH`, `OOS_KEY` and `CREATE_MISSING_ACCOUNTS` env vars
+    ///    through [`OBClientBuilder::from_env`].
+    /// 2. Apply the given commitment, market id and `load` flag.
+    /// 3. Build the client with [`OBClientBuilder::build`].
+    ///
+    /// Use [`OBClientBuilder`] directly to configure the client without env vars.
+    ///
+    pub async fn new(
+        commitment: CommitmentConfig,
+        market_id: Pubkey,
+        load: bool,
+    ) -> Result<Self, Error> {
+        OBClientBuilder::from_env()
+            .commitment(commitment)
+            .market_id(market_id)
+            .load(load)
+            .build()
+            .await
     }
 
-    /// Processes asks information to fetch asks info.
+    /// Initializes a read-only `OBClient`, which holds no key material of the caller.
     ///
-    /// This function iteratively removes asks from the provided `Slab` until
-    /// it finds all asks.
+    /// The market state, vaults, decimals and book are loaded, but no token or open orders
+    /// account is created, and every method that signs a transaction returns
+    /// [`OpenBookError::ReadOnly`].
     ///
     /// # Arguments
     ///
-    /// * `&self` - A reference to the `OBClient` struct.
-    /// * `asks` - A mutable reference to the `Slab` containing asks information.
+    /// * `rpc_client` - The RPC client used to read the market.
+    /// * `program_id` - The OpenBook program owning the market.
+    /// * `market_id` - Public key (ID) of the market to monitor.
     ///
     /// # Returns
     ///
-    /// A `Result` containing a tuple of `(open_asks, open_asks_prices, min_ask)` if successful,
-    /// or an error if processing asks fails.
-    pub fn process_asks(&self, asks: &mut RefMut<Slab>) -> Result<(Vec<u128>, Vec<f64>, u64)> {
-        let mut min_ask = 0;
-        let mut open_asks = Vec::new();
-        let mut open_asks_prices = Vec::new();
-        loop {
-            let node = asks.remove_min();
-            match node {
-                Some(node) => {
-                    let owner = node.owner();
-                    let bytes = u64_slice_to_pubkey(owner);
-                    let owner_address = Pubkey::from(bytes);
-
-                    let order_id = node.order_id();
-                    let price_raw = node.price().get();
-                    let ui_price = price_raw as f64 / 1e4;
-
-                    debug!("[*] Ask: {price_raw}");
-
-                    if min_ask == 0 {
-                        min_ask = price_raw;
-                    }
+    /// A `Result` wrapping the read-only `OBClient`, or an error if the market cannot be loaded.
+    pub async fn new_read_only(
+        rpc_client: Rpc,
+        program_id: Pubkey,
+        market_id: Pubkey,
+    ) -> Result<Self> {
+        OBClientBuilder::new()
+            .rpc_client(rpc_client)
+            .program_id(program_id)
+            .market_id(market_id)
+            .read_only(true)
+            .load(true)
+            .build()
+            .await
+    }
 
-                    if owner_address == self.open_orders.oo_key {
-                        open_asks.push(order_id);
-                        open_asks_prices.push(ui_price);
-                    }
-                }
-                None => {
-                    break;
-                }
-            }
+    /// Returns [`OpenBookError::ReadOnly`] if the client cannot sign transactions.
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(OpenBookError::ReadOnly.into());
         }
-        Ok((open_asks, open_asks_prices, min_ask))
+        Ok(())
     }
 
-    /// Loads the open bids from the market.
+    /// Sets the commitment used by the client's account fetches and transaction sends.
     ///
-    /// # Returns
+    /// The RPC connection is shared with the original client, so a clone can read at
+    /// `processed` while the original keeps settling at `confirmed` or `finalized`.
     ///
-    /// The open bids stored in the market.
+    /// # Arguments
     ///
-    /// # Errors
+    /// * `self` - The `OBClient` to configure.
+    /// * `commitment` - The commitment level to use.
     ///
-    /// Returns an error if there is an issue with loading the bids.
+    /// # Returns
     ///
-    /// # Examples
+    /// The `OBClient` using the given commitment.
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.rpc_client = self.rpc_client.with_commitment(commitment);
+        self
+    }
+
+    /// Sets whether the client's transactions skip the preflight simulation.
     ///
-    /// ```rust
-    /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// Preflight runs by default, so simulation errors such as insufficient funds or bad
+    /// accounts are returned right away. The RPC connection is shared with the original client,
+    /// so a clone can override the setting for a single call:
+    /// `ob_client.clone().with_skip_preflight(true).cancel_orders(true)`.
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let commitment = CommitmentConfig::confirmed();
+    /// # Arguments
     ///
-    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    /// * `self` - The `OBClient` to configure.
+    /// * `skip_preflight` - Whether to skip the preflight simulation.
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    /// # Returns
     ///
-    ///     let result = ob_client.load_bids()?;
+    /// The `OBClient` using the given preflight setting.
+    pub fn with_skip_preflight(mut self, skip_preflight: bool) -> Self {
+        let send_config = SendConfig {
+            skip_preflight,
+            ..*self.rpc_client.send_config()
+        };
+        self.rpc_client = self.rpc_client.with_send_config(send_config);
+        self
+    }
+
+    /// Points the client at another open orders account of the owner on this market.
     ///
-    ///     println!("{:?}", result);
+    /// The cached book and open orders state are kept; reload them to see the account's orders.
     ///
-    ///     Ok(())
-    /// }
-    /// ```
-    pub fn load_bids(&mut self) -> Result<Vec<u128>, ProgramError> {
-        Ok(self.open_orders.open_bids.clone())
+    /// # Arguments
+    ///
+    /// * `self` - The `OBClient` to configure.
+    /// * `oo_key` - The open orders account to trade from.
+    ///
+    /// # Returns
+    ///
+    /// The `OBClient` trading from `oo_key`.
+    pub fn with_open_orders_account(mut self, oo_key: Pubkey) -> Self {
+        self.open_orders.oo_key = oo_key;
+        if let Some(entry) = self.open_orders_cache.get_mut(&self.owner.pubkey()) {
+            entry.open_orders.oo_key = oo_key;
+        }
+        self
     }
 
-    /// Loads the open asks from the market.
+    /// Returns the account holding the base tokens.
+    ///
+    /// Unless it was set explicitly, the owner's associated token account for the base mint is
+    /// derived on the first call and reused afterwards.
+    pub fn base_ata(&self) -> Pubkey {
+        *self.base_ata.get_or_init(|| {
+            get_associated_token_address(&self.owner.pubkey(), &self.market_info.base_mint)
+        })
+    }
+
+    /// Returns the account holding the quote tokens.
+    ///
+    /// Unless it was set explicitly, the owner's associated token account for the quote mint is
+    /// derived on the first call and reused afterwards.
+    pub fn quote_ata(&self) -> Pubkey {
+        *self.quote_ata.get_or_init(|| {
+            get_associated_token_address(&self.owner.pubkey(), &self.market_info.quote_mint)
+        })
+    }
+
+    /// Creates a new open orders account of the owner on this market.
+    ///
+    /// The client keeps trading from its current account; use `with_open_orders_account` to
+    /// switch to the new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
     ///
     /// # Returns
     ///
-    /// The open asks stored in the market.
+    /// A `Result` containing the address of the new account and the signature of the
+    /// transaction creating it.
     ///
     /// # Errors
     ///
-    /// Returns an error if there is an issue with loading the asks.
-    ///
-    /// # Examples
+    /// Returns an error if the rent cannot be fetched or the transaction is not confirmed.
+    pub async fn create_open_orders_account(&self) -> Result<(Pubkey, Signature)> {
+        self.ensure_writable()?;
+        let account = Keypair::new();
+        let space = open_orders_account_space(OPEN_ORDERS_SLOTS)?;
+        let lamports = self.rpc_client.fetch_rent_exemption(space).await?;
+
+        let instructions = vec![
+            solana_sdk::system_instruction::create_account(
+                &self.owner.pubkey(),
+                &account.pubkey(),
+                lamports,
+                space as u64,
+                &self.market_info.program_id,
+            ),
+            openbook_dex::instruction::init_open_orders(
+                &self.market_info.program_id,
+                &account.pubkey(),
+                &self.owner.pubkey(),
+                &self.market_info.market_address,
+                None,
+            )?,
+        ];
+        let (confirmed, signature) = self
+            .rpc_client
+            .send_and_confirm_with_signers(&[&self.owner, &account], instructions)
+            .await?;
+        if !confirmed {
+            anyhow::bail!(
+                "transaction creating open orders account {} was not confirmed",
+                account.pubkey()
+            );
+        }
+
+        Ok((account.pubkey(), signature))
+    }
+
+    /// Loads information about bids, asks, and the open orders associated with the wallet from the market state.
     ///
-    /// ```rust
-    /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// This function fetches and processes bids information, including extracting the bids and asks addresses
+    /// and loading the open orders. It also determines the maximum bid price and minimum ask price.
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let commitment = CommitmentConfig::confirmed();
+    /// # Arguments
     ///
-    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    /// # Returns
     ///
-    ///     let result = ob_client.load_asks()?;
+    /// A `Result` containing a tuple of `(bids_address, asks_address, open_orders)` if successful,
+    /// or an error if loading the bids information fails.
     ///
-    ///     println!("{:?}", result);
+    /// # Errors
     ///
-    ///     Ok(())
-    /// }
-    /// ```
-    pub fn load_asks(&mut self) -> Result<Vec<u128>, ProgramError> {
-        Ok(self.open_orders.open_asks.clone())
+    /// This function may return an error if there is an issue with fetching accounts
+    /// or processing the bids information.
+    pub async fn load_bids_asks_info(&mut self) -> Result<(Pubkey, Pubkey, OpenOrders)> {
+        let bids_address = self.market_info.bids_address;
+        let asks_address = self.market_info.asks_address;
+
+        // Read the market, bids and asks together, never going back before the last loaded slot,
+        // so that the book is not assembled from accounts observed at different slots.
+        let min_context_slot = Some(self.open_orders.slot).filter(|slot| *slot > 0);
+        let (slot, accounts) = self
+            .rpc_client
+            .fetch_multiple_accounts_at_slot(
+                &[self.market_info.market_address, bids_address, asks_address],
+                min_context_slot,
+            )
+            .await?;
+        let [market_account, bids_account, asks_account] = market_accounts(accounts)?;
+
+        self.open_orders = self.parse_book(slot, market_account, bids_account, asks_account)?;
+
+        Ok((bids_address, asks_address, self.open_orders.clone()))
     }
-    /// Places a limit order on the market.
+
+    /// Reads a consistent snapshot of the book and the event queue.
+    ///
+    /// The market, bids, asks and event queue are read in a single request, so they are all
+    /// observed at the same slot, which is never older than `slot` when given.
     ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
-    /// * `target_amount_quote` - The target amount in quote currency for the order.
-    /// * `side` - The side of the order (buy or sell).
-    /// * `best_offset_usdc` - The best offset in USDC for the order.
-    /// * `execute` - A boolean indicating whether to execute the order immediately.
-    /// * `target_price` - The target price for the order.
+    /// * `slot` - Optional. The minimum slot the snapshot must be observed at.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the transaction signature if successful,
-    /// or an error if placing the limit order fails.
-    ///
-    /// # Errors
-    ///
-    /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// A `Result` containing the book, whose `slot` field is the snapshot slot, and the
+    /// unconsumed events of the event queue.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::matching::Side;
-    /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
     ///
@@ -517,250 +858,265 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let target_amount_quote = 5.0;
-    ///     let side = Side::Bid; // or Side::Ask
-    ///     let best_offset_usdc = 5.0;
-    ///     let execute = true;
-    ///     let target_price = 2.1;
+    ///     let (book, events) = ob_client.snapshot_at_slot(None).await?;
     ///
-    ///     if let Some(ord_ret_type) = ob_client.place_limit_order(
-    ///         target_amount_quote,
-    ///         side,
-    ///         best_offset_usdc,
-    ///         execute,
-    ///         target_price
-    ///     ).await?
-    ///     {
-    ///         match ord_ret_type {
-    ///             OrderReturnType::Instructions(insts) => {
-    ///                 println!("[*] Got Instructions: {:?}", insts);
-    ///             }
-    ///             OrderReturnType::Signature(sign) => {
-    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
-    ///             }
-    ///         }
-    ///     }
+    ///     println!("Book at slot {} with {} pending events", book.slot, events.len());
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn place_limit_order(
-        &self,
-        target_amount_quote: f64,
-        side: Side,
-        best_offset_usdc: f64,
-        execute: bool,
-        target_price: f64,
-    ) -> Result<Option<OrderReturnType>, Error> {
-        // coin: base
-        // pc: quote
-        let base_d_factor = 10u32.pow(self.market_info.coin_decimals as u32) as f64;
-        let quote_d_factor = 10u32.pow(self.market_info.pc_decimals as u32) as f64;
-        let base_lot_factor = self.market_info.coin_lot_size as f64;
-        let quote_lot_factor = self.market_info.pc_lot_size as f64;
-
-        let price_factor = quote_d_factor * base_lot_factor / base_d_factor / quote_lot_factor;
-
-        let (input_ata, price) = match side {
-            Side::Bid => {
-                let mut price = self.open_orders.max_bid as f64 / price_factor - best_offset_usdc;
-                if execute {
-                    price = target_price;
-                }
-
-                (&self.quote_ata, price)
-            }
-            Side::Ask => {
-                let mut price = self.open_orders.min_ask as f64 / price_factor + best_offset_usdc;
-                if execute {
-                    price = target_price;
-                }
-
-                (&self.base_ata, price)
-            }
-        };
+    pub async fn snapshot_at_slot(&self, slot: Option<u64>) -> Result<(OpenOrders, Vec<Event>)> {
+        let (context_slot, accounts) = self
+            .rpc_client
+            .fetch_multiple_accounts_at_slot(
+                &[
+                    self.market_info.market_address,
+                    self.market_info.bids_address,
+                    self.market_info.asks_address,
+                    self.market_info.event_queue,
+                ],
+                slot,
+            )
+            .await?;
+        let [market_account, bids_account, asks_account, event_queue_account] =
+            market_accounts(accounts)?;
 
-        let limit_price_lots = (price * price_factor) as u64;
-        let target_amount_base = target_amount_quote / price;
+        let book = self.parse_book(context_slot, market_account, bids_account, asks_account)?;
+        let events = EventQueue::parse(&event_queue_account.data)?.events;
 
-        let target_base_lots = (target_amount_base * base_d_factor / base_lot_factor) as u64;
-        let target_quote_lots_w_fee =
-            (target_base_lots as f64 * quote_lot_factor * limit_price_lots as f64) as u64;
+        Ok((book, events))
+    }
 
-        debug!("[*] Using limit price lots: {:?}", limit_price_lots);
-        debug!("[*] Using target base lots: {:?}", target_base_lots);
+    /// Decodes the book from the market, bids and asks accounts read at `slot`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `slot` - The context slot the accounts were read at.
+    /// * `market_account` - The market account.
+    /// * `bids_account` - The bids account of the market.
+    /// * `asks_account` - The asks account of the market.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the decoded `OpenOrders` book.
+    pub fn parse_book(
+        &self,
+        slot: u64,
+        mut market_account: Account,
+        mut bids_account: Account,
+        mut asks_account: Account,
+    ) -> Result<OpenOrders> {
+        let bids_address = self.market_info.bids_address;
+        let asks_address = self.market_info.asks_address;
 
-        if target_base_lots == 0 {
-            debug!(
-                "[*] Got zero base lots, and quote: {:?}",
-                target_amount_quote
+        let program_id_binding = self.market_info.program_id;
+        let market_account_binding = self.market_info.market_address;
+        let account_info;
+        {
+            account_info = create_account_info_from_account(
+                &mut market_account,
+                &market_account_binding,
+                &program_id_binding,
+                false,
+                false,
             );
-            return Ok(None);
         }
+        let market_state = MarketState::load(&account_info, &self.market_info.program_id, false)?;
 
-        let limit_price = NonZeroU64::new(limit_price_lots).unwrap();
-        let max_coin_qty = NonZeroU64::new(target_base_lots).unwrap();
-        let max_native_pc_qty_including_fees = NonZeroU64::new(target_quote_lots_w_fee).unwrap();
-
-        let place_order_ix = openbook_dex::instruction::new_order(
-            &self.market_info.market_address,
-            &self.open_orders.oo_key,
-            &self.market_info.request_queue,
-            &self.market_info.event_queue,
-            &self.market_info.bids_address,
-            &self.market_info.asks_address,
-            input_ata,
-            &self.owner.pubkey(),
-            &self.market_info.coin_vault,
-            &self.market_info.pc_vault,
-            &SPL_TOKEN_ID.parse()?,
-            &rent::ID,
-            None,
+        // The bids and asks slabs are drained in place by `process_bids` / `process_asks`, so
+        // they are loaded through writable account infos.
+        let bids_info = create_account_info_from_account(
+            &mut bids_account,
+            &bids_address,
             &self.market_info.program_id,
-            side,
-            limit_price,
-            max_coin_qty,
-            OrderType::PostOnly,
-            random::<u64>(),
-            SelfTradeBehavior::AbortTransaction,
-            u16::MAX,
-            max_native_pc_qty_including_fees,
-            (get_unix_secs() + 30) as i64,
-        )?;
-
-        let instructions = vec![place_order_ix];
-
-        if !execute {
-            return Ok(Some(OrderReturnType::Instructions(instructions)));
-        }
+            false,
+            true,
+        );
+        let mut bids = market_state.load_bids_mut(&bids_info)?;
+        let (open_bids, open_bids_prices, max_bid, bids) = self.process_bids(&mut bids)?;
 
-        let (_, signature) = self
-            .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await?;
+        let asks_info = create_account_info_from_account(
+            &mut asks_account,
+            &asks_address,
+            &self.market_info.program_id,
+            false,
+            true,
+        );
+        let mut asks = market_state.load_asks_mut(&asks_info)?;
+        let (open_asks, open_asks_prices, min_ask, asks) = self.process_asks(&mut asks)?;
 
-        Ok(Some(OrderReturnType::Signature(signature)))
+        Ok(OpenOrders {
+            oo_key: self.open_orders.oo_key,
+            min_ask,
+            max_bid,
+            open_asks,
+            open_bids,
+            bids_address,
+            asks_address,
+            open_asks_prices,
+            open_bids_prices,
+            base_total: 0.,
+            quote_total: 0.,
+            bids,
+            asks,
+            slot,
+            order_slots: self.open_orders.order_slots.clone(),
+        })
     }
 
-    /// Cancels all limit orders in the market.
+    /// Processes bids information to find the maximum bid price.
+    ///
+    /// This function removes bids from the provided `Slab` to find the maximum bid price.
     ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
-    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `bids` - A mutable reference to the `Slab` containing bids information.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the transaction signature if successful,
-    /// or an error if canceling all orders fails.
+    /// A `Result` containing a tuple of `(open_bids, open_bids_prices, max_bid, bids)` if successful,
+    /// where `bids` is the full bids ladder, or an error if processing bids fails.
     ///
     /// # Errors
     ///
-    /// This function may return an error if there is an issue with creating or sending the transaction.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// use openbook::v1::orders::OrderReturnType;
-    /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// This function may return an error if there is an issue with processing the bids information.
+    pub fn process_bids(
+        &self,
+        bids: &mut RefMut<Slab>,
+    ) -> Result<(Vec<u128>, Vec<f64>, u64, Vec<Order>)> {
+        let ladder = drain_slab(bids, Side::Bid);
+        let max_bid = ladder.first().map(|order| order.price).unwrap_or(0);
+
+        let mut open_bids = Vec::new();
+        let mut open_bids_prices = Vec::new();
+        for order in ladder
+            .iter()
+            .filter(|order| order.owner == self.open_orders.oo_key)
+        {
+            debug!("[*] Bid: {}", order.price);
+            open_bids.push(order.order_id);
+            open_bids_prices.push(self.market_info.price_lots_to_number(order.price));
+        }
+
+        Ok((open_bids, open_bids_prices, max_bid, ladder))
+    }
+
+    /// Processes asks information to fetch asks info.
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let commitment = CommitmentConfig::confirmed();
+    /// This function iteratively removes asks from the provided `Slab` until
+    /// it finds all asks.
     ///
-    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    /// # Arguments
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `asks` - A mutable reference to the `Slab` containing asks information.
     ///
-    ///     if let Some(ord_ret_type) = ob_client
-    ///         .cancel_orders(true)
-    ///         .await?
-    ///     {
-    ///         match ord_ret_type {
-    ///             OrderReturnType::Instructions(insts) => {
-    ///                 println!("[*] Got Instructions: {:?}", insts);
-    ///             }
-    ///             OrderReturnType::Signature(sign) => {
-    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
-    ///             }
-    ///         }
-    ///     }
+    /// # Returns
     ///
-    ///     Ok(())
-    /// }
-    /// ```
-    pub async fn cancel_orders(&self, execute: bool) -> Result<Option<OrderReturnType>, Error> {
-        let mut ixs = Vec::new();
-
-        for oid in &self.open_orders.open_bids {
-            let ix = openbook_dex::instruction::cancel_order(
-                &self.market_info.program_id,
-                &self.market_info.market_address,
-                &self.market_info.bids_address,
-                &self.market_info.asks_address,
-                &self.open_orders.oo_key,
-                &self.owner.pubkey(),
-                &self.market_info.event_queue,
-                Side::Bid,
-                *oid,
-            )?;
-            ixs.push(ix);
-        }
-
-        for oid in &self.open_orders.open_asks {
-            let ix = openbook_dex::instruction::cancel_order(
-                &self.market_info.program_id,
-                &self.market_info.market_address,
-                &self.market_info.bids_address,
-                &self.market_info.asks_address,
-                &self.open_orders.oo_key,
-                &self.owner.pubkey(),
-                &self.market_info.event_queue,
-                Side::Ask,
-                *oid,
-            )?;
-            ixs.push(ix);
-        }
+    /// A `Result` containing a tuple of `(open_asks, open_asks_prices, min_ask, asks)` if successful,
+    /// where `asks` is the full asks ladder,
+    /// or an error if processing asks fails.
+    pub fn process_asks(
+        &self,
+        asks: &mut RefMut<Slab>,
+    ) -> Result<(Vec<u128>, Vec<f64>, u64, Vec<Order>)> {
+        let ladder = drain_slab(asks, Side::Ask);
+        let min_ask = ladder.first().map(|order| order.price).unwrap_or(0);
 
-        if ixs.is_empty() {
-            return Ok(None);
+        let mut open_asks = Vec::new();
+        let mut open_asks_prices = Vec::new();
+        for order in ladder
+            .iter()
+            .filter(|order| order.owner == self.open_orders.oo_key)
+        {
+            debug!("[*] Ask: {}", order.price);
+            open_asks.push(order.order_id);
+            open_asks_prices.push(self.market_info.price_lots_to_number(order.price));
         }
 
-        if !execute {
-            return Ok(Some(OrderReturnType::Instructions(ixs)));
-        }
+        Ok((open_asks, open_asks_prices, min_ask, ladder))
+    }
 
-        let (_, signature) = self
+    /// Verifies that the cached bids and asks addresses match the on-chain market state.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::BookAddressMismatch` if `market_info` holds a wrong bids or asks
+    /// address, or an error if the market account cannot be fetched or loaded.
+    pub async fn verify_book_addresses(&self) -> Result<()> {
+        let mut account = self
             .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), ixs)
+            .fetch_account(&self.market_info.market_address)
             .await?;
+        let account_info = create_account_info_from_account(
+            &mut account,
+            &self.market_info.market_address,
+            &self.market_info.program_id,
+            false,
+            false,
+        );
 
-        Ok(Some(OrderReturnType::Signature(signature)))
+        self.market_info.check_book_addresses(&account_info)
     }
 
-    /// Settles the balance for a user in the market.
+    /// Loads the bids and asks and returns the top `depth` price levels of each side.
     ///
     /// # Arguments
     ///
-    /// * `&self` - A reference to the `OBClient` struct.
-    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `depth` - The maximum number of price levels returned per side.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the transaction signature if successful,
-    /// or an error if settling the balance fails.
+    /// A `Result` containing the `(price, size)` levels in UI units, best price first.
     ///
     /// # Errors
     ///
-    /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// This function returns an error if loading the bids and asks fails.
+    pub async fn get_order_book(&mut self, depth: usize) -> Result<OrderBook> {
+        self.load_bids_asks_info().await?;
+
+        Ok(self.open_orders.order_book(&self.market_info, depth))
+    }
+
+    /// Computes the balances locked by the owner's resting orders in the last loaded book.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(base_locked, quote_locked)` in native units: the size of the resting asks and
+    /// the notional of the resting bids.
+    pub fn locked_balances(&self) -> (u64, u64) {
+        self.open_orders
+            .locked_balances(&self.open_orders.oo_key, &self.market_info)
+    }
+
+    /// Computes the queue position of a resting order from the last loaded book.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `order_id` - The id of the resting order.
+    ///
+    /// # Returns
+    ///
+    /// The size and number of orders ahead of the order at its price level, or `None`
+    /// if the order is not resting on the book.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
     ///
@@ -770,72 +1126,67 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     if let Some(ord_ret_type) = ob_client
-    ///         .settle_balance(true)
-    ///         .await?
-    ///     {
-    ///         match ord_ret_type {
-    ///             OrderReturnType::Instructions(insts) => {
-    ///                 println!("[*] Got Instructions: {:?}", insts);
-    ///             }
-    ///             OrderReturnType::Signature(sign) => {
-    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
-    ///             }
-    ///         }
+    ///     for order_id in &ob_client.open_orders.open_bids {
+    ///         println!("{:?}", ob_client.queue_position(*order_id));
     ///     }
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn settle_balance(&self, execute: bool) -> Result<Option<OrderReturnType>, Error> {
-        let ix = openbook_dex::instruction::settle_funds(
-            &self.market_info.program_id,
-            &self.market_info.market_address,
-            &SPL_TOKEN_ID.parse()?,
-            &self.open_orders.oo_key,
-            &self.owner.pubkey(),
-            &self.market_info.coin_vault,
-            &self.base_ata,
-            &self.market_info.pc_vault,
-            &self.quote_ata,
-            None,
-            &self.market_info.vault_signer_key,
-        )?;
-
-        let instructions = vec![ix];
-
-        if !execute {
-            return Ok(Some(OrderReturnType::Instructions(instructions)));
-        }
-
-        let (_, signature) = self
-            .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await?;
-
-        Ok(Some(OrderReturnType::Signature(signature)))
+    pub fn queue_position(&self, order_id: u128) -> Option<QueuePosition> {
+        self.open_orders.queue_position(order_id, &self.market_info)
     }
 
-    /// Creates a new transaction to match orders in the market.
+    /// Loads the open bids from the market.
     ///
-    /// # Arguments
+    /// # Returns
     ///
-    /// * `limit` - The maximum number of orders to match.
+    /// The open bids stored in the market.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is an issue with loading the bids.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let result = ob_client.load_bids()?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn load_bids(&mut self) -> Result<Vec<u128>, ProgramError> {
+        Ok(self.open_orders.open_bids.clone())
+    }
+
+    /// Loads the open asks from the market.
     ///
     /// # Returns
     ///
-    /// A transaction for matching orders.
+    /// The open asks stored in the market.
     ///
     /// # Errors
     ///
-    /// Returns an error if there is an issue with transaction creation or sending.
+    /// Returns an error if there is an issue with loading the asks.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
     ///
@@ -845,43 +1196,3425 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let result = ob_client.match_orders_transaction(100).await?;
+    ///     let result = ob_client.load_asks()?;
     ///
     ///     println!("{:?}", result);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn match_orders_transaction(&self, limit: u16) -> Result<(bool, Signature)> {
-        let ix = openbook_dex::instruction::match_orders(
-            &self.market_info.program_id,
-            &self.market_info.market_address,
-            &self.market_info.request_queue,
-            &self.market_info.bids_address,
-            &self.market_info.asks_address,
-            &self.market_info.event_queue,
-            &self.market_info.coin_vault,
-            &self.market_info.pc_vault,
-            limit,
-        )
-        .unwrap();
+    pub fn load_asks(&mut self) -> Result<Vec<u128>, ProgramError> {
+        Ok(self.open_orders.open_asks.clone())
+    }
+    /// Places a limit order on the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `best_offset_usdc` - The best offset in USDC for the order.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `target_price` - The target price for the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature if successful,
+    /// or an error if placing the limit order fails.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let target_amount_quote = 5.0;
+    ///     let side = Side::Bid; // or Side::Ask
+    ///     let best_offset_usdc = 5.0;
+    ///     let execute = true;
+    ///     let target_price = 2.1;
+    ///
+    ///     if let Some(ord_ret_type) = ob_client.place_limit_order(
+    ///         target_amount_quote,
+    ///         side,
+    ///         best_offset_usdc,
+    ///         execute,
+    ///         target_price
+    ///     ).await?
+    ///     {
+    ///         match ord_ret_type {
+    ///             OrderReturnType::Instructions(insts) => {
+    ///                 println!("[*] Got Instructions: {:?}", insts);
+    ///             }
+    ///             OrderReturnType::Signature(sign) => {
+    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        best_offset_usdc: f64,
+        execute: bool,
+        target_price: f64,
+    ) -> Result<Option<OrderReturnType>, Error> {
+        self.ensure_writable()?;
+
+        let price = match side {
+            Side::Bid => {
+                let mut price = self
+                    .market_info
+                    .price_lots_to_number(self.open_orders.max_bid)
+                    - best_offset_usdc;
+                if execute {
+                    price = target_price;
+                }
+
+                price
+            }
+            Side::Ask => {
+                let mut price = self
+                    .market_info
+                    .price_lots_to_number(self.open_orders.min_ask)
+                    + best_offset_usdc;
+                if execute {
+                    price = target_price;
+                }
+
+                price
+            }
+        };
+
+        let place_order_ix = match self.new_order_instruction(
+            side,
+            price,
+            target_amount_quote,
+            OrderType::PostOnly,
+        )? {
+            Some(ix) => ix,
+            None => return Ok(None),
+        };
+
+        let instructions = vec![place_order_ix];
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(instructions)));
+        }
+
+        let (_, signature) = self.send_order_instructions(instructions).await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Checks that the signer holds at least `needed` lamports before a transaction is sent.
+    ///
+    /// `needed` should cover the transaction fees plus any rent paid on first use, such as
+    /// creating the associated token accounts or the open orders account. When
+    /// `min_fee_balance` is set, every order placement runs this check before sending.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `needed` - The minimum balance required, in lamports.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InsufficientSol` if the signer's balance is below `needed`, or
+    /// an error if the balance could not be fetched.
+    pub async fn ensure_fee_balance(&self, needed: u64) -> Result<()> {
+        let have = self.rpc_client.fetch_balance(&self.owner.pubkey()).await?;
+
+        if have < needed {
+            return Err(OpenBookError::InsufficientSol { have, need: needed }.into());
+        }
+
+        Ok(())
+    }
+
+    /// Runs the checks shared by every order entry point on a transaction placing orders.
+    ///
+    /// In order: the cached book must not be crossed when `crossed_book_guard` is set, the signer
+    /// must hold `min_fee_balance` lamports when it is set, the event queue full policy is applied,
+    /// and the instructions are pre-simulated when `pre_simulate` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `instructions` - The instructions of the transaction, including its new orders.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failing check.
+    async fn check_order_instructions(&self, instructions: &[Instruction]) -> Result<()> {
+        if self.crossed_book_guard {
+            self.open_orders.ensure_book_not_crossed()?;
+        }
+        if let Some(needed) = self.min_fee_balance {
+            self.ensure_fee_balance(needed).await?;
+        }
+        self.handle_full_event_queue().await?;
+        if self.pre_simulate {
+            self.pre_simulate_order(instructions).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a transaction placing orders, after the checks of `check_order_instructions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `instructions` - The instructions of the transaction, including its new orders.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the transaction was confirmed and its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failing check, or an error if sending fails.
+    async fn send_order_instructions(
+        &self,
+        instructions: Vec<Instruction>,
+    ) -> Result<(bool, Signature)> {
+        self.check_order_instructions(&instructions).await?;
+
+        self.rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .await
+    }
+
+    /// Sends a taker order after the checks of `check_order_instructions` and reconstructs what
+    /// it filled.
+    ///
+    /// The open orders balances are read before sending, so that `taker_result` can account for
+    /// funds the transaction leaves unsettled.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `instructions` - The instructions of the transaction, including the taker order.
+    /// * `side` - The side of the taker order.
+    /// * `requested_base` - The base quantity requested by the order, in UI units.
+    /// * `client_order_id` - The client order id attached to the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the placed order and its `TakerResult`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failing check, or an error if sending fails, the
+    /// transaction is not confirmed, or its outcome cannot be read back.
+    async fn send_taker_order(
+        &self,
+        instructions: Vec<Instruction>,
+        side: Side,
+        requested_base: f64,
+        client_order_id: u64,
+    ) -> Result<TakerOrder> {
+        self.check_order_instructions(&instructions).await?;
+        let before = self.load_balances().await?;
+
+        let (confirmed, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .await?;
+        if !confirmed {
+            anyhow::bail!("taker order transaction {} was not confirmed", signature);
+        }
+        let result = self
+            .taker_result(&signature, side, requested_base, &before)
+            .await?;
+
+        Ok(TakerOrder {
+            placed: PlacedOrder {
+                signature,
+                client_order_id,
+            },
+            result,
+        })
+    }
+
+    /// Returns the slot at which the cached bids and asks were loaded.
+    pub fn book_slot(&self) -> u64 {
+        self.open_orders.slot
+    }
+
+    /// Computes how many slots the cached book lags behind the cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the current cluster slot minus `book_slot`, or zero if the book is
+    /// ahead of the node that answered.
+    pub async fn book_slot_lag(&self) -> Result<u64> {
+        let current_slot = self.rpc_client.fetch_slot().await?;
+
+        Ok(current_slot.saturating_sub(self.book_slot()))
+    }
+
+    /// Places an order from a price and size already expressed in lots, with every order
+    /// parameter chosen by the caller.
+    ///
+    /// Bids are paid from `quote_ata` with the quote ceiling of `max_native_pc_qty`, asks from
+    /// `base_ata`. `client_order_id` is sent as is, so callers can track or cancel the order by it.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `limit_price` - The limit price of the order, in lots.
+    /// * `max_coin_qty` - The size of the order, in base lots.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    /// * `self_trade` - What happens when the order would match one of the owner's orders.
+    /// * `client_order_id` - The client order id attached to the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction and the client order id of the
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `limit_price` or `max_coin_qty` is zero, or if sending fails.
+    pub async fn place_order(
+        &self,
+        side: Side,
+        limit_price: u64,
+        max_coin_qty: u64,
+        order_type: OrderType,
+        self_trade: SelfTradeBehavior,
+        client_order_id: u64,
+    ) -> Result<PlacedOrder> {
+        self.ensure_writable()?;
+        let ix = self
+            .new_order_instruction_raw(
+                side,
+                limit_price,
+                max_coin_qty,
+                self.max_native_pc_qty(order_type, limit_price, max_coin_qty),
+                order_type,
+                self_trade,
+                client_order_id,
+            )?
+            .ok_or_else(|| anyhow::anyhow!("order price and quantity must be non-zero"))?;
+        let (_, signature) = self.send_order_instructions(vec![ix]).await?;
+
+        Ok(PlacedOrder {
+            signature,
+            client_order_id,
+        })
+    }
+
+    /// Builds the instructions of `place_and_settle`: a `new_order` followed by a
+    /// `settle_funds` to the owner's `base_ata` and `quote_ata`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `limit_price` - The limit price of the order, in lots.
+    /// * `max_coin_qty` - The size of the order, in base lots.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    /// * `self_trade` - What happens when the order would match one of the owner's orders.
+    /// * `client_order_id` - The client order id attached to the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the order and settle instructions, in that order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `limit_price` or `max_coin_qty` is zero.
+    pub fn place_and_settle_instructions(
+        &self,
+        side: Side,
+        limit_price: u64,
+        max_coin_qty: u64,
+        order_type: OrderType,
+        self_trade: SelfTradeBehavior,
+        client_order_id: u64,
+    ) -> Result<Vec<Instruction>> {
+        let ix = self
+            .new_order_instruction_raw(
+                side,
+                limit_price,
+                max_coin_qty,
+                self.max_native_pc_qty(order_type, limit_price, max_coin_qty),
+                order_type,
+                self_trade,
+                client_order_id,
+            )?
+            .ok_or_else(|| anyhow::anyhow!("order price and quantity must be non-zero"))?;
+
+        Ok(vec![ix, self.settle_funds_instruction()?])
+    }
+
+    /// Places an order and settles the owner's free funds in the same transaction.
+    ///
+    /// Whatever the order takes from the book is credited to `base_ata` or `quote_ata` right
+    /// away, instead of sitting in the open orders account until `settle_balance` is called.
+    /// This is meant for immediate-or-cancel orders. For maker orders the settle moves nothing
+    /// until a fill occurs, so their later fills still need a `settle_balance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `limit_price` - The limit price of the order, in lots.
+    /// * `max_coin_qty` - The size of the order, in base lots.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    /// * `self_trade` - What happens when the order would match one of the owner's orders.
+    /// * `client_order_id` - The client order id attached to the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction and the client order id of the
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `limit_price` or `max_coin_qty` is zero, or if sending fails.
+    pub async fn place_and_settle(
+        &self,
+        side: Side,
+        limit_price: u64,
+        max_coin_qty: u64,
+        order_type: OrderType,
+        self_trade: SelfTradeBehavior,
+        client_order_id: u64,
+    ) -> Result<PlacedOrder> {
+        self.ensure_writable()?;
+        let instructions = self.place_and_settle_instructions(
+            side,
+            limit_price,
+            max_coin_qty,
+            order_type,
+            self_trade,
+            client_order_id,
+        )?;
+        let (_, signature) = self.send_order_instructions(instructions).await?;
+
+        Ok(PlacedOrder {
+            signature,
+            client_order_id,
+        })
+    }
+
+    /// Places a post only bid from a price and size already expressed in lots.
+    ///
+    /// The order is paid from `quote_ata` and aborts the transaction on a self trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `max_bid` - The limit price of the bid, in lots.
+    /// * `coin_qty` - The size of the bid, in base lots.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction and the client order id of the
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `max_bid` or `coin_qty` is zero, or if sending fails.
+    pub async fn place_limit_bid(&self, max_bid: u64, coin_qty: u64) -> Result<PlacedOrder> {
+        self.place_order(
+            Side::Bid,
+            max_bid,
+            coin_qty,
+            OrderType::PostOnly,
+            SelfTradeBehavior::AbortTransaction,
+            self.gen_client_order_id(),
+        )
+        .await
+    }
+
+    /// Places a post only ask from a price and size already expressed in lots.
+    ///
+    /// The order is paid from `base_ata`, the owner's base token account: an ask sells base
+    /// tokens, so the quote account (`quote_ata`) is never debited. `coin_qty` is used as the
+    /// order's `max_coin_qty` as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `min_ask` - The limit price of the ask, in lots.
+    /// * `coin_qty` - The size of the ask, in base lots.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction and the client order id of the
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min_ask` or `coin_qty` is zero, or if sending fails.
+    pub async fn place_limit_ask(&self, min_ask: u64, coin_qty: u64) -> Result<PlacedOrder> {
+        self.place_order(
+            Side::Ask,
+            min_ask,
+            coin_qty,
+            OrderType::PostOnly,
+            SelfTradeBehavior::AbortTransaction,
+            self.gen_client_order_id(),
+        )
+        .await
+    }
+
+    /// Returns `true` if an order at `price` on `side` would cross one of the owner's resting
+    /// orders on the opposite side of the cached book, triggering the self-trade behavior.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order about to be placed.
+    /// * `price` - The limit price of the order about to be placed, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a bid would reach the owner's best ask or an ask would reach the owner's best bid.
+    pub fn would_self_cross(&self, side: Side, price: f64) -> bool {
+        let price_lots = self.market_info.price_number_to_lots(price);
+        let opposite = match side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let resting = self
+            .open_orders
+            .own_orders(opposite, &self.open_orders.oo_key)
+            .into_iter()
+            .map(|order| order.price);
+
+        match side {
+            Side::Bid => resting.min().is_some_and(|best_ask| price_lots >= best_ask),
+            Side::Ask => resting.max().is_some_and(|best_bid| price_lots <= best_bid),
+        }
+    }
+
+    /// Lists the owner's resting orders on one side of the cached book, grouped by price level.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the book to aggregate.
+    ///
+    /// # Returns
+    ///
+    /// One `(price, total_size, count)` tuple per price level, with price and size in UI units,
+    /// ordered from the best level outwards.
+    pub fn my_orders_by_level(&self, side: Side) -> Vec<(f64, f64, usize)> {
+        let mut levels: BTreeMap<u64, (u64, usize)> = BTreeMap::new();
+        for order in self.open_orders.own_orders(side, &self.open_orders.oo_key) {
+            let level = levels.entry(order.price).or_insert((0, 0));
+            level.0 += order.quantity;
+            level.1 += 1;
+        }
+
+        let levels = levels.into_iter().map(|(price, (quantity, count))| {
+            (
+                self.market_info.price_lots_to_number(price),
+                self.market_info.base_size_lots_to_number(quantity),
+                count,
+            )
+        });
+
+        match side {
+            Side::Bid => levels.rev().collect(),
+            Side::Ask => levels.collect(),
+        }
+    }
+
+    /// Returns `true` if `order_id` belongs to the owner's open orders account.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `order_id` - The order id to attribute, e.g. the maker order id of a fill event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the id is one of the owner's resting orders in the cached book or is held in
+    /// the order slots of the owner's open orders account.
+    pub fn owns_order(&self, order_id: u128) -> bool {
+        self.open_orders.owns_order(order_id)
+    }
+
+    /// Returns the best bid of the last loaded book in UI units, or `None` if there are no bids.
+    pub fn best_bid(&self) -> Option<f64> {
+        self.open_orders.best_bid(&self.market_info)
+    }
+
+    /// Returns the best ask of the last loaded book in UI units, or `None` if there are no asks.
+    pub fn best_ask(&self) -> Option<f64> {
+        self.open_orders.best_ask(&self.market_info)
+    }
+
+    /// Returns the mid price of the last loaded book in UI units, or `None` if a side is empty.
+    pub fn mid_price(&self) -> Option<f64> {
+        self.open_orders.mid_price(&self.market_info)
+    }
+
+    /// Previews the fills, fee and total cost of a taker order against the last loaded book.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the taker order.
+    /// * `base_qty` - The base quantity to trade, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// The `TakerPreview`, partially filled if the book is too shallow for `base_qty`.
+    pub fn preview_taker(&self, side: Side, base_qty: f64) -> TakerPreview {
+        self.open_orders
+            .preview_taker(&self.market_info, self.fee_tier, side, base_qty)
+    }
+
+    /// Simulates a limit order against the last loaded book, without any RPC call.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order.
+    /// * `price` - The limit price of the order, in UI units.
+    /// * `qty` - The base quantity of the order, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// The expected filled quantity, average fill price, taker fee and resting quantity of the
+    /// order, with the fee of the client's fee tier.
+    pub fn simulate_order(&self, side: Side, price: f64, qty: f64) -> SimResult {
+        self.open_orders
+            .simulate_order(&self.market_info, self.fee_tier, side, price, qty)
+    }
+
+    /// Computes the exit price at which a round trip opened at `entry_price` breaks even.
+    ///
+    /// The entry is a resting maker order credited the maker rebate of the client's fee tier, and
+    /// the exit crosses the book and is charged its taker fee, both on their notional.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `entry_price` - The price the position was opened at, in UI units.
+    /// * `side` - The side of the entry: `Side::Bid` for a long, `Side::Ask` for a short.
+    ///
+    /// # Returns
+    ///
+    /// The exit price in UI units, above the entry for a long and below it for a short.
+    pub fn break_even_price(&self, entry_price: f64, side: Side) -> f64 {
+        let rebate = maker_rebate_rate(self.fee_tier);
+        let fee = taker_fee_rate(self.fee_tier);
+
+        match side {
+            Side::Bid => entry_price * (1.0 - rebate) / (1.0 - fee),
+            Side::Ask => entry_price * (1.0 + rebate) / (1.0 + fee),
+        }
+    }
+
+    /// Computes a mark price blending the mid price of the cached book with the VWAP of `trades`.
+    ///
+    /// On thin books the mid price is noisy; weighting in recent trades gives a steadier reference
+    /// for PnL. When only one of the two is available, it is returned as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `trades` - Recent trades, e.g. from `recent_trades`.
+    /// * `trade_weight` - Weight of the trade VWAP in the blend, clamped to `[0, 1]`.
+    ///
+    /// # Returns
+    ///
+    /// The mark price in UI units, or `None` if the book has an empty side and there are no
+    /// trades.
+    pub fn mark_price(&self, trades: &[Trade], trade_weight: f64) -> Option<f64> {
+        let mid = self.open_orders.mid_price(&self.market_info);
+        let vwap = self.market_info.trades_vwap(trades);
+
+        match (mid, vwap) {
+            (Some(mid), Some(vwap)) => {
+                let weight = trade_weight.clamp(0.0, 1.0);
+                Some(mid * (1.0 - weight) + vwap * weight)
+            }
+            (Some(price), None) | (None, Some(price)) => Some(price),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the immutable parameters of the loaded market.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// An owned `MarketParameters` bundling lot sizes, decimals, mints, vaults and queue
+    /// addresses, serde-serializable with the `serde` feature.
+    pub fn market_parameters(&self) -> MarketParameters {
+        self.market_info.parameters()
+    }
+
+    /// Fetches the raw data of every account involved in the market, for debugging.
+    ///
+    /// The market, bids, asks, event queue, request queue, both vaults and the owner's open
+    /// orders account are read in a single request, so they are consistent with each other.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the dump, with the account data base64-encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accounts cannot be fetched.
+    pub async fn dump_accounts(&self) -> Result<MarketAccountsDump> {
+        let named_keys = [
+            ("market", self.market_info.market_address),
+            ("bids", self.market_info.bids_address),
+            ("asks", self.market_info.asks_address),
+            ("event_queue", self.market_info.event_queue),
+            ("request_queue", self.market_info.request_queue),
+            ("coin_vault", self.market_info.coin_vault),
+            ("pc_vault", self.market_info.pc_vault),
+            ("open_orders", self.open_orders.oo_key),
+        ];
+        let keys: Vec<Pubkey> = named_keys.iter().map(|(_, key)| *key).collect();
+
+        let (slot, accounts) = self
+            .rpc_client
+            .fetch_multiple_accounts_at_slot(&keys, None)
+            .await?;
+
+        let accounts = named_keys
+            .iter()
+            .zip(accounts)
+            .map(|((name, address), account)| AccountDump {
+                name: name.to_string(),
+                address: *address,
+                account: account.map(|account| {
+                    UiAccount::encode(address, &account, UiAccountEncoding::Base64, None, None)
+                }),
+            })
+            .collect();
+
+        Ok(MarketAccountsDump { slot, accounts })
+    }
+
+    /// Returns the market state, refetching it only when the cached copy is older than `max_age`.
+    ///
+    /// Concurrent readers share one cache, so many reads within `max_age` cost a single RPC call.
+    /// The refreshed state is stored in the cache only; `self.market_info` is left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `max_age` - The maximum staleness the caller tolerates.
+    ///
+    /// # Returns
+    ///
+    /// The cached or freshly loaded `Market`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the cache is stale and reloading the market fails.
+    pub async fn cached_market_info(&self, max_age: Duration) -> Result<Market> {
+        let mut cache = self.market_info_cache.lock().await;
+
+        if let Some((fetched_at, market)) = cache.as_ref() {
+            if fetched_at.elapsed() < max_age {
+                return Ok(market.clone());
+            }
+        }
+
+        let mut market = self.market_info.clone();
+        market.load(&self.rpc_client).await?;
+        *cache = Some((Instant::now(), market.clone()));
+
+        Ok(market)
+    }
+
+    /// Returns `true` if the cached book is crossed or locked (best bid >= best ask).
+    ///
+    /// A crossed book usually means the bids and asks were read at skewed slots; call
+    /// `load_bids_asks_info` to refresh it. When `crossed_book_guard` is set, order placement
+    /// fails with `OpenBookError::CrossedBook` while this returns `true`.
+    pub fn is_book_crossed(&self) -> bool {
+        self.open_orders.is_book_crossed()
+    }
+
+    /// Finds the owner's associated token account for `mint`, creating it if it does not exist.
+    ///
+    /// Creation uses the idempotent instruction, so it does not fail if another process creates
+    /// the same account concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `mint` - The mint of the associated token account.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the address of the associated token account.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MarketError::MintNotFound` if the mint account does not exist, or
+    /// `MarketError::Rpc` if an account cannot be fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let ata = ob_client
+    ///         .find_or_create_associated_token_account(&ob_client.market_info.base_mint)
+    ///         .await?;
+    ///
+    ///     println!("Base ATA: {:?}", ata);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn find_or_create_associated_token_account(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, MarketError> {
+        let owner = self.owner.pubkey();
+        let ata = get_associated_token_address(&owner, mint);
+
+        if self.rpc_client.get_account(&ata).await?.is_some() {
+            return Ok(ata);
+        }
+
+        if self.rpc_client.get_account(mint).await?.is_none() {
+            return Err(MarketError::MintNotFound(*mint));
+        }
+
+        let ix = create_associated_token_account_instruction(
+            &owner,
+            &owner,
+            mint,
+            &SPL_TOKEN_ID.parse()?,
+        );
+
+        self.rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?;
+
+        Ok(ata)
+    }
+
+    /// Resolves a token symbol to its mint address.
+    ///
+    /// Well-known symbols (`USDC`, `USDT`, `WSOL`) map to their canonical mainnet mints. Any other
+    /// symbol is read as a mint address, which is accepted if the owner holds a token account
+    /// for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `symbol` - The token symbol or mint address.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the mint address.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MarketError::OpenBook(OpenBookError::UnknownMintSymbol)` if `symbol` is neither
+    /// well known nor an address, `MarketError::MintNotFound` if the owner holds no token
+    /// account for the mint, or `MarketError::Rpc` if the token accounts cannot be fetched.
+    pub async fn get_mint_address(&self, symbol: &str) -> Result<Pubkey, MarketError> {
+        if let Some(mint) = known_mint(symbol) {
+            return Ok(mint);
+        }
+
+        let mint: Pubkey = symbol
+            .parse()
+            .map_err(|_| OpenBookError::UnknownMintSymbol(symbol.to_string()))?;
+        let token_accounts = self
+            .rpc_client
+            .fetch_token_accounts_by_owner(&self.owner.pubkey(), TokenAccountsFilter::Mint(mint))
+            .await?;
+        if token_accounts.is_empty() {
+            return Err(MarketError::MintNotFound(mint));
+        }
+
+        Ok(mint)
+    }
+
+    /// Builds the instructions creating the client's `base_ata` and `quote_ata` if they do not
+    /// exist.
+    ///
+    /// Only accounts that are the owner's associated token accounts for the market's base and
+    /// quote mints can be created; explicitly set token accounts are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing one create instruction per missing account, empty if none is
+    /// missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::MintNotFound` if the mint of a missing account does not exist, or
+    /// an error if the accounts cannot be fetched.
+    pub async fn missing_token_account_instructions(&self) -> Result<Vec<Instruction>> {
+        let owner = self.owner.pubkey();
+        let (owner_base_ata, owner_quote_ata) = self.market_info.owner_atas(&owner);
+        let (base_mint, quote_mint) = (self.market_info.base_mint, self.market_info.quote_mint);
+
+        let accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&[self.base_ata(), self.quote_ata(), base_mint, quote_mint])
+            .await?;
+        if accounts.len() != 4 {
+            anyhow::bail!("expected 4 accounts, got {}", accounts.len());
+        }
+
+        let mut instructions = Vec::new();
+        for (i, (ata, owner_ata, mint)) in [
+            (self.base_ata(), owner_base_ata, base_mint),
+            (self.quote_ata(), owner_quote_ata, quote_mint),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if ata != owner_ata || accounts[i].is_some() {
+                continue;
+            }
+            if accounts[i + 2].is_none() {
+                return Err(OpenBookError::MintNotFound(mint).into());
+            }
+            instructions.push(create_associated_token_account_instruction(
+                &owner,
+                &owner,
+                &mint,
+                &SPL_TOKEN_ID.parse()?,
+            ));
+        }
+
+        Ok(instructions)
+    }
+
+    /// Creates the client's base and quote token accounts if they do not exist, in a single
+    /// transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `(base_ata, quote_ata)` addresses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if building the instructions fails, or if the transaction is not
+    /// confirmed.
+    pub async fn create_missing_token_accounts(&self) -> Result<(Pubkey, Pubkey)> {
+        let instructions = self.missing_token_account_instructions().await?;
+        if !instructions.is_empty() {
+            let (confirmed, signature) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), instructions)
+                .await?;
+            if !confirmed {
+                anyhow::bail!("token account creation {} was not confirmed", signature);
+            }
+        }
+
+        Ok((self.base_ata(), self.quote_ata()))
+    }
+
+    /// Builds a `new_order` instruction for the open orders account of the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `price` - The limit price of the order, in UI units.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instruction, or `None` if the order rounds down to zero base lots.
+    pub fn new_order_instruction(
+        &self,
+        side: Side,
+        price: f64,
+        target_amount_quote: f64,
+        order_type: OrderType,
+    ) -> Result<Option<Instruction>> {
+        let limit_price_lots = self.market_info.price_number_to_lots(price);
+        let target_amount_base = target_amount_quote / price;
+
+        let target_base_lots = self
+            .market_info
+            .base_size_number_to_lots(target_amount_base);
+
+        debug!("[*] Using limit price lots: {:?}", limit_price_lots);
+        debug!("[*] Using target base lots: {:?}", target_base_lots);
+
+        if target_base_lots == 0 {
+            debug!(
+                "[*] Got zero base lots, and quote: {:?}",
+                target_amount_quote
+            );
+            return Ok(None);
+        }
+
+        self.new_order_instruction_lots(side, limit_price_lots, target_base_lots, order_type)
+    }
+
+    /// Builds a maker `new_order` instruction from a price and size already expressed in lots.
+    ///
+    /// The quote ceiling (`max_native_pc_qty_including_fees`) comes from `max_native_pc_qty`, so
+    /// orders that can take liquidity can always pay their taker fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `limit_price_lots` - The limit price of the order, in lots.
+    /// * `base_lots` - The size of the order, in base lots.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instruction, or `None` if the price or size is zero.
+    pub fn new_order_instruction_lots(
+        &self,
+        side: Side,
+        limit_price_lots: u64,
+        base_lots: u64,
+        order_type: OrderType,
+    ) -> Result<Option<Instruction>> {
+        let max_native_pc_qty = self.max_native_pc_qty(order_type, limit_price_lots, base_lots);
+
+        self.new_order_instruction_raw(
+            side,
+            limit_price_lots,
+            base_lots,
+            max_native_pc_qty,
+            order_type,
+            SelfTradeBehavior::AbortTransaction,
+            self.gen_client_order_id(),
+        )
+    }
+
+    /// Computes the quote ceiling of an order, in native quote units.
+    ///
+    /// Post-only orders never take liquidity and are credited the maker rebate instead of paying
+    /// a fee, so their ceiling is the notional. Limit and immediate-or-cancel orders can fill as
+    /// takers and are padded by the taker fee of the client's fee tier.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    /// * `limit_price_lots` - The limit price of the order, in lots.
+    /// * `base_lots` - The size of the order, in base lots.
+    ///
+    /// # Returns
+    ///
+    /// The order notional, plus the taker fee on it, rounded up, unless the order is post only.
+    pub fn max_native_pc_qty(
+        &self,
+        order_type: OrderType,
+        limit_price_lots: u64,
+        base_lots: u64,
+    ) -> u64 {
+        let notional =
+            base_lots as u128 * self.market_info.pc_lot_size as u128 * limit_price_lots as u128;
+        let notional = notional.min(u64::MAX as u128) as u64;
+
+        match order_type {
+            OrderType::PostOnly => notional,
+            OrderType::Limit | OrderType::ImmediateOrCancel => {
+                notional.saturating_add(taker_fee(self.fee_tier, notional))
+            }
+        }
+    }
+
+    /// Builds an immediate-or-cancel `new_order` instruction spending at most a quote budget.
+    ///
+    /// Unlike the maker helpers, the quote ceiling (`max_native_pc_qty_including_fees`) is the
+    /// exact budget, so a bid stops filling once the budget, fees included, is spent.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `price` - The worst acceptable price, in UI units.
+    /// * `quote_budget` - The quote amount to trade, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instruction, or `None` if the budget rounds down to zero base
+    /// lots.
+    pub fn new_taker_order_instruction(
+        &self,
+        side: Side,
+        price: f64,
+        quote_budget: f64,
+    ) -> Result<Option<Instruction>> {
+        Ok(self
+            .taker_order_instruction(side, price, quote_budget, self.gen_client_order_id())?
+            .map(|(ix, _)| ix))
+    }
+
+    /// Builds the instruction of `new_taker_order_instruction` with the given client order id,
+    /// along with the base lots it requests.
+    fn taker_order_instruction(
+        &self,
+        side: Side,
+        price: f64,
+        quote_budget: f64,
+        client_order_id: u64,
+    ) -> Result<Option<(Instruction, u64)>> {
+        let limit_price_lots = self.market_info.price_number_to_lots(price);
+        let base_lots = self
+            .market_info
+            .base_size_number_to_lots(quote_budget / price);
+        let budget_native =
+            (quote_budget * 10f64.powi(self.market_info.pc_decimals as i32)).round() as u64;
+
+        Ok(self
+            .new_order_instruction_raw(
+                side,
+                limit_price_lots,
+                base_lots,
+                budget_native,
+                OrderType::ImmediateOrCancel,
+                SelfTradeBehavior::AbortTransaction,
+                client_order_id,
+            )?
+            .map(|ix| (ix, base_lots)))
+    }
+
+    /// Places an immediate-or-cancel order spending at most `quote_budget`.
+    ///
+    /// The order is not settled, so what it fills stays in the open orders account. Use
+    /// `new_taker_order_instruction` to build the instruction without sending it.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `price` - The worst acceptable price, in UI units.
+    /// * `quote_budget` - The quote amount to trade, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the placed order and what it filled, or `None` if the budget
+    /// rounds down to zero base lots.
+    pub async fn place_ioc_order(
+        &self,
+        side: Side,
+        price: f64,
+        quote_budget: f64,
+    ) -> Result<Option<TakerOrder>> {
+        self.ensure_writable()?;
+        let client_order_id = self.gen_client_order_id();
+        let (ix, base_lots) =
+            match self.taker_order_instruction(side, price, quote_budget, client_order_id)? {
+                Some(parts) => parts,
+                None => return Ok(None),
+            };
+        let requested_base = self.market_info.base_size_lots_to_number(base_lots);
+
+        self.send_taker_order(vec![ix], side, requested_base, client_order_id)
+            .await
+            .map(Some)
+    }
+
+    /// Builds the instructions of a market order: an immediate-or-cancel `new_order` at the
+    /// most permissive limit price, followed by a `settle_funds` to the owner's accounts.
+    ///
+    /// Buys use a limit price of `u64::MAX` and are bounded by `qty` in native quote units;
+    /// sells use a limit price of `1` and are bounded by `qty` in base lots. Both use the
+    /// `DecrementTake` self-trade behavior, so nothing is left resting.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `qty` - The native quote amount to spend for a buy, or the base lots to sell.
+    /// * `client_order_id` - The client order id attached to the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the order and settle instructions, in that order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `qty` is zero.
+    pub fn market_order_instructions(
+        &self,
+        side: Side,
+        qty: u64,
+        client_order_id: u64,
+    ) -> Result<Vec<Instruction>> {
+        let (limit_price_lots, base_lots, max_native_pc_qty) = match side {
+            Side::Bid => (u64::MAX, u64::MAX, qty),
+            Side::Ask => (1, qty, u64::MAX),
+        };
+        let ix = self
+            .new_order_instruction_raw(
+                side,
+                limit_price_lots,
+                base_lots,
+                max_native_pc_qty,
+                OrderType::ImmediateOrCancel,
+                SelfTradeBehavior::DecrementTake,
+                client_order_id,
+            )?
+            .ok_or_else(|| anyhow::anyhow!("market order quantity must be non-zero"))?;
+
+        Ok(vec![ix, self.settle_funds_instruction()?])
+    }
+
+    /// Buys at market, spending at most `max_quote_qty` native quote units, and settles the
+    /// proceeds in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `max_quote_qty` - The most quote to spend, in native units, fees included.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the placed order and what it filled. A buy is bounded by quote
+    /// only, so its `cancelled_base` is always zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `max_quote_qty` is zero, or if sending fails.
+    pub async fn market_buy(&self, max_quote_qty: u64) -> Result<TakerOrder> {
+        self.place_market_order(Side::Bid, max_quote_qty).await
+    }
+
+    /// Sells `coin_qty` base lots at market and settles the proceeds in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `coin_qty` - The size to sell, in base lots.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the placed order and what it filled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `coin_qty` is zero, or if sending fails.
+    pub async fn market_sell(&self, coin_qty: u64) -> Result<TakerOrder> {
+        self.place_market_order(Side::Ask, coin_qty).await
+    }
+
+    /// Sends the instructions of `market_order_instructions`.
+    async fn place_market_order(&self, side: Side, qty: u64) -> Result<TakerOrder> {
+        self.ensure_writable()?;
+        let client_order_id = self.gen_client_order_id();
+        let instructions = self.market_order_instructions(side, qty, client_order_id)?;
+        let requested_base = match side {
+            Side::Bid => 0.0,
+            Side::Ask => self.market_info.base_size_lots_to_number(qty),
+        };
+
+        self.send_taker_order(instructions, side, requested_base, client_order_id)
+            .await
+    }
+
+    /// Builds a `new_order` instruction with an explicit quote ceiling, in native quote units.
+    #[allow(clippy::too_many_arguments)]
+    fn new_order_instruction_raw(
+        &self,
+        side: Side,
+        limit_price_lots: u64,
+        base_lots: u64,
+        max_native_pc_qty: u64,
+        order_type: OrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        client_order_id: u64,
+    ) -> Result<Option<Instruction>> {
+        let input_ata = match side {
+            Side::Bid => self.quote_ata(),
+            Side::Ask => self.base_ata(),
+        };
+
+        let (limit_price, max_coin_qty, max_native_pc_qty_including_fees) = match (
+            NonZeroU64::new(limit_price_lots),
+            NonZeroU64::new(base_lots),
+            NonZeroU64::new(max_native_pc_qty),
+        ) {
+            (Some(price), Some(qty), Some(pc_qty)) => (price, qty, pc_qty),
+            _ => return Ok(None),
+        };
+
+        let place_order_ix = openbook_dex::instruction::new_order(
+            &self.market_info.market_address,
+            &self.open_orders.oo_key,
+            &self.market_info.request_queue,
+            &self.market_info.event_queue,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &input_ata,
+            &self.owner.pubkey(),
+            &self.market_info.coin_vault,
+            &self.market_info.pc_vault,
+            &SPL_TOKEN_ID.parse()?,
+            &rent::ID,
+            self.fee_discount_account.as_ref(),
+            &self.market_info.program_id,
+            side,
+            limit_price,
+            max_coin_qty,
+            order_type,
+            client_order_id,
+            self_trade_behavior,
+            u16::MAX,
+            max_native_pc_qty_including_fees,
+            (get_unix_secs() + 30) as i64,
+        )?;
+
+        Ok(Some(place_order_ix))
+    }
+
+    /// Sets the SRM or MSRM token account passed to new orders for a fee discount.
+    ///
+    /// The account is fetched to check that it holds SRM or MSRM, and its balance is mapped to
+    /// the fee tier the program will apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `account` - The SRM or MSRM token account owned by the signer.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the fee tier earned by the account's holdings, which the client
+    /// then budgets fees with.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InvalidFeeDiscountMint` if the account holds another mint, or an
+    /// error if it cannot be fetched or is not a token account.
+    pub async fn set_fee_discount_account(&mut self, account: Pubkey) -> Result<FeeTier> {
+        let data = self.rpc_client.fetch_account(&account).await?.data;
+        if data.len() < 72 {
+            anyhow::bail!("{} is not a token account", account);
+        }
+
+        // SPL token account layout: mint (32 bytes), owner (32 bytes), amount (u64), ...
+        let mint = Pubkey::new_from_array(data[0..32].try_into()?);
+        let amount = u64::from_le_bytes(data[64..72].try_into()?);
+        let tier = self.market_info.fee_discount_tier(&mint, amount)?;
+        self.fee_discount_account = Some(account);
+        self.fee_tier = tier;
+
+        Ok(tier)
+    }
+
+    /// Builds the bid and ask `new_order` instructions of a two-sided quote.
+    ///
+    /// Both are limit orders using the `CancelProvide` self-trade behavior, so an overlap with
+    /// the owner's resting orders cancels the resting side instead of failing the transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `bid` - The bid to place; its side must be `Side::Bid`.
+    /// * `ask` - The ask to place; its side must be `Side::Ask`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the bid and ask instructions, in that order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a spec has the wrong side, rounds down to zero lots, or if both
+    /// instructions do not fit in a single transaction.
+    pub fn quote_both_sides_instructions(
+        &self,
+        bid: OrderSpec,
+        ask: OrderSpec,
+    ) -> Result<Vec<Instruction>> {
+        if bid.side != Side::Bid || ask.side != Side::Ask {
+            anyhow::bail!("a two-sided quote needs one bid and one ask");
+        }
+
+        let mut instructions = Vec::with_capacity(2);
+        for spec in [bid, ask] {
+            let limit_price_lots = self.market_info.price_number_to_lots(spec.price);
+            let base_lots = self.market_info.base_size_number_to_lots(spec.size);
+            let ix = self
+                .new_order_instruction_raw(
+                    spec.side,
+                    limit_price_lots,
+                    base_lots,
+                    self.max_native_pc_qty(OrderType::Limit, limit_price_lots, base_lots),
+                    OrderType::Limit,
+                    SelfTradeBehavior::CancelProvide,
+                    self.gen_client_order_id(),
+                )?
+                .ok_or_else(|| anyhow::anyhow!("{:?} quote rounds down to zero lots", spec.side))?;
+            instructions.push(ix);
+        }
+
+        let size = transaction_size(&instructions, &self.owner.pubkey());
+        if size > PACKET_DATA_SIZE {
+            anyhow::bail!(
+                "two-sided quote does not fit in one transaction: {} bytes",
+                size
+            );
+        }
+
+        Ok(instructions)
+    }
+
+    /// Places a bid and an ask in one transaction so both quotes go live together.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `bid` - The bid to place; its side must be `Side::Bid`.
+    /// * `ask` - The ask to place; its side must be `Side::Ask`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `quote_both_sides_instructions`, or an error if sending fails.
+    pub async fn quote_both_sides(&self, bid: OrderSpec, ask: OrderSpec) -> Result<Signature> {
+        self.ensure_writable()?;
+        let instructions = self.quote_both_sides_instructions(bid, ask)?;
+
+        let (_, signature) = self.send_order_instructions(instructions).await?;
+
+        Ok(signature)
+    }
+
+    /// Enters the market in as few transactions as possible.
+    ///
+    /// Assembles the creation of the open orders account and of the base and quote associated
+    /// token accounts (each only if missing) followed by the first order, and splits them into the
+    /// minimum number of transactions that fit the packet size limit. If the client's open orders
+    /// account does not exist but the owner's seeded one does, the seeded account is reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `first_order` - The initial order to place.
+    /// * `execute` - A boolean indicating whether to send the transactions.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing, per transaction, either its instructions or its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accounts cannot be fetched, or as soon as a transaction is not
+    /// confirmed; the transactions after it are not sent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::orders::OrderSpec;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let first_order = OrderSpec { side: Side::Bid, price: 2.1, size: 1.0 };
+    ///
+    ///     let transactions = ob_client.enter_market(first_order, false).await?;
+    ///
+    ///     println!("Entering the market takes {} transaction(s)", transactions.len());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn enter_market(
+        &mut self,
+        first_order: OrderSpec,
+        execute: bool,
+    ) -> Result<Vec<OrderReturnType>> {
+        self.ensure_writable()?;
+        let owner = self.owner.pubkey();
+        let mut instructions = Vec::new();
+
+        let oo_exists = self.open_orders.oo_key != Pubkey::default()
+            && self
+                .rpc_client
+                .get_account(&self.open_orders.oo_key)
+                .await?
+                .is_some();
+        if !oo_exists {
+            let (seeded_oo_key, _) = open_orders_seeded_address(&self.market_info, &owner)?;
+            if self.rpc_client.get_account(&seeded_oo_key).await?.is_some() {
+                self.open_orders.oo_key = seeded_oo_key;
+            } else {
+                let lamports = self
+                    .rpc_client
+                    .fetch_rent_exemption(OPEN_ORDERS_ACCOUNT_LEN)
+                    .await?;
+                let (oo_key, ixs) =
+                    create_open_orders_instructions(&self.market_info, &owner, lamports)?;
+                self.open_orders.oo_key = oo_key;
+                instructions.extend(ixs);
+            }
+        }
+
+        for (ata, mint) in [
+            (self.base_ata(), self.market_info.base_mint),
+            (self.quote_ata(), self.market_info.quote_mint),
+        ] {
+            if self.rpc_client.get_account(&ata).await?.is_none() {
+                instructions.push(create_associated_token_account_instruction(
+                    &owner,
+                    &owner,
+                    &mint,
+                    &SPL_TOKEN_ID.parse()?,
+                ));
+            }
+        }
+
+        if let Some(ix) = self.new_order_instruction(
+            first_order.side,
+            first_order.price,
+            first_order.size * first_order.price,
+            OrderType::PostOnly,
+        )? {
+            instructions.push(ix);
+        }
+
+        let groups = split_into_transactions(instructions, &owner);
+        if !execute {
+            return Ok(groups
+                .into_iter()
+                .map(OrderReturnType::Instructions)
+                .collect());
+        }
+
+        let mut signatures = Vec::with_capacity(groups.len());
+        for (index, group) in groups.into_iter().enumerate() {
+            let (confirmed, signature) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), group)
+                .await?;
+            if !confirmed {
+                anyhow::bail!(
+                    "transaction {} of entering the market was not confirmed",
+                    index + 1
+                );
+            }
+            signatures.push(OrderReturnType::Signature(signature));
+        }
+
+        Ok(signatures)
+    }
+
+    /// Fetches the base token balance held by the owner in UI units.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the base balance of the owner's base ATA.
+    pub async fn base_position(&self) -> Result<f64> {
+        let balance = self
+            .rpc_client
+            .fetch_token_account_balance(&self.base_ata())
+            .await?;
+
+        Ok(balance.ui_amount.unwrap_or_default())
+    }
+
+    /// Plans the WSOL wrap and unwrap around a round trip of intended trades.
+    ///
+    /// The current WSOL balance is read from whichever of the owner's ATAs holds WSOL, and is zero
+    /// if that ATA does not exist yet; see `sol_plan` for how the amounts are derived.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `intended_trades` - The trades to execute, in order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the plan, all zero if neither leg of the market is WSOL.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the WSOL token account cannot be fetched or is not a
+    /// token account.
+    pub async fn plan_sol_management(&self, intended_trades: &[OrderSpec]) -> Result<SolPlan> {
+        let wsol_ata = if self.market_info.base_mint == WSOL_MINT {
+            self.base_ata()
+        } else if self.market_info.quote_mint == WSOL_MINT {
+            self.quote_ata()
+        } else {
+            return Ok(SolPlan::default());
+        };
+
+        // A missing WSOL ATA holds nothing yet; it is created when the first wrap is sent.
+        let wsol_balance = match self.rpc_client.get_account(&wsol_ata).await? {
+            None => 0,
+            // SPL token account layout: mint (32 bytes), owner (32 bytes), amount (u64), ...
+            Some(account) if account.data.len() >= 72 => {
+                u64::from_le_bytes(account.data[64..72].try_into()?)
+            }
+            Some(_) => anyhow::bail!("{} is not a token account", wsol_ata),
+        };
+
+        Ok(sol_plan(
+            &self.market_info,
+            self.fee_tier,
+            intended_trades,
+            wsol_balance,
+        ))
+    }
+
+    /// Places a reduce-only limit order on the market.
+    ///
+    /// The owner's base holdings are the base ATA balance plus the free and locked base of the
+    /// open orders account. Their distance to `base_position_target` is the position: asks reduce
+    /// a long one and bids a short one. The order size is clamped to the part of the position not
+    /// already covered by the owner's resting orders on the same side, so the order can only
+    /// reduce the position, never flip it. An error is returned if the order would increase
+    /// exposure.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order.
+    /// * `size` - The requested order size in base UI units.
+    /// * `price` - The limit price of the order.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature or the instructions if successful.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let result = ob_client.place_reduce_only_order(Side::Ask, 1.0, 2.5, false).await;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_reduce_only_order(
+        &self,
+        side: Side,
+        size: f64,
+        price: f64,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, Error> {
+        self.ensure_writable()?;
+        let wallet_base = self.base_position().await?;
+        let balances = self.load_balances().await?;
+        let size = self.reduce_only_size(side, size, wallet_base, &balances)?;
+
+        if !execute {
+            return Ok(self
+                .new_order_instruction(side, price, size * price, OrderType::PostOnly)?
+                .map(|ix| OrderReturnType::Instructions(vec![ix])));
+        }
+
+        self.place_limit_order(size * price, side, 0., execute, price)
+            .await
+    }
+
+    /// Clamps a reduce-only order to the owner's position net of its resting orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order.
+    /// * `size` - The requested order size in base UI units.
+    /// * `wallet_base` - The base balance of the owner's base ATA, in UI units.
+    /// * `balances` - The balances of the owner's open orders account.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the clamped order size, or an error if the order would increase
+    /// exposure.
+    pub fn reduce_only_size(
+        &self,
+        side: Side,
+        size: f64,
+        wallet_base: f64,
+        balances: &Balances,
+    ) -> Result<f64> {
+        let held = wallet_base + balances.base_free + balances.base_locked;
+        let position = held - self.base_position_target;
+        let resting = match side {
+            // Resting asks hold their base locked in the open orders account.
+            Side::Ask => balances.base_locked,
+            Side::Bid => self
+                .open_orders
+                .bids
+                .iter()
+                .filter(|order| order.owner == self.open_orders.oo_key)
+                .map(|order| self.market_info.base_size_lots_to_number(order.quantity))
+                .sum(),
+        };
+
+        clamp_reduce_only(side, size, position, resting)
+    }
+
+    /// Estimates the compute units a set of instructions will consume.
+    ///
+    /// The instructions are assembled into a transaction paid by the owner and simulated,
+    /// which allows callers to cap the number of orders per transaction by compute budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `instructions` - The instructions to estimate.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the compute units consumed by the simulated transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     if let Some(OrderReturnType::Instructions(insts)) = ob_client
+    ///         .place_limit_order(5.0, Side::Bid, 5.0, false, 2.1)
+    ///         .await?
+    ///     {
+    ///         let units = ob_client.estimate_compute_units(&insts).await?;
+    ///         println!("[*] Estimated compute units: {:?}", units);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn estimate_compute_units(&self, instructions: &[Instruction]) -> Result<u64> {
+        self.rpc_client
+            .simulate_compute_units(&self.owner.pubkey(), instructions)
+            .await
+    }
+
+    /// Checks that a client order id lies inside the client's `client_id_prefix` namespace, so a
+    /// strategy never cancels the orders of another one sharing the open orders account.
+    fn ensure_in_namespace(&self, client_order_id: u64) -> Result<()> {
+        if client_order_id_in_namespace(self.client_id_prefix, client_order_id) {
+            return Ok(());
+        }
+
+        Err(OpenBookError::ClientOrderIdOutsideNamespace {
+            client_order_id,
+            prefix: self.client_id_prefix,
+        }
+        .into())
+    }
+
+    /// Generates a random client order id inside the client's `client_id_prefix` namespace.
+    pub fn gen_client_order_id(&self) -> u64 {
+        namespaced_client_order_id(self.client_id_prefix, random::<u64>())
+    }
+
+    /// Reconstructs the outcome of a taker order from its confirmed transaction.
+    ///
+    /// The fill is derived from the change of the owner's base and quote token balances in the
+    /// transaction, plus the change of the open orders balances since `open_orders_before`. Funds
+    /// the transaction deposits but leaves unsettled are therefore not counted as traded.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `signature` - The signature of the taker transaction.
+    /// * `side` - The side of the taker order.
+    /// * `requested_base` - The base quantity requested by the order, in UI units.
+    /// * `open_orders_before` - The open orders balances read before the transaction was sent.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `TakerResult` of the order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction or the open orders account cannot be fetched.
+    pub async fn taker_result(
+        &self,
+        signature: &Signature,
+        side: Side,
+        requested_base: f64,
+        open_orders_before: &Balances,
+    ) -> Result<TakerResult> {
+        let confirmed_transaction = self.rpc_client.fetch_transaction(signature).await?;
+        let meta = confirmed_transaction
+            .transaction
+            .meta
+            .ok_or_else(|| anyhow::anyhow!("transaction {} has no status meta", signature))?;
+
+        let owner = self.owner.pubkey().to_string();
+        let base_delta = token_balance_delta(&meta, &owner, &self.market_info.base_mint);
+        let quote_delta = token_balance_delta(&meta, &owner, &self.market_info.quote_mint);
+        let open_orders_after = self.load_balances().await?;
+
+        Ok(TakerResult::from_account_deltas(
+            side,
+            requested_base,
+            (base_delta, quote_delta),
+            open_orders_before,
+            &open_orders_after,
+        ))
+    }
+
+    /// Cancels all limit orders in the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature if successful,
+    /// or an error if canceling all orders fails.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     if let Some(ord_ret_type) = ob_client
+    ///         .cancel_orders(true)
+    ///         .await?
+    ///     {
+    ///         match ord_ret_type {
+    ///             OrderReturnType::Instructions(insts) => {
+    ///                 println!("[*] Got Instructions: {:?}", insts);
+    ///             }
+    ///             OrderReturnType::Signature(sign) => {
+    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_orders(&self, execute: bool) -> Result<Option<OrderReturnType>, Error> {
+        self.ensure_writable()?;
+        let mut ixs = Vec::new();
+
+        for oid in &self.open_orders.open_bids {
+            let ix = openbook_dex::instruction::cancel_order(
+                &self.market_info.program_id,
+                &self.market_info.market_address,
+                &self.market_info.bids_address,
+                &self.market_info.asks_address,
+                &self.open_orders.oo_key,
+                &self.owner.pubkey(),
+                &self.market_info.event_queue,
+                Side::Bid,
+                *oid,
+            )?;
+            ixs.push(ix);
+        }
+
+        for oid in &self.open_orders.open_asks {
+            let ix = openbook_dex::instruction::cancel_order(
+                &self.market_info.program_id,
+                &self.market_info.market_address,
+                &self.market_info.bids_address,
+                &self.market_info.asks_address,
+                &self.open_orders.oo_key,
+                &self.owner.pubkey(),
+                &self.market_info.event_queue,
+                Side::Ask,
+                *oid,
+            )?;
+            ixs.push(ix);
+        }
+
+        if ixs.is_empty() {
+            return Ok(None);
+        }
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(ixs)));
+        }
+
+        let (_, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), ixs)
+            .await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Builds the cancel instructions for the given orders of the owner's open orders account.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `orders` - The `(order_id, side)` of each order to cancel.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing one cancel instruction per order.
+    pub fn cancel_order_instructions(&self, orders: &[(u128, Side)]) -> Result<Vec<Instruction>> {
+        orders
+            .iter()
+            .map(|(order_id, side)| {
+                Ok(openbook_dex::instruction::cancel_order(
+                    &self.market_info.program_id,
+                    &self.market_info.market_address,
+                    &self.market_info.bids_address,
+                    &self.market_info.asks_address,
+                    &self.open_orders.oo_key,
+                    &self.owner.pubkey(),
+                    &self.market_info.event_queue,
+                    *side,
+                    *order_id,
+                )?)
+            })
+            .collect()
+    }
+
+    /// Cancels one of the owner's resting orders by its full 128-bit order id.
+    ///
+    /// The side is decoded from the id itself (see `order_id_side`), so no book is needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `order_id` - The order id, with the price in the high 64 bits.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction.
+    pub async fn cancel_order(&self, order_id: u128) -> Result<Signature> {
+        self.cancel_order_with_side(order_id, order_id_side(order_id))
+            .await
+    }
+
+    /// Cancels one of the owner's resting orders on a side the caller already knows.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `order_id` - The order id, with the price in the high 64 bits.
+    /// * `side` - The side the order rests on.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction.
+    pub async fn cancel_order_with_side(&self, order_id: u128, side: Side) -> Result<Signature> {
+        self.ensure_writable()?;
+        let ixs = self.cancel_order_instructions(&[(order_id, side)])?;
+        let (_, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), ixs)
+            .await?;
+
+        Ok(signature)
+    }
+
+    /// Cancels every resting order of the owner found in the cached book.
+    ///
+    /// The cancels are packed into as few transactions as fit under the packet size limit, at
+    /// most `MAX_CANCELS_PER_TRANSACTION` per transaction, and each transaction is confirmed
+    /// before the next one is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing one signature per submitted transaction, empty if the cached book
+    /// holds no orders of the owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error as soon as a transaction is not confirmed; the transactions after it are
+    /// not sent.
+    pub async fn cancel_all_orders(&self) -> Result<Vec<Signature>> {
+        self.ensure_writable()?;
+        let orders: Vec<(u128, Side)> = self
+            .open_orders
+            .open_bids
+            .iter()
+            .map(|order_id| (*order_id, Side::Bid))
+            .chain(
+                self.open_orders
+                    .open_asks
+                    .iter()
+                    .map(|order_id| (*order_id, Side::Ask)),
+            )
+            .collect();
+        let instructions = self.cancel_order_instructions(&orders)?;
+
+        let mut signatures = Vec::new();
+        for (index, group) in self
+            .cancel_transactions(instructions)
+            .into_iter()
+            .enumerate()
+        {
+            let (confirmed, signature) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), group)
+                .await?;
+            if !confirmed {
+                anyhow::bail!(
+                    "cancel transaction {} was not confirmed after {} confirmed",
+                    index + 1,
+                    signatures.len()
+                );
+            }
+            signatures.push(signature);
+        }
+
+        Ok(signatures)
+    }
+
+    /// Splits cancel instructions into transactions of at most `MAX_CANCELS_PER_TRANSACTION`
+    /// cancels that fit the packet size limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `instructions` - The cancel instructions to group.
+    ///
+    /// # Returns
+    ///
+    /// The instruction groups, one per transaction.
+    pub fn cancel_transactions(&self, instructions: Vec<Instruction>) -> Vec<Vec<Instruction>> {
+        instructions
+            .chunks(MAX_CANCELS_PER_TRANSACTION)
+            .flat_map(|chunk| split_into_transactions(chunk.to_vec(), &self.owner.pubkey()))
+            .collect()
+    }
+
+    /// Cancels every live order found in the owner's open orders account.
+    ///
+    /// The order ids and sides are read from the `orders` slots and `is_bid_bits` of the open
+    /// orders account rather than from the book, making this the recovery path when the local
+    /// order list was lost or the bids and asks cannot be parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `execute` - A boolean indicating whether to execute the transactions immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instructions or the signature of each transaction, empty if the
+    /// account holds no live orders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the open orders account cannot be fetched or parsed, or as soon as a
+    /// transaction is not confirmed; the transactions after it are not sent.
+    pub async fn cancel_all_from_open_orders_account(
+        &self,
+        execute: bool,
+    ) -> Result<Vec<OrderReturnType>> {
+        self.ensure_writable()?;
+        let data = self
+            .rpc_client
+            .fetch_account(&self.open_orders.oo_key)
+            .await?
+            .data;
+        let orders = open_orders_live_orders(&data)?;
+        let instructions = self.cancel_order_instructions(&orders)?;
+        if instructions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let groups = self.cancel_transactions(instructions);
+        if !execute {
+            return Ok(groups
+                .into_iter()
+                .map(OrderReturnType::Instructions)
+                .collect());
+        }
+
+        let mut signatures = Vec::with_capacity(groups.len());
+        for (index, group) in groups.into_iter().enumerate() {
+            let (confirmed, signature) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), group)
+                .await?;
+            if !confirmed {
+                anyhow::bail!(
+                    "cancel transaction {} was not confirmed after {} confirmed",
+                    index + 1,
+                    signatures.len()
+                );
+            }
+            signatures.push(OrderReturnType::Signature(signature));
+        }
+
+        Ok(signatures)
+    }
+
+    /// Cancels an order given only its client order id, resolving it from a cached book.
+    ///
+    /// Using an already loaded book avoids fetching the bids and asks again. The book must have
+    /// been loaded by `load_bids_asks_info` (e.g. `ob_client.open_orders`) recently enough to
+    /// contain the order.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `client_id` - The client order id of the order to cancel.
+    /// * `book` - The cached book to resolve the order from.
+    /// * `execute` - A boolean indicating whether to execute the cancel immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the cancel instruction or the transaction signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::ClientOrderIdOutsideNamespace` if the id lies outside the
+    /// client's `client_id_prefix` namespace, or `OpenBookError::ClientOrderIdNotFound` if the
+    /// order is not in the cached book, in which case the book should be refreshed before retrying.
+    pub async fn cancel_client_id_from(
+        &self,
+        client_id: u64,
+        book: &OpenOrders,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, Error> {
+        self.ensure_writable()?;
+        self.ensure_in_namespace(client_id)?;
+        let (side, order) = book.find_client_order(&self.open_orders.oo_key, client_id)?;
+
+        let ix = openbook_dex::instruction::cancel_order(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.market_info.event_queue,
+            side,
+            order.order_id,
+        )?;
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(vec![ix])));
+        }
+
+        let (_, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Builds the instruction cancelling the owner's order carrying `client_id`.
+    ///
+    /// The program resolves the order from the client order id stored in the open orders
+    /// account, so no book is needed to recover the full 128-bit order id.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `client_id` - The client order id the order was placed with.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `cancel_order_by_client_order_id` instruction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::ClientOrderIdOutsideNamespace` if the id lies outside the client's
+    /// `client_id_prefix` namespace.
+    pub fn cancel_order_by_client_id_instruction(&self, client_id: u64) -> Result<Instruction> {
+        self.ensure_in_namespace(client_id)?;
+        Ok(openbook_dex::instruction::cancel_order_by_client_order_id(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.market_info.event_queue,
+            client_id,
+        )?)
+    }
+
+    /// Cancels the owner's order carrying `client_id` without scanning the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `client_id` - The client order id the order was placed with, e.g. via `place_order`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction.
+    pub async fn cancel_order_by_client_id(&self, client_id: u64) -> Result<Signature> {
+        self.ensure_writable()?;
+        let ix = self.cancel_order_by_client_id_instruction(client_id)?;
+
+        let (_, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?;
+
+        Ok(signature)
+    }
+
+    /// Finds all of the owner's open orders accounts on this market.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the addresses and raw accounts of the owner's open orders accounts.
+    pub async fn find_all_open_orders_accounts(&self) -> Result<Vec<(Pubkey, Account)>> {
+        fetch_open_orders_accounts(&self.rpc_client, &self.market_info, &self.owner.pubkey()).await
+    }
+
+    /// Sums the free (settleable) balances of all of the owner's open orders accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the total `(base, pc)` free balances in native units.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let (base, pc) = ob_client.aggregate_balances().await?;
+    ///
+    ///     println!("Settleable base: {}, quote: {}", base, pc);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn aggregate_balances(&self) -> Result<(u64, u64)> {
+        let accounts = self.find_all_open_orders_accounts().await?;
+
+        aggregate_free_balances(accounts.iter().map(|(_, account)| account.data.as_slice()))
+    }
+
+    /// Reads the free and locked balances of the owner's open orders account, in UI units.
+    ///
+    /// The free balances can be settled with `settle_balance`; the locked ones are tied up in
+    /// resting orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the balances of the account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account cannot be fetched or is not an open orders account.
+    pub async fn load_balances(&self) -> Result<Balances> {
+        let account = self
+            .rpc_client
+            .fetch_account(&self.open_orders.oo_key)
+            .await?;
+
+        open_orders_balances(&account.data, &self.market_info)
+    }
+
+    /// Builds a `close_open_orders` instruction for the owner's open orders account, returning
+    /// its rent to the owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instruction.
+    pub fn close_open_orders_instruction(&self) -> Result<Instruction> {
+        Ok(openbook_dex::instruction::close_open_orders(
+            &self.market_info.program_id,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.owner.pubkey(),
+            &self.market_info.market_address,
+        )?)
+    }
+
+    /// Closes the owner's open orders account and reclaims its rent.
+    ///
+    /// The program only closes an account holding no orders and no funds, so every order must be
+    /// cancelled and the balances settled first. The account is checked before sending.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::OpenOrdersNotEmpty` if the account still holds orders or
+    /// unsettled funds, or an error if it cannot be fetched or sending fails.
+    pub async fn close_open_orders_account(&self) -> Result<Signature> {
+        self.ensure_writable()?;
+        let account = self
+            .rpc_client
+            .fetch_account(&self.open_orders.oo_key)
+            .await?;
+        if !open_orders_is_empty(&account.data)? {
+            return Err(OpenBookError::OpenOrdersNotEmpty(self.open_orders.oo_key).into());
+        }
+
+        let ix = self.close_open_orders_instruction()?;
+        let (_, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?;
+
+        Ok(signature)
+    }
+
+    /// Subscribes to changes of the owner's open orders account.
+    ///
+    /// Every account update (fills, settles, new or cancelled orders) is decoded into the
+    /// `OpenOrders` state, which signals fills and balance changes without polling the event
+    /// queue. The websocket endpoint is read from the `WS_URL` env var, or derived from the RPC URL.
+    ///
+    /// Available with the `ws` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a stream of the updated `OpenOrders` states.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let updates = ob_client.subscribe_open_orders().await?;
+    ///     let mut updates = Box::pin(updates);
+    ///
+    ///     while let Some(open_orders) = updates.next().await {
+    ///         println!("Open orders changed: {:?}", open_orders);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "ws")]
+    pub async fn subscribe_open_orders(&self) -> Result<impl Stream<Item = OpenOrders>> {
+        let raw_updates = self.subscribe_open_orders_data().await?;
+
+        Ok(open_orders_updates(
+            raw_updates,
+            self.open_orders.clone(),
+            self.market_info.clone(),
+        ))
+    }
+
+    /// Subscribes to the owner's open orders account and streams its balances on every change.
+    ///
+    /// Every account update is decoded into the free and locked balances and the live order ids
+    /// of the account, which signals fills without polling the event queue. The websocket
+    /// endpoint is read from the `WS_URL` env var, or derived from the RPC URL.
+    ///
+    /// Available with the `ws` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a stream of the updated `Balances`.
+    #[cfg(feature = "ws")]
+    pub async fn subscribe_balances(&self) -> Result<impl Stream<Item = Balances>> {
+        let raw_updates = self.subscribe_open_orders_data().await?;
+
+        Ok(open_orders_balance_updates(
+            raw_updates,
+            self.market_info.clone(),
+        ))
+    }
+
+    /// Account-subscribes to the owner's open orders account and streams its raw data.
+    #[cfg(feature = "ws")]
+    async fn subscribe_open_orders_data(&self) -> Result<impl Stream<Item = Vec<u8>>> {
+        let ws_url = std::env::var("WS_URL")
+            .unwrap_or_else(|_| ws_url_from_rpc_url(&self.rpc_client.inner().url()));
+        let pubsub = PubsubClient::new(&ws_url).await?;
+
+        let oo_key = self.open_orders.oo_key;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.rpc_client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        };
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let (mut updates, unsubscribe) =
+                match pubsub.account_subscribe(&oo_key, Some(config)).await {
+                    Ok(subscription) => subscription,
+                    Err(err) => {
+                        error!("[*] Failed to subscribe to {}: {:?}", oo_key, err);
+                        return;
+                    }
+                };
+
+            while let Some(update) = updates.next().await {
+                if let Some(account) = update.value.decode::<Account>() {
+                    if sender.send(account.data).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            unsubscribe().await;
+        });
+
+        Ok(stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|data| (data, receiver))
+        }))
+    }
+
+    /// Subscribes to the market's bids and asks accounts and streams the book on every change.
+    ///
+    /// The current book is yielded first, then a fresh book each time either side changes, so
+    /// strategies can react within a slot instead of polling `load_bids_asks_info`. Updates are
+    /// decoded with `parse_book`; malformed ones are skipped. The websocket endpoint is read
+    /// from the `WS_URL` env var, or derived from the RPC URL.
+    ///
+    /// Available with the `ws` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `depth` - The maximum number of price levels of each yielded book, per side.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a stream of the updated `OrderBook`s.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the websocket cannot be reached or the book accounts cannot be fetched.
+    #[cfg(feature = "ws")]
+    pub async fn subscribe_order_book(
+        &self,
+        depth: usize,
+    ) -> Result<impl Stream<Item = OrderBook>> {
+        let ws_url = std::env::var("WS_URL")
+            .unwrap_or_else(|_| ws_url_from_rpc_url(&self.rpc_client.inner().url()));
+        let pubsub = PubsubClient::new(&ws_url).await?;
+
+        let bids_address = self.market_info.bids_address;
+        let asks_address = self.market_info.asks_address;
+        let (slot, accounts) = self
+            .rpc_client
+            .fetch_multiple_accounts_at_slot(
+                &[self.market_info.market_address, bids_address, asks_address],
+                None,
+            )
+            .await?;
+        let [market_account, mut bids_account, mut asks_account] = market_accounts(accounts)?;
+
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.rpc_client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        };
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let _ = sender.send((slot, bids_account.clone(), asks_account.clone()));
+        tokio::spawn(async move {
+            let (bids_updates, bids_unsubscribe) = match pubsub
+                .account_subscribe(&bids_address, Some(config.clone()))
+                .await
+            {
+                Ok(subscription) => subscription,
+                Err(err) => {
+                    error!("[*] Failed to subscribe to {}: {:?}", bids_address, err);
+                    return;
+                }
+            };
+            let (asks_updates, asks_unsubscribe) =
+                match pubsub.account_subscribe(&asks_address, Some(config)).await {
+                    Ok(subscription) => subscription,
+                    Err(err) => {
+                        error!("[*] Failed to subscribe to {}: {:?}", asks_address, err);
+                        bids_unsubscribe().await;
+                        return;
+                    }
+                };
+
+            let mut updates = stream::select(
+                bids_updates.map(|update| (Side::Bid, update)),
+                asks_updates.map(|update| (Side::Ask, update)),
+            );
+            while let Some((side, update)) = updates.next().await {
+                let Some(account) = update.value.decode::<Account>() else {
+                    continue;
+                };
+                match side {
+                    Side::Bid => bids_account = account,
+                    Side::Ask => asks_account = account,
+                }
+                let book_accounts = (
+                    update.context.slot,
+                    bids_account.clone(),
+                    asks_account.clone(),
+                );
+                if sender.send(book_accounts).is_err() {
+                    break;
+                }
+            }
+            drop(updates);
+
+            bids_unsubscribe().await;
+            asks_unsubscribe().await;
+        });
+
+        let ob_client = self.clone();
+        let raw_updates = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|accounts| (accounts, receiver))
+        });
+
+        Ok(
+            raw_updates.filter_map(move |(slot, bids_account, asks_account)| {
+                let book = ob_client
+                    .parse_book(slot, market_account.clone(), bids_account, asks_account)
+                    .map(|book| book.order_book(&ob_client.market_info, depth));
+                futures_util::future::ready(book.ok())
+            }),
+        )
+    }
+
+    /// Keeps a quote at `target_price`, cancelling and replacing it only when necessary.
+    ///
+    /// If the resting order on `side` is already at the target price (within a tick), nothing is
+    /// sent, preserving its price-time priority. Otherwise the resting orders on that side are
+    /// cancelled and a new post only order is placed in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the quote.
+    /// * `target_price` - The target price of the quote.
+    /// * `size` - The size of the quote in base units.
+    /// * `execute` - A boolean indicating whether to execute the transaction immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `None` if the quote was kept, or the instructions or the signature of
+    /// the cancel-replace otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let result = ob_client.maintain_quote(Side::Bid, 2.1, 1.0, false).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn maintain_quote(
+        &self,
+        side: Side,
+        target_price: f64,
+        size: f64,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>> {
+        self.ensure_writable()?;
+        let resting = self.open_orders.own_orders(side, &self.open_orders.oo_key);
+        let target_price_lots = self.market_info.price_number_to_lots(target_price);
+
+        let mut instructions = Vec::new();
+        match quote_action(resting.first(), target_price_lots) {
+            QuoteAction::Keep => return Ok(None),
+            QuoteAction::Replace => {
+                for order in &resting {
+                    instructions.push(openbook_dex::instruction::cancel_order(
+                        &self.market_info.program_id,
+                        &self.market_info.market_address,
+                        &self.market_info.bids_address,
+                        &self.market_info.asks_address,
+                        &self.open_orders.oo_key,
+                        &self.owner.pubkey(),
+                        &self.market_info.event_queue,
+                        side,
+                        order.order_id,
+                    )?);
+                }
+            }
+            QuoteAction::Place => {}
+        }
+
+        if let Some(ix) = self.new_order_instruction(
+            side,
+            target_price,
+            size * target_price,
+            OrderType::PostOnly,
+        )? {
+            instructions.push(ix);
+        }
+
+        if instructions.is_empty() {
+            return Ok(None);
+        }
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(instructions)));
+        }
+
+        let (_, signature) = self.send_order_instructions(instructions).await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Builds the instructions of a cancel-replace: a `cancel_order` for `old_order_id` followed
+    /// by a limit `new_order` for `new`.
+    ///
+    /// The side of the old order is read from its id. The new order uses the `CancelProvide`
+    /// self-trade behavior and a freshly generated client order id.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `old_order_id` - The id of the resting order to cancel.
+    /// * `new` - The order to place in its stead.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the cancel and new order instructions, in that order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `new` rounds down to zero lots, or if both instructions do not fit in
+    /// a single transaction.
+    pub fn replace_order_instructions(
+        &self,
+        old_order_id: u128,
+        new: OrderSpec,
+    ) -> Result<Vec<Instruction>> {
+        let mut instructions =
+            self.cancel_order_instructions(&[(old_order_id, order_id_side(old_order_id))])?;
+
+        let limit_price_lots = self.market_info.price_number_to_lots(new.price);
+        let base_lots = self.market_info.base_size_number_to_lots(new.size);
+        let ix = self
+            .new_order_instruction_raw(
+                new.side,
+                limit_price_lots,
+                base_lots,
+                self.max_native_pc_qty(OrderType::Limit, limit_price_lots, base_lots),
+                OrderType::Limit,
+                SelfTradeBehavior::CancelProvide,
+                self.gen_client_order_id(),
+            )?
+            .ok_or_else(|| anyhow::anyhow!("replacement order rounds down to zero lots"))?;
+        instructions.push(ix);
+
+        let size = transaction_size(&instructions, &self.owner.pubkey());
+        if size > PACKET_DATA_SIZE {
+            anyhow::bail!(
+                "cancel-replace does not fit in one transaction: {} bytes",
+                size
+            );
+        }
+
+        Ok(instructions)
+    }
+
+    /// Atomically reprices an order: cancels `old_order_id` and places `new` in one transaction.
+    ///
+    /// There is no window in which neither order rests. If the cancel fails on chain, e.g.
+    /// because the old order was filled or cancelled meanwhile, the whole transaction fails and
+    /// the new order is not placed either.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `old_order_id` - The id of the resting order to cancel.
+    /// * `new` - The order to place in its stead.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature of the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `replace_order_instructions`, or an error if sending fails.
+    pub async fn replace_order(&self, old_order_id: u128, new: OrderSpec) -> Result<Signature> {
+        self.ensure_writable()?;
+        let instructions = self.replace_order_instructions(old_order_id, new)?;
+
+        let (_, signature) = self.send_order_instructions(instructions).await?;
+
+        Ok(signature)
+    }
+
+    /// Shrinks a resting order of the owner to `new_size`, keeping its price.
+    ///
+    /// OpenBook V1 has no partial cancel, so this cancels the order and places a post only order
+    /// at the same price for the smaller size in one transaction. The replacement joins the back
+    /// of the queue at that price: the order loses its time priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `order_id` - The id of the resting order to shrink.
+    /// * `side` - The side of the resting order.
+    /// * `new_size` - The remaining size in base units; zero cancels the order outright.
+    /// * `execute` - A boolean indicating whether to execute the transaction immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instructions or the signature of the cancel-replace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the owner has no resting order with `order_id` on `side` in the cached
+    /// book, or if `new_size` is not smaller than the order's remaining size.
+    pub async fn reduce_order(
+        &self,
+        order_id: u128,
+        side: Side,
+        new_size: f64,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>> {
+        self.ensure_writable()?;
+        let order = self
+            .open_orders
+            .own_orders(side, &self.open_orders.oo_key)
+            .into_iter()
+            .find(|order| order.order_id == order_id)
+            .ok_or_else(|| anyhow::anyhow!("order {} not found in the cached book", order_id))?;
+
+        let new_base_lots = self.market_info.base_size_number_to_lots(new_size);
+        if new_base_lots >= order.quantity {
+            anyhow::bail!(
+                "new size of {} base lots does not reduce the order of {} base lots",
+                new_base_lots,
+                order.quantity
+            );
+        }
+
+        let mut instructions = vec![openbook_dex::instruction::cancel_order(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.market_info.event_queue,
+            side,
+            order.order_id,
+        )?];
+
+        let replacement =
+            self.new_order_instruction_lots(side, order.price, new_base_lots, OrderType::PostOnly)?;
+        let places_order = replacement.is_some();
+        instructions.extend(replacement);
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(instructions)));
+        }
+
+        // A plain cancel takes nothing from the book, so only a replacement needs the checks.
+        let (_, signature) = if places_order {
+            self.send_order_instructions(instructions).await?
+        } else {
+            self.rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), instructions)
+                .await?
+        };
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Sums the lamport fees (base and priority) the owner paid over its recent transactions.
+    ///
+    /// Transactions the owner signed but did not pay for are not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `since` - Optional. Only count transactions at or after this unix timestamp.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the total fees in lamports.
+    pub async fn total_fees_paid(&self, since: Option<i64>) -> Result<u64> {
+        self.rpc_client
+            .total_fees_paid(&self.owner.pubkey(), since)
+            .await
+    }
+
+    /// Settles the balance for a user in the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature if successful,
+    /// or an error if settling the balance fails.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     if let Some(ord_ret_type) = ob_client
+    ///         .settle_balance(true)
+    ///         .await?
+    ///     {
+    ///         match ord_ret_type {
+    ///             OrderReturnType::Instructions(insts) => {
+    ///                 println!("[*] Got Instructions: {:?}", insts);
+    ///             }
+    ///             OrderReturnType::Signature(sign) => {
+    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn settle_balance(&self, execute: bool) -> Result<Option<OrderReturnType>, Error> {
+        self.settle_balance_to(self.base_ata(), self.quote_ata(), execute)
+            .await
+    }
+
+    /// Settles the owner's free funds into the given token accounts instead of the trading ATAs.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `coin_wallet` - The base token account owned by the signer, receiving the base funds.
+    /// * `pc_wallet` - The quote token account owned by the signer, receiving the quote funds.
+    /// * `execute` - A boolean indicating whether to execute the transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instructions, or the signature of the transaction if `execute`
+    /// is set.
+    pub async fn settle_balance_to(
+        &self,
+        coin_wallet: Pubkey,
+        pc_wallet: Pubkey,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, Error> {
+        self.ensure_writable()?;
+        let instructions = vec![self.settle_funds_instruction_to(&coin_wallet, &pc_wallet)?];
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(instructions)));
+        }
+
+        let (_, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Builds a `settle_funds` instruction moving the owner's free funds to its token accounts.
+    fn settle_funds_instruction(&self) -> Result<Instruction> {
+        self.settle_funds_instruction_to(&self.base_ata(), &self.quote_ata())
+    }
+
+    /// Builds a `settle_funds` instruction moving the owner's free funds to the given accounts.
+    fn settle_funds_instruction_to(
+        &self,
+        coin_wallet: &Pubkey,
+        pc_wallet: &Pubkey,
+    ) -> Result<Instruction> {
+        let ix = openbook_dex::instruction::settle_funds(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &SPL_TOKEN_ID.parse()?,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.market_info.coin_vault,
+            coin_wallet,
+            &self.market_info.pc_vault,
+            pc_wallet,
+            None,
+            &self.market_info.vault_signer_key,
+        )?;
+
+        Ok(ix)
+    }
+
+    /// Builds an unsigned transaction placing an order, for signing by an external signer.
+    ///
+    /// The owner pays the fees, and the compute budget instructions of the RPC's send options
+    /// are included. Neither the event queue nor the crossed book guard is checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `price` - The limit price of the order, in UI units.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `order_type` - The order type (limit, IOC or post only).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the unsigned transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the order rounds down to zero base lots or the blockhash cannot be
+    /// fetched.
+    pub async fn build_place_order_transaction(
+        &self,
+        side: Side,
+        price: f64,
+        target_amount_quote: f64,
+        order_type: OrderType,
+    ) -> Result<Transaction> {
+        let ix = self
+            .new_order_instruction(side, price, target_amount_quote, order_type)?
+            .ok_or_else(|| anyhow::anyhow!("order price and quantity must be non-zero"))?;
+        self.unsigned_transaction(vec![ix]).await
+    }
+
+    /// Builds an unsigned transaction cancelling the given orders, for signing by an external
+    /// signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `orders` - The `(order_id, side)` pairs of the orders to cancel.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the unsigned transaction.
+    pub async fn build_cancel_transaction(&self, orders: &[(u128, Side)]) -> Result<Transaction> {
+        let ixs = self.cancel_order_instructions(orders)?;
+        self.unsigned_transaction(ixs).await
+    }
+
+    /// Builds an unsigned transaction settling the owner's free funds to `base_ata` and
+    /// `quote_ata`, for signing by an external signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the unsigned transaction.
+    pub async fn build_settle_transaction(&self) -> Result<Transaction> {
+        let ix = self.settle_funds_instruction()?;
+        self.unsigned_transaction(vec![ix]).await
+    }
+
+    /// Builds an unsigned transaction paid by the owner, with the RPC's compute budget.
+    async fn unsigned_transaction(&self, instructions: Vec<Instruction>) -> Result<Transaction> {
+        let instructions = self.rpc_client.with_priority_fee(instructions).await?;
+        self.rpc_client
+            .unsigned_transaction(&self.owner.pubkey(), &instructions)
+            .await
+    }
+
+    /// Builds the transaction matching orders in the market, paid for and signed by the owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of orders to match.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signed `Transaction`, ready to be sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instruction cannot be built or the blockhash cannot be fetched.
+    pub async fn build_match_orders_transaction(&self, limit: u16) -> Result<Transaction> {
+        self.ensure_writable()?;
+        let instructions = self
+            .rpc_client
+            .with_priority_fee(vec![self.match_orders_instruction(limit)?])
+            .await?;
+
+        self.rpc_client
+            .sign_transaction(&[&*self.owner], &instructions)
+            .await
+    }
+
+    /// Creates a new transaction to match orders in the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of orders to match.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the transaction was confirmed and its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is an issue with transaction creation or sending.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let result = ob_client.match_orders_transaction(100).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn match_orders_transaction(&self, limit: u16) -> Result<(bool, Signature)> {
+        self.ensure_writable()?;
+        let instructions = vec![self.match_orders_instruction(limit)?];
+
+        self.rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .await
+    }
+
+    /// Builds the instruction matching orders in the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of orders to match.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the match orders instruction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instruction cannot be built.
+    pub fn match_orders_instruction(&self, limit: u16) -> Result<Instruction> {
+        Ok(openbook_dex::instruction::match_orders(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &self.market_info.request_queue,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &self.market_info.event_queue,
+            &self.market_info.coin_vault,
+            &self.market_info.pc_vault,
+            limit,
+        )?)
+    }
+
+    /// Executes a combination of canceling all limit orders, settling balance, and placing new bid and ask orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_size_usdc_ask` - The target size in USDC for the ask order.
+    /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
+    /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
+    /// * `ask_price_jlp_usdc` - The ask price in JLP/USDC.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let target_size_usdc_ask = 0.5;
+    ///     let target_size_usdc_bid = 1.0;
+    ///     let bid_price_jlp_usdc = 1.5;
+    ///     let ask_price_jlp_usdc = 2.5;
+    ///
+    ///     let result = ob_client.cancel_settle_place(target_size_usdc_ask, target_size_usdc_bid, bid_price_jlp_usdc, ask_price_jlp_usdc).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_settle_place(
+        &mut self,
+        target_size_usdc_ask: f64,
+        target_size_usdc_bid: f64,
+        bid_price_jlp_usdc: f64,
+        ask_price_jlp_usdc: f64,
+    ) -> Result<(bool, Signature)> {
+        self.ensure_writable()?;
+        let mut instructions = Vec::new();
+
+        // Fetch recent prioritization fees
+        let r = self
+            .rpc_client
+            .fetch_recent_prioritization_fees(&[])
+            .await?;
+        let mut max_fee = 1;
+        for f in r {
+            if f.prioritization_fee > max_fee {
+                max_fee = f.prioritization_fee;
+            }
+        }
+
+        // Set compute budget and fee instructions
+        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
+        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
+        instructions.push(budget_ix);
+        instructions.push(fee_ix);
+
+        // Cancel all limit orders
+        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
+
+        // Settle balance
+        if let Some(ord_ret_type) = self.settle_balance(false).await? {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
+
+        // Place bid order
+        if let Some(ord_ret_type) = self
+            .place_limit_order(
+                target_size_usdc_bid,
+                Side::Bid,
+                0.,
+                false,
+                bid_price_jlp_usdc,
+            )
+            .await?
+        {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
+
+        // Place ask order
+        if let Some(ord_ret_type) = self
+            .place_limit_order(
+                target_size_usdc_ask,
+                Side::Ask,
+                0.,
+                false,
+                ask_price_jlp_usdc,
+            )
+            .await?
+        {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
+
+        self.send_order_instructions(instructions).await
+    }
+
+    /// Executes a combination of canceling all limit orders, settling balance, and placing a bid order.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
+    /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let result = ob_client.cancel_settle_place_bid(1.5, 1.0).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_settle_place_bid(
+        &mut self,
+        target_size_usdc_bid: f64,
+        bid_price_jlp_usdc: f64,
+    ) -> Result<(bool, Signature)> {
+        self.ensure_writable()?;
+        let mut instructions = Vec::new();
+
+        // Fetch recent prioritization fees
+        let r = self
+            .rpc_client
+            .fetch_recent_prioritization_fees(&[])
+            .await?;
+        let mut max_fee = 1;
+        for f in r {
+            if f.prioritization_fee > max_fee {
+                max_fee = f.prioritization_fee;
+            }
+        }
+
+        // Set compute budget and fee instructions
+        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
+        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
+        instructions.push(budget_ix);
+        instructions.push(fee_ix);
+
+        // Cancel all limit orders
+        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
+
+        // Settle balance
+        if let Some(ord_ret_type) = self.settle_balance(false).await? {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
 
-        let instructions = vec![ix];
+        // Place bid order
+        if let Some(ord_ret_type) = self
+            .place_limit_order(
+                target_size_usdc_bid,
+                Side::Bid,
+                0.,
+                false,
+                bid_price_jlp_usdc,
+            )
+            .await?
+        {
+            match ord_ret_type {
+                OrderReturnType::Instructions(insts) => {
+                    instructions.extend(insts);
+                }
+                OrderReturnType::Signature(_) => {}
+            }
+        }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        self.send_order_instructions(instructions).await
     }
 
-    /// Executes a combination of canceling all limit orders, settling balance, and placing new bid and ask orders.
+    /// Executes a combination of canceling all limit orders, settling balance, and placing an ask order.
     ///
     /// # Arguments
     ///
     /// * `target_size_usdc_ask` - The target size in USDC for the ask order.
-    /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
-    /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
     /// * `ask_price_jlp_usdc` - The ask price in JLP/USDC.
     ///
     /// # Returns
@@ -901,35 +4634,29 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let target_size_usdc_ask = 0.5;
-    ///     let target_size_usdc_bid = 1.0;
-    ///     let bid_price_jlp_usdc = 1.5;
-    ///     let ask_price_jlp_usdc = 2.5;
-    ///
-    ///     let result = ob_client.cancel_settle_place(target_size_usdc_ask, target_size_usdc_bid, bid_price_jlp_usdc, ask_price_jlp_usdc).await?;
+    ///     let result = ob_client.cancel_settle_place_ask(1.5, 1.0).await?;
     ///
     ///     println!("{:?}", result);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_settle_place(
+    pub async fn cancel_settle_place_ask(
         &mut self,
         target_size_usdc_ask: f64,
-        target_size_usdc_bid: f64,
-        bid_price_jlp_usdc: f64,
         ask_price_jlp_usdc: f64,
     ) -> Result<(bool, Signature)> {
+        self.ensure_writable()?;
         let mut instructions = Vec::new();
 
         // Fetch recent prioritization fees
         let r = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .fetch_recent_prioritization_fees(&[])
             .await?;
+
         let mut max_fee = 1;
         for f in r {
             if f.prioritization_fee > max_fee {
@@ -938,7 +4665,7 @@ impl OBClient {
         }
 
         // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
+        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
         let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
         instructions.push(budget_ix);
         instructions.push(fee_ix);
@@ -963,25 +4690,6 @@ impl OBClient {
             }
         }
 
-        // Place bid order
-        if let Some(ord_ret_type) = self
-            .place_limit_order(
-                target_size_usdc_bid,
-                Side::Bid,
-                0.,
-                false,
-                bid_price_jlp_usdc,
-            )
-            .await?
-        {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
-        }
-
         // Place ask order
         if let Some(ord_ret_type) = self
             .place_limit_order(
@@ -1001,17 +4709,10 @@ impl OBClient {
             }
         }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        self.send_order_instructions(instructions).await
     }
 
-    /// Executes a combination of canceling all limit orders, settling balance, and placing a bid order.
-    ///
-    /// # Arguments
-    ///
-    /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
-    /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
+    /// Executes a combination of canceling all limit orders and settling balance.
     ///
     /// # Returns
     ///
@@ -1030,27 +4731,23 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let result = ob_client.cancel_settle_place_bid(1.5, 1.0).await?;
+    ///     let result = ob_client.cancel_settle().await?;
     ///
     ///     println!("{:?}", result);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_settle_place_bid(
-        &mut self,
-        target_size_usdc_bid: f64,
-        bid_price_jlp_usdc: f64,
-    ) -> Result<(bool, Signature)> {
+    pub async fn cancel_settle(&mut self) -> Result<(bool, Signature)> {
+        self.ensure_writable()?;
         let mut instructions = Vec::new();
 
         // Fetch recent prioritization fees
         let r = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .fetch_recent_prioritization_fees(&[])
             .await?;
         let mut max_fee = 1;
         for f in r {
@@ -1085,40 +4782,22 @@ impl OBClient {
             }
         }
 
-        // Place bid order
-        if let Some(ord_ret_type) = self
-            .place_limit_order(
-                target_size_usdc_bid,
-                Side::Bid,
-                0.,
-                false,
-                bid_price_jlp_usdc,
-            )
-            .await?
-        {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
-        }
-
         self.rpc_client
             .send_and_confirm((*self.owner).insecure_clone(), instructions)
             .await
     }
 
-    /// Executes a combination of canceling all limit orders, settling balance, and placing an ask order.
+    /// Consumes events from the market for specified open orders accounts.
     ///
     /// # Arguments
     ///
-    /// * `target_size_usdc_ask` - The target size in USDC for the ask order.
-    /// * `ask_price_jlp_usdc` - The ask price in JLP/USDC.
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
+    /// * `limit` - The maximum number of events to consume.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure.
+    /// A `Result` containing the `Signature` of the transaction or a `Error` if an error occurs.
     ///
     /// # Examples
     ///
@@ -1133,96 +4812,299 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let result = ob_client.cancel_settle_place_ask(1.5, 1.0).await?;
+    ///     let open_orders_accounts = vec![ob_client.open_orders.oo_key];
+    ///     let limit = 10;
+    ///     let result = ob_client.consume_events_instruction(open_orders_accounts, limit).await?;
     ///
     ///     println!("{:?}", result);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_settle_place_ask(
-        &mut self,
-        target_size_usdc_ask: f64,
-        ask_price_jlp_usdc: f64,
+    pub async fn consume_events_instruction(
+        &self,
+        open_orders_accounts: Vec<Pubkey>,
+        limit: u16,
     ) -> Result<(bool, Signature)> {
-        let mut instructions = Vec::new();
+        let ix = self.consume_events_ix(&open_orders_accounts, limit)?;
 
-        // Fetch recent prioritization fees
-        let r = self
+        self.rpc_client
+            .send_and_confirm(self.crank_signer(), vec![ix])
+            .await
+    }
+
+    /// Builds the consume events transaction, paid for and signed by the crank payer.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
+    /// * `limit` - The maximum number of events to consume.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signed `Transaction`, ready to be sent.
+    pub async fn consume_events_transaction(
+        &self,
+        open_orders_accounts: Vec<Pubkey>,
+        limit: u16,
+    ) -> Result<Transaction> {
+        let ix = self.consume_events_ix(&open_orders_accounts, limit)?;
+        let instructions = self.rpc_client.with_priority_fee(vec![ix]).await?;
+
+        self.rpc_client
+            .sign_transaction(&[&self.crank_signer()], &instructions)
+            .await
+    }
+
+    /// Builds the consume events instruction for the given open orders accounts.
+    fn consume_events_ix(
+        &self,
+        open_orders_accounts: &[Pubkey],
+        limit: u16,
+    ) -> Result<Instruction> {
+        Ok(openbook_dex::instruction::consume_events(
+            &self.market_info.program_id,
+            open_orders_accounts.iter().collect(),
+            &self.market_info.market_address,
+            &self.market_info.event_queue,
+            &self.market_info.coin_vault,
+            &self.market_info.pc_vault,
+            limit,
+        )?)
+    }
+
+    /// Sets a dedicated keypair paying for and signing the consume events cranks.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `payer` - The cranker wallet; for permissioned markets it must be the events authority.
+    pub fn set_crank_payer(&mut self, payer: Keypair) {
+        self.crank_payer = Some(Arc::new(payer));
+    }
+
+    /// Returns the keypair signing consume events cranks: the crank payer, or else the owner.
+    fn crank_signer(&self) -> Keypair {
+        self.crank_payer
+            .as_deref()
+            .unwrap_or(&self.owner)
+            .insecure_clone()
+    }
+
+    /// Consumes permissioned events from the market for specified open orders accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
+    /// * `limit` - The maximum number of events to consume.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Signature` of the transaction or a `Error` if an error occurs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust , ignore
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let open_orders_accounts = vec![ob_client.open_orders.oo_key];
+    ///     let limit = 10;
+    ///     let result = ob_client.consume_events_permissioned_instruction(open_orders_accounts, limit).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn consume_events_permissioned_instruction(
+        &self,
+        open_orders_accounts: Vec<Pubkey>,
+        limit: u16,
+    ) -> Result<(bool, Signature)> {
+        let signer = self.crank_signer();
+        if signer.pubkey() != self.market_info.events_authority {
+            anyhow::bail!(
+                "permissioned crank must be signed by the events authority {}, not {}",
+                self.market_info.events_authority,
+                signer.pubkey()
+            );
+        }
+
+        let ix = openbook_dex::instruction::consume_events_permissioned(
+            &self.market_info.program_id,
+            open_orders_accounts.iter().collect(),
+            &self.market_info.market_address,
+            &self.market_info.event_queue,
+            &self.market_info.events_authority,
+            limit,
+        )?;
+
+        self.rpc_client.send_and_confirm(signer, vec![ix]).await
+    }
+
+    /// Reads the owner's fills from the market's event queue.
+    ///
+    /// Only events that have not been consumed by the crank yet are still in the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `limit` - The maximum number of fills returned.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the most recent `limit` fills of the owner's open orders account,
+    /// oldest first.
+    pub async fn load_fills(&self, limit: usize) -> Result<Vec<FillEvent>> {
+        let event_queue = self.load_event_queue().await?;
+        let mut fills = event_queue.fills(&self.open_orders.oo_key, &self.market_info);
+        fills.drain(..fills.len().saturating_sub(limit));
+
+        Ok(fills)
+    }
+
+    /// Fetches and decodes the event queue of the market.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the decoded `EventQueue` or an error if fetching or decoding fails.
+    pub async fn load_event_queue(&self) -> Result<EventQueue> {
+        let account = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .fetch_account(&self.market_info.event_queue)
             .await?;
 
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
+        EventQueue::parse(&account.data)
+    }
+
+    /// Fetches the trades of the unconsumed fill events of the market's event queue.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the trades, oldest first, or an error if fetching or decoding fails.
+    pub async fn recent_trades(&self) -> Result<Vec<Trade>> {
+        Ok(self.load_event_queue().await?.recent_trades())
+    }
+
+    /// Simulates an order before it is sent, unless an order with the same account set was
+    /// simulated successfully within `PRE_SIMULATION_WINDOW`.
+    ///
+    /// Orders with the same accounts differ only in price and size, which rarely decide whether
+    /// the transaction succeeds, so one simulation covers a burst of them. The simulation runs
+    /// against the latest blockhash, so only the account set is cached. A failed simulation
+    /// clears the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `instructions` - The instructions of the order about to be sent.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if a simulation ran, or `false` if a cached one was reused.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the simulation fails.
+    pub async fn pre_simulate_order(&self, instructions: &[Instruction]) -> Result<bool> {
+        let accounts: InstructionAccounts = instructions
+            .iter()
+            .map(|ix| (ix.program_id, ix.accounts.clone()))
+            .collect();
+
+        let mut cache = self.pre_simulation_cache.lock().await;
+        if let Some((simulated_at, cached)) = cache.as_ref() {
+            if simulated_at.elapsed() < PRE_SIMULATION_WINDOW && *cached == accounts {
+                return Ok(false);
             }
         }
 
-        // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
+        *cache = None;
+        self.rpc_client
+            .simulate(&self.owner.pubkey(), instructions)
+            .await?;
+        *cache = Some((Instant::now(), accounts));
 
-        // Cancel all limit orders
-        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
+        Ok(true)
+    }
+
+    /// Returns whether the market's event queue is full.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if every slot of the queue holds an unconsumed event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching or decoding the event queue fails.
+    pub async fn is_event_queue_full(&self) -> Result<bool> {
+        Ok(self.load_event_queue().await?.is_full())
+    }
+
+    /// Applies `event_queue_full_policy` before an order is sent.
+    async fn handle_full_event_queue(&self) -> Result<()> {
+        if self.event_queue_full_policy == EventQueueFullPolicy::Ignore {
+            return Ok(());
         }
 
-        // Settle balance
-        if let Some(ord_ret_type) = self.settle_balance(false).await? {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
+        let event_queue = self.load_event_queue().await?;
+        if !event_queue.is_full() {
+            return Ok(());
         }
 
-        // Place ask order
-        if let Some(ord_ret_type) = self
-            .place_limit_order(
-                target_size_usdc_ask,
-                Side::Ask,
-                0.,
-                false,
-                ask_price_jlp_usdc,
-            )
-            .await?
-        {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
+        match self.event_queue_full_policy {
+            EventQueueFullPolicy::Error => Err(OpenBookError::EventQueueFull.into()),
+            _ => {
+                let open_orders_accounts = event_queue.crank_accounts(FULL_QUEUE_CRANK_LIMIT);
+                self.consume_events_instruction(open_orders_accounts, FULL_QUEUE_CRANK_LIMIT)
+                    .await?;
+                Ok(())
             }
         }
+    }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+    /// Returns the sequence number of the current head of the event queue.
+    ///
+    /// Callers can store this value and later pass it to [`OBClient::consume_events_from`]
+    /// to resume consumption without reprocessing already handled events.
+    pub async fn event_queue_head(&self) -> Result<u64> {
+        Ok(self.load_event_queue().await?.head_seq_num())
     }
 
-    /// Executes a combination of canceling all limit orders and settling balance.
+    /// Consumes events starting from a stored sequence number.
+    ///
+    /// The program always consumes from the head of the queue, so events still queued before
+    /// `start_seq` are consumed along with up to `limit` events from `start_seq` on. A restarted
+    /// crank does not reprocess events it already handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `start_seq` - The sequence number of the first event to process.
+    /// * `limit` - The maximum number of events to consume.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure.
+    /// A `Result` containing the confirmation status, the signature of the transaction (default if
+    /// there was nothing to consume) and the sequence number to resume from next time.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
     ///
@@ -1232,80 +5114,137 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let result = ob_client.cancel_settle().await?;
+    ///     let start_seq = ob_client.event_queue_head().await?;
+    ///     let (_confirmed, _signature, next_seq) =
+    ///         ob_client.consume_events_from(start_seq, 10).await?;
     ///
-    ///     println!("{:?}", result);
+    ///     println!("Resume from: {:?}", next_seq);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_settle(&mut self) -> Result<(bool, Signature)> {
-        let mut instructions = Vec::new();
+    pub async fn consume_events_from(
+        &self,
+        start_seq: u64,
+        limit: u16,
+    ) -> Result<(bool, Signature, u64)> {
+        let event_queue = self.load_event_queue().await?;
+        let resume_seq = start_seq.max(event_queue.head_seq_num());
 
-        // Fetch recent prioritization fees
-        let r = self
+        let Some((ix, next_seq)) =
+            self.consume_events_from_instruction(&event_queue, start_seq, limit)?
+        else {
+            return Ok((false, Signature::default(), resume_seq));
+        };
+
+        let (confirmed, signature) = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .send_and_confirm(self.crank_signer(), vec![ix])
             .await?;
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
-            }
-        }
 
-        // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
+        Ok((
+            confirmed,
+            signature,
+            if confirmed { next_seq } else { resume_seq },
+        ))
+    }
 
-        // Cancel all limit orders
-        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
+    /// Builds the consume events instruction resuming from a stored sequence number.
+    ///
+    /// The program consumes from the head of the queue and stops at the first event whose open
+    /// orders account is missing. The instruction therefore covers every event from the head
+    /// through the `limit`-th event at or after `start_seq`, and lists the accounts of all of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `event_queue` - The decoded event queue of the market.
+    /// * `start_seq` - The sequence number of the first event to process.
+    /// * `limit` - The maximum number of events to consume from `start_seq` on.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instruction and the sequence number following the last event it
+    /// consumes, or `None` if no event at or after `start_seq` is queued.
+    pub fn consume_events_from_instruction(
+        &self,
+        event_queue: &EventQueue,
+        start_seq: u64,
+        limit: u16,
+    ) -> Result<Option<(Instruction, u64)>> {
+        let skipped = event_queue
+            .events
+            .iter()
+            .take_while(|event| event.seq_num < start_seq)
+            .count();
+        let count = (skipped + limit as usize)
+            .min(event_queue.events.len())
+            .min(u16::MAX as usize);
+        if count <= skipped {
+            return Ok(None);
         }
 
-        // Settle balance
-        if let Some(ord_ret_type) = self.settle_balance(false).await? {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
-        }
+        let open_orders_accounts = event_queue.crank_accounts(count as u16);
+        let ix = self.consume_events_ix(&open_orders_accounts, count as u16)?;
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        Ok(Some((ix, event_queue.events[count - 1].seq_num + 1)))
     }
 
-    /// Consumes events from the market for specified open orders accounts.
+    /// Builds the consume events instruction of one `run_crank` round.
     ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
-    /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
+    /// * `event_queue` - The decoded event queue of the market.
     /// * `limit` - The maximum number of events to consume.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the `Signature` of the transaction or a `Error` if an error occurs.
+    /// A `Result` containing the instruction consuming up to `limit` events from the head of the
+    /// queue and listing every open orders account they reference, or `None` if the queue is
+    /// empty.
+    pub fn crank_instruction(
+        &self,
+        event_queue: &EventQueue,
+        limit: u16,
+    ) -> Result<Option<Instruction>> {
+        if event_queue.events.is_empty() {
+            return Ok(None);
+        }
+
+        let open_orders_accounts = event_queue.crank_accounts(limit);
+        Ok(Some(self.consume_events_ix(&open_orders_accounts, limit)?))
+    }
+
+    /// Cranks the market's event queue until it is drained.
+    ///
+    /// Each round reads the event queue and consumes up to `limit` events, listing every open
+    /// orders account they reference. The next round starts after `interval`. The crank stops once
+    /// the queue is empty, or if a round fails to move the head of the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `interval` - The delay between two rounds.
+    /// * `limit` - The maximum number of events consumed per transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signatures of the consume events transactions, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching the event queue or sending a transaction fails, or if the
+    /// queue stops draining.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
+    /// use std::time::Duration;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1313,92 +5252,140 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
-    ///     let open_orders_accounts = vec![ob_client.open_orders.oo_key];
-    ///     let limit = 10;
-    ///     let result = ob_client.consume_events_instruction(open_orders_accounts, limit).await?;
+    ///     let signatures = ob_client.run_crank(Duration::from_secs(1), 10).await?;
     ///
-    ///     println!("{:?}", result);
+    ///     println!("Cranked in {} transactions", signatures.len());
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn consume_events_instruction(
-        &self,
-        open_orders_accounts: Vec<Pubkey>,
-        limit: u16,
-    ) -> Result<(bool, Signature)> {
-        let ix = openbook_dex::instruction::consume_events(
-            &self.market_info.program_id,
-            open_orders_accounts.iter().collect(),
-            &self.market_info.market_address,
-            &self.market_info.event_queue,
-            &self.market_info.coin_vault,
-            &self.market_info.pc_vault,
-            limit,
-        )
-        .unwrap();
+    pub async fn run_crank(&self, interval: Duration, limit: u16) -> Result<Vec<Signature>> {
+        let mut signatures = Vec::new();
+        let mut last_head = None;
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        loop {
+            let event_queue = self.load_event_queue().await?;
+            let ix = match self.crank_instruction(&event_queue, limit)? {
+                Some(ix) => ix,
+                None => return Ok(signatures),
+            };
+
+            let head = event_queue.head_seq_num();
+            if last_head == Some(head) {
+                anyhow::bail!(
+                    "event queue is not draining: head still at sequence number {}",
+                    head
+                );
+            }
+            last_head = Some(head);
+
+            let (confirmed, signature) = self
+                .rpc_client
+                .send_and_confirm(self.crank_signer(), vec![ix])
+                .await?;
+            debug!("[*] Consume events {} confirmed: {}", signature, confirmed);
+            signatures.push(signature);
+
+            tokio::time::sleep(interval).await;
+        }
     }
 
-    /// Consumes permissioned events from the market for specified open orders accounts.
+    /// Builds the instructions cranking the owner's events and settling the owner's funds.
+    ///
+    /// The consume events instruction is only built if one of the first `limit` events of the
+    /// queue belongs to the owner; it lists every open orders account of those events, as the
+    /// program stops at the first event whose account is missing. The settle instruction is only
+    /// built if the crank releases funds or the open orders account already holds free funds.
     ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
-    /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
+    /// * `event_queue` - The decoded event queue of the market.
+    /// * `free_balances` - The `(native_coin_free, native_pc_free)` balances of the owner's open
+    ///   orders account.
     /// * `limit` - The maximum number of events to consume.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the `Signature` of the transaction or a `Error` if an error occurs.
-    ///
-    /// # Examples
+    /// A `Result` containing the instructions, empty if there is nothing to crank or settle.
+    pub fn crank_and_settle_instructions(
+        &self,
+        event_queue: &EventQueue,
+        free_balances: (u64, u64),
+        limit: u16,
+    ) -> Result<Vec<Instruction>> {
+        let events: Vec<&Event> = event_queue.events.iter().take(limit as usize).collect();
+        let needs_crank = events
+            .iter()
+            .any(|event| event.owner == self.open_orders.oo_key);
+        let needs_settle = needs_crank || free_balances != (0, 0);
+
+        let mut instructions = Vec::new();
+        if needs_crank {
+            let open_orders_accounts = event_queue.crank_accounts(limit);
+            instructions.push(self.consume_events_ix(&open_orders_accounts, limit)?);
+        }
+
+        if needs_settle {
+            instructions.push(self.settle_funds_instruction()?);
+        }
+
+        Ok(instructions)
+    }
+
+    /// Cranks the owner's pending events and settles the owner's funds, in a single transaction
+    /// when both fit.
     ///
-    /// ```rust , ignore
-    /// use openbook::v1::orders::OrderReturnType;
-    /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// Each step is skipped when it has nothing to do: the crank when none of the first `limit`
+    /// events belong to the owner, the settle when no funds are free or released.
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let commitment = CommitmentConfig::confirmed();
+    /// # Arguments
     ///
-    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `limit` - The maximum number of events to consume.
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    /// # Returns
     ///
-    ///     let open_orders_accounts = vec![ob_client.open_orders.oo_key];
-    ///     let limit = 10;
-    ///     let result = ob_client.consume_events_permissioned_instruction(open_orders_accounts, limit).await?;
+    /// A `Result` containing the signatures of the sent transactions, empty if nothing was sent.
     ///
-    ///     println!("{:?}", result);
+    /// # Errors
     ///
-    ///     Ok(())
-    /// }
-    /// ```
-    pub async fn consume_events_permissioned_instruction(
-        &self,
-        open_orders_accounts: Vec<Pubkey>,
-        limit: u16,
-    ) -> Result<(bool, Signature)> {
-        let ix = openbook_dex::instruction::consume_events_permissioned(
-            &self.market_info.program_id,
-            open_orders_accounts.iter().collect(),
-            &self.market_info.market_address,
-            &self.market_info.event_queue,
-            &self.market_info.events_authority,
-            limit,
-        )
-        .unwrap();
+    /// Returns an error if the event queue or open orders account cannot be loaded, or if a
+    /// transaction is not confirmed, in which case the settle is not sent after a failed crank.
+    pub async fn crank_and_settle(&self, limit: u16) -> Result<Vec<Signature>> {
+        self.ensure_writable()?;
+        let event_queue = self.load_event_queue().await?;
+        let oo_data = self
+            .rpc_client
+            .fetch_account(&self.open_orders.oo_key)
+            .await?
+            .data;
+        let free_balances = open_orders_free_balances(&oo_data)?;
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        let instructions =
+            self.crank_and_settle_instructions(&event_queue, free_balances, limit)?;
+        if instructions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut signatures = Vec::new();
+        for group in split_into_transactions(instructions, &self.owner.pubkey()) {
+            let (confirmed, signature) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), group)
+                .await?;
+            if !confirmed {
+                anyhow::bail!(
+                    "crank and settle transaction {} was not confirmed",
+                    signatures.len() + 1
+                );
+            }
+            signatures.push(signature);
+        }
+
+        Ok(signatures)
     }
 
     /// Loads open orders accounts for the owner, filtering them based on bids and asks.
@@ -1409,7 +5396,11 @@ impl OBClient {
     ///
     /// # Returns
     ///
-    /// A `Result` containing a vector of `Account` representing open orders accounts or a boxed `Error` if an error occurs.
+    /// A `Result` containing a vector of `Account` representing open orders accounts or an `Error` if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MarketError::Rpc` if the accounts cannot be fetched.
     ///
     /// # Examples
     ///
@@ -1424,7 +5415,7 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
     ///     let result = ob_client.load_orders_for_owner().await?;
     ///
@@ -1433,7 +5424,7 @@ impl OBClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn load_orders_for_owner(&mut self) -> Result<Vec<u128>, Box<dyn std::error::Error>> {
+    pub async fn load_orders_for_owner(&mut self) -> Result<Vec<u128>, MarketError> {
         let mut bids = self.load_bids()?;
         let asks = self.load_asks()?;
         bids.extend(asks);
@@ -1470,7 +5461,7 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
     ///     let open_orders_accounts = vec![];
     ///     let result = ob_client.filter_for_open_orders(ob_client.market_info.bids_address, ob_client.market_info.asks_address, open_orders_accounts);
@@ -1504,7 +5495,11 @@ impl OBClient {
     ///
     /// # Returns
     ///
-    /// A `Result` containing a vector of `Account` representing open orders accounts or a boxed `Error` if an error occurs.
+    /// A `Result` containing a vector of `Account` representing open orders accounts or an `Error` if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MarketError::Rpc` if the accounts cannot be fetched.
     ///
     /// # Examples
     ///
@@ -1518,7 +5513,7 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true).await?;
     ///
     ///     let result = ob_client.find_open_orders_accounts_for_owner(ob_client.open_orders.oo_key, 5000).await?;
     ///
@@ -1531,13 +5526,10 @@ impl OBClient {
         &mut self,
         owner_address: Pubkey,
         cache_duration_ms: u128,
-    ) -> Result<OpenOrders, Box<dyn std::error::Error>> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis();
+    ) -> Result<OpenOrders, MarketError> {
+        let now = get_unix_millis();
         if let Some(cache_entry) = self.open_orders_cache.get(&owner_address) {
-            if now - cache_entry.ts < cache_duration_ms as u128 {
+            if cache_entry.is_fresh(now, cache_duration_ms) {
                 return Ok(cache_entry.open_orders.clone());
             }
         }
@@ -1554,4 +5546,69 @@ impl OBClient {
 
         Ok(self.open_orders.clone())
     }
+
+    /// Serializes the open orders cache so it can be persisted, e.g. to disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The serialized cache, to be restored later with [`OBClient::load_cache`].
+    pub fn dump_cache(&self) -> Vec<u8> {
+        dump_open_orders_cache(&self.open_orders_cache)
+    }
+
+    /// Restores an open orders cache produced by [`OBClient::dump_cache`].
+    ///
+    /// Restored entries keep their original timestamps, so entries older than the
+    /// `cache_duration_ms` passed to `find_open_orders_accounts_for_owner` are refetched while
+    /// fresh ones are reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `bytes` - The serialized cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid serialized cache.
+    pub fn load_cache(&mut self, bytes: &[u8]) -> Result<()> {
+        self.open_orders_cache
+            .extend(load_open_orders_cache(bytes)?);
+        Ok(())
+    }
+}
+
+/// Unpacks the accounts of a multiple-accounts read of `N` market accounts, in request order.
+///
+/// Fails if an account does not exist or the node answered with another number of accounts.
+fn market_accounts<const N: usize>(accounts: Vec<Option<Account>>) -> Result<[Account; N]> {
+    let accounts = accounts
+        .into_iter()
+        .map(|account| account.ok_or_else(|| anyhow::anyhow!("market account not found")))
+        .collect::<Result<Vec<_>>>()?;
+
+    accounts.try_into().map_err(|accounts: Vec<Account>| {
+        anyhow::anyhow!("expected {} market accounts, got {}", N, accounts.len())
+    })
+}
+
+/// Computes the change of the token balance held by `owner` for `mint` within a transaction.
+fn token_balance_delta(meta: &UiTransactionStatusMeta, owner: &str, mint: &Pubkey) -> f64 {
+    let mint = mint.to_string();
+    let total = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| {
+        Option::<Vec<UiTransactionTokenBalance>>::from(balances.clone())
+            .unwrap_or_default()
+            .iter()
+            .filter(|balance| {
+                balance.mint == mint
+                    && Option::<String>::from(balance.owner.clone()).as_deref() == Some(owner)
+            })
+            .map(|balance| balance.ui_token_amount.ui_amount.unwrap_or_default())
+            .sum::<f64>()
+    };
+
+    total(&meta.post_token_balances) - total(&meta.pre_token_balances)
 }
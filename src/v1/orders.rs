@@ -1,20 +1,72 @@
 //! This module contains structs and functions related to open orders on the Solana blockchain.
 
+use crate::error::OpenBookError;
 use crate::rpc::Rpc;
-use crate::v1::traits::OpenOrdersT;
+use crate::utils::{get_unix_millis, u64_slice_to_pubkey};
+use crate::v1::{
+    events::{ACCOUNT_HEAD_PADDING, ACCOUNT_TAIL_PADDING},
+    market::{taker_fee, taker_fee_rate, FixedPrice, Market},
+    traits::OpenOrdersT,
+};
 use anyhow::{Error, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_client::rpc_config::RpcSendTransactionConfig;
+use futures_util::{future, Stream, StreamExt};
+use openbook_dex::{critbit::Slab, fees::FeeTier, matching::Side};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction,
+    account::Account,
     instruction::Instruction,
     pubkey::Pubkey,
     signature::{Signature, Signer},
     signer::keypair::Keypair,
-    transaction::Transaction,
 };
-use std::fmt::{Debug, Formatter};
-use tracing::{debug, error};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+};
+use tracing::debug;
+
+/// Length of the account flags preceding the slab in bids and asks accounts.
+const SLAB_ACCOUNT_FLAGS_LEN: usize = 8;
+
+/// Length of the slab header (bump index, free list length and head, root and leaf count).
+const SLAB_HEADER_LEN: usize = 32;
+
+/// Length of a slab node.
+const SLAB_NODE_LEN: usize = 72;
+
+/// Size of an on-chain open orders account, including the `serum` and `padding` markers.
+pub const OPEN_ORDERS_ACCOUNT_LEN: usize = 3228;
+
+/// Offset of the market address in an open orders account.
+pub const OPEN_ORDERS_MARKET_OFFSET: usize = 13;
+
+/// Offset of the owner address in an open orders account.
+pub const OPEN_ORDERS_OWNER_OFFSET: usize = 45;
+
+/// Offset of the free native base (coin) balance in an open orders account.
+const OPEN_ORDERS_NATIVE_COIN_FREE_OFFSET: usize = 77;
+
+/// Offset of the total native base (coin) balance in an open orders account.
+const OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET: usize = 85;
+
+/// Offset of the free native quote (pc) balance in an open orders account.
+const OPEN_ORDERS_NATIVE_PC_FREE_OFFSET: usize = 93;
+
+/// Offset of the total native quote (pc) balance in an open orders account.
+const OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET: usize = 101;
+
+/// Offset of the free slot bitmap in an open orders account; a cleared bit marks a used slot.
+const OPEN_ORDERS_FREE_SLOT_BITS_OFFSET: usize = 109;
+
+/// Offset of the bitmap marking which used slots hold bids.
+const OPEN_ORDERS_IS_BID_BITS_OFFSET: usize = 125;
+
+/// Offset of the order id slots in an open orders account.
+const OPEN_ORDERS_ORDERS_OFFSET: usize = 141;
+
+/// Number of order slots in an open orders account.
+pub const OPEN_ORDERS_SLOTS: usize = 128;
 
 #[derive(Clone, Default, BorshDeserialize, BorshSerialize)]
 pub struct OpenOrders {
@@ -50,6 +102,18 @@ pub struct OpenOrders {
 
     /// The total amount of quote currency (pc) in the open orders account.
     pub quote_total: f64,
+
+    /// Every resting bid of the market, best price first.
+    pub bids: Vec<Order>,
+
+    /// Every resting ask of the market, best price first.
+    pub asks: Vec<Order>,
+
+    /// The slot at which the bids and asks were loaded.
+    pub slot: u64,
+
+    /// Order ids held in the used slots of the open orders account, as last read from it.
+    pub order_slots: Vec<u128>,
 }
 
 impl Debug for OpenOrders {
@@ -66,6 +130,10 @@ impl Debug for OpenOrders {
         writeln!(f, "        open_bids_prices: {:?}", self.open_bids_prices)?;
         writeln!(f, "        base_total: {:?}", self.base_total)?;
         writeln!(f, "        quote_total: {:?}", self.quote_total)?;
+        writeln!(f, "        bids: {} orders", self.bids.len())?;
+        writeln!(f, "        asks: {} orders", self.asks.len())?;
+        writeln!(f, "        slot: {:?}", self.slot)?;
+        writeln!(f, "        order_slots: {:?}", self.order_slots)?;
         writeln!(f, "    }}")
     }
 }
@@ -96,7 +164,13 @@ impl OpenOrdersT for OpenOrders {
         let mut oo_account = Self::default();
 
         let _ = oo_account
-            .make_create_account_transaction(&rpc_client, program_id, &keypair, market_address)
+            .make_create_account_transaction(
+                &rpc_client,
+                program_id,
+                &keypair,
+                market_address,
+                OPEN_ORDERS_SLOTS,
+            )
             .await?;
 
         Ok(oo_account)
@@ -113,6 +187,7 @@ impl OpenOrdersT for OpenOrders {
     /// * `program_id` - The program ID associated with the open orders.
     /// * `keypair` - The keypair of the wallet owner, used for signing the transaction.
     /// * `market_account` - The public key of the market associated with the open orders account.
+    /// * `order_capacity` - The number of order slots the account must hold.
     ///
     /// # Returns
     ///
@@ -120,27 +195,27 @@ impl OpenOrdersT for OpenOrders {
     ///
     /// # Errors
     ///
-    /// Returns a `Box<dyn Error>` if there is an error during the RPC call or transaction creation.
+    /// Returns a `Box<dyn Error>` if there is an error during the RPC call or transaction creation,
+    /// if the program has no account layout with `order_capacity` slots, or if the transaction
+    /// is not confirmed.
     async fn make_create_account_transaction(
         &mut self,
         connection: &Rpc,
         program_id: Pubkey,
         keypair: &Keypair,
         market_account: Pubkey,
+        order_capacity: usize,
     ) -> Result<Pubkey, Error> {
         let new_account_address = Keypair::new();
-        let space = 0;
-        let minimum_balance = connection
-            .inner()
-            .get_minimum_balance_for_rent_exemption(space)
-            .await?;
+        let space = open_orders_account_space(order_capacity)?;
+        let minimum_balance = connection.fetch_rent_exemption(space).await?;
 
         let instruction = solana_sdk::system_instruction::create_account(
             &keypair.pubkey(),
             &new_account_address.pubkey(),
             minimum_balance,
             space as u64,
-            &keypair.pubkey(),
+            &program_id,
         );
         let init_ix = openbook_dex::instruction::init_open_orders(
             &program_id,
@@ -154,54 +229,400 @@ impl OpenOrdersT for OpenOrders {
             new_account_address.pubkey()
         );
 
-        let mut instructions = Vec::new();
-        let r = connection
-            .inner()
-            .get_recent_prioritization_fees(&[])
-            .await
-            .unwrap();
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
-            }
+        let (confirmed, signature) = connection
+            .send_and_confirm_with_signers(
+                &[keypair, &new_account_address],
+                vec![instruction, init_ix],
+            )
+            .await?;
+        if !confirmed {
+            anyhow::bail!(
+                "transaction creating open orders account {} was not confirmed",
+                new_account_address.pubkey()
+            );
         }
+        debug!("[*] Transaction successful, signature: {:?}", signature);
 
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
+        self.oo_key = new_account_address.pubkey();
 
-        instructions.push(instruction);
-        instructions.push(init_ix);
+        Ok(new_account_address.pubkey())
+    }
+}
 
-        debug!("[*] Using Pubkey: {}", &keypair.pubkey().to_string());
+impl OpenOrders {
+    /// Returns `true` if the cached book is crossed or locked (best bid >= best ask).
+    ///
+    /// An empty side never makes the book crossed.
+    pub fn is_book_crossed(&self) -> bool {
+        self.max_bid != 0 && self.min_ask != 0 && self.max_bid >= self.min_ask
+    }
 
-        let recent_hash = connection.inner().get_latest_blockhash().await?;
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&keypair.pubkey()),
-            &[&new_account_address, &keypair],
-            recent_hash,
-        );
+    /// Looks up a resting order of `oo_key` by its client order id in the cached book.
+    ///
+    /// # Arguments
+    ///
+    /// * `oo_key` - The open orders account owning the order.
+    /// * `client_order_id` - The client order id to look up.
+    ///
+    /// # Returns
+    ///
+    /// The side and the order, or `OpenBookError::ClientOrderIdNotFound` if the cached book does
+    /// not contain it.
+    pub fn find_client_order(
+        &self,
+        oo_key: &Pubkey,
+        client_order_id: u64,
+    ) -> Result<(Side, Order)> {
+        let matches =
+            |order: &&Order| order.owner == *oo_key && order.client_order_id == client_order_id;
 
-        let mut config = RpcSendTransactionConfig::default();
-        config.skip_preflight = true;
+        if let Some(order) = self.bids.iter().find(matches) {
+            return Ok((Side::Bid, *order));
+        }
+        if let Some(order) = self.asks.iter().find(matches) {
+            return Ok((Side::Ask, *order));
+        }
 
-        let result = connection
-            .inner()
-            .send_transaction_with_config(&txn, config)
-            .await;
+        Err(OpenBookError::ClientOrderIdNotFound(client_order_id).into())
+    }
 
-        match result {
-            Ok(sig) => debug!("[*] Transaction successful, signature: {:?}", sig),
-            Err(err) => error!("[*] Transaction failed: {:?}", err),
+    /// Returns the resting orders of `oo_key` on `side` of the cached book, best first.
+    pub fn own_orders(&self, side: Side, oo_key: &Pubkey) -> Vec<Order> {
+        let ladder = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
         };
 
-        self.oo_key = new_account_address.pubkey();
+        ladder
+            .iter()
+            .filter(|order| order.owner == *oo_key)
+            .copied()
+            .collect()
+    }
 
-        Ok(new_account_address.pubkey())
+    /// Computes the balances locked by the resting orders of `oo_key` in the cached book.
+    ///
+    /// Asks lock their base size and bids lock their quote notional (fees excluded).
+    ///
+    /// # Arguments
+    ///
+    /// * `oo_key` - The open orders account owning the orders.
+    /// * `market` - The market of the book, used to convert lots into native units.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(base_locked, quote_locked)` in native units.
+    pub fn locked_balances(&self, oo_key: &Pubkey, market: &Market) -> (u64, u64) {
+        let base_locked = self
+            .own_orders(Side::Ask, oo_key)
+            .iter()
+            .map(|order| order.quantity * market.coin_lot_size)
+            .sum();
+        let quote_locked = self
+            .own_orders(Side::Bid, oo_key)
+            .iter()
+            .map(|order| order.quantity * order.price * market.pc_lot_size)
+            .sum();
+
+        (base_locked, quote_locked)
     }
+
+    /// Updates the base and quote totals from the raw data of the open orders account.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The raw open orders account data, including the `serum` and `padding` markers.
+    /// * `market` - The market of the open orders account, used to convert to UI units.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not an open orders account.
+    pub fn update_from_account(&mut self, data: &[u8], market: &Market) -> Result<()> {
+        if data.len() != OPEN_ORDERS_ACCOUNT_LEN {
+            anyhow::bail!("invalid open orders account size: {} bytes", data.len());
+        }
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        self.base_total = u64_at(OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET) as f64
+            / 10f64.powi(market.coin_decimals as i32);
+        self.quote_total = u64_at(OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET) as f64
+            / 10f64.powi(market.pc_decimals as i32);
+        self.order_slots = open_orders_order_ids(data)?;
+
+        Ok(())
+    }
+
+    /// Returns the best bid of the cached book in UI units, or `None` if there are no bids.
+    pub fn best_bid(&self, market: &Market) -> Option<f64> {
+        (self.max_bid != 0).then(|| market.price_lots_to_number(self.max_bid))
+    }
+
+    /// Returns the best ask of the cached book in UI units, or `None` if there are no asks.
+    pub fn best_ask(&self, market: &Market) -> Option<f64> {
+        (self.min_ask != 0).then(|| market.price_lots_to_number(self.min_ask))
+    }
+
+    /// Returns the mid price of the cached book in UI units, or `None` if a side is empty.
+    pub fn mid_price(&self, market: &Market) -> Option<f64> {
+        Some((self.best_bid(market)? + self.best_ask(market)?) / 2.0)
+    }
+
+    /// Aggregates the cached ladders into the top `depth` price levels of each side.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market the book belongs to, used to convert lots into UI units.
+    /// * `depth` - The maximum number of price levels kept per side.
+    ///
+    /// # Returns
+    ///
+    /// The `(price, size)` levels of both sides in UI units, best price first.
+    pub fn order_book(&self, market: &Market, depth: usize) -> OrderBook {
+        OrderBook {
+            bids: aggregate_levels(&self.bids, market, depth),
+            asks: aggregate_levels(&self.asks, market, depth),
+        }
+    }
+
+    /// Previews the fills and cost of a taker order against the cached book.
+    ///
+    /// The order walks the opposite ladder best price first and is charged the taker fee of
+    /// `fee_tier` on the quote it exchanges.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market the book belongs to, used to convert lots into UI units.
+    /// * `fee_tier` - The fee tier of the taker order.
+    /// * `side` - The side of the taker order.
+    /// * `base_qty` - The base quantity to trade, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// The `TakerPreview`, partially filled if the book is too shallow for `base_qty`.
+    pub fn preview_taker(
+        &self,
+        market: &Market,
+        fee_tier: FeeTier,
+        side: Side,
+        base_qty: f64,
+    ) -> TakerPreview {
+        let limit_price_lots = match side {
+            Side::Bid => u64::MAX,
+            Side::Ask => 0,
+        };
+
+        self.walk_ladder(market, fee_tier, side, limit_price_lots, base_qty)
+    }
+
+    /// Walks the opposite ladder best price first, filling `base_qty` at the resting prices that
+    /// cross `limit_price_lots` and charging the taker fee of `fee_tier` on the quote exchanged.
+    fn walk_ladder(
+        &self,
+        market: &Market,
+        fee_tier: FeeTier,
+        side: Side,
+        limit_price_lots: u64,
+        base_qty: f64,
+    ) -> TakerPreview {
+        let ladder = match side {
+            Side::Bid => &self.asks,
+            Side::Ask => &self.bids,
+        };
+
+        let mut filled_qty = 0.0;
+        let mut quote_cost = 0.0;
+        for order in ladder {
+            let crosses = match side {
+                Side::Bid => order.price <= limit_price_lots,
+                Side::Ask => order.price >= limit_price_lots,
+            };
+            let remaining = base_qty - filled_qty;
+            if !crosses || remaining <= 0.0 {
+                break;
+            }
+            let quantity = market
+                .base_size_lots_to_number(order.quantity)
+                .min(remaining);
+            filled_qty += quantity;
+            quote_cost += quantity * market.price_lots_to_number(order.price);
+        }
+
+        let fee = quote_cost * taker_fee_rate(fee_tier);
+        let total_cost = match side {
+            Side::Bid => quote_cost + fee,
+            Side::Ask => quote_cost - fee,
+        };
+        let avg_price = if filled_qty > 0.0 {
+            quote_cost / filled_qty
+        } else {
+            0.0
+        };
+
+        TakerPreview {
+            avg_price,
+            quote_cost,
+            fee,
+            total_cost,
+            filled_qty,
+        }
+    }
+
+    /// Simulates a limit order against the cached book, without sending anything.
+    ///
+    /// The order walks the opposite ladder best price first while the resting prices are within
+    /// `price`, like `preview_taker`; whatever does not fill would rest on the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market the book belongs to, used to convert between lots and UI units.
+    /// * `fee_tier` - The fee tier of the order, used for the taker fee on the fills.
+    /// * `side` - The side of the order.
+    /// * `price` - The limit price of the order, in UI units.
+    /// * `qty` - The base quantity of the order, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// The `SimResult` of the order.
+    pub fn simulate_order(
+        &self,
+        market: &Market,
+        fee_tier: FeeTier,
+        side: Side,
+        price: f64,
+        qty: f64,
+    ) -> SimResult {
+        let limit_price_lots = market.price_number_to_lots(price);
+        let fills = self.walk_ladder(market, fee_tier, side, limit_price_lots, qty);
+
+        SimResult {
+            filled_qty: fills.filled_qty,
+            avg_price: fills.avg_price,
+            fee: fills.fee,
+            resting_qty: (qty - fills.filled_qty).max(0.0),
+        }
+    }
+
+    /// Returns the exact prices of the client's open bids, in the order of `open_bids_prices`.
+    pub fn open_bids_fixed(&self, market: &Market) -> Vec<FixedPrice> {
+        self.open_bids
+            .iter()
+            .map(|order_id| market.price_lots_to_fixed((order_id >> 64) as u64))
+            .collect()
+    }
+
+    /// Returns the exact prices of the client's open asks, in the order of `open_asks_prices`.
+    pub fn open_asks_fixed(&self, market: &Market) -> Vec<FixedPrice> {
+        self.open_asks
+            .iter()
+            .map(|order_id| market.price_lots_to_fixed((order_id >> 64) as u64))
+            .collect()
+    }
+
+    /// Returns `true` if `order_id` belongs to the open orders account of the client.
+    ///
+    /// The id is checked against the client's resting bids and asks in the cached book and
+    /// against the order slots last read from the open orders account, which also hold orders
+    /// that left the book but were not yet consumed from the event queue.
+    pub fn owns_order(&self, order_id: u128) -> bool {
+        self.open_bids.contains(&order_id)
+            || self.open_asks.contains(&order_id)
+            || self.order_slots.contains(&order_id)
+    }
+
+    /// Fails with `OpenBookError::CrossedBook` if the cached book is crossed.
+    pub fn ensure_book_not_crossed(&self) -> Result<()> {
+        if self.is_book_crossed() {
+            return Err(OpenBookError::CrossedBook.into());
+        }
+        Ok(())
+    }
+
+    /// Computes the queue position of a resting order at its price level.
+    ///
+    /// Orders are stored in price-time priority, so every order found before `order_id` at the
+    /// same price is ahead of it in the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - The id of the resting order.
+    /// * `market` - The market used to convert lots into UI units.
+    ///
+    /// # Returns
+    ///
+    /// The `QueuePosition` of the order, or `None` if the order is not resting on the book.
+    pub fn queue_position(&self, order_id: u128, market: &Market) -> Option<QueuePosition> {
+        let ladder = [&self.bids, &self.asks]
+            .into_iter()
+            .find(|ladder| ladder.iter().any(|o| o.order_id == order_id))?;
+        let idx = ladder.iter().position(|o| o.order_id == order_id)?;
+        let price = ladder[idx].price;
+
+        let ahead = ladder[..idx].iter().filter(|o| o.price == price);
+        let (lots_ahead, orders_ahead) =
+            ahead.fold((0, 0), |(lots, count), o| (lots + o.quantity, count + 1));
+
+        Some(QueuePosition {
+            size_ahead_ui: market.base_size_lots_to_number(lots_ahead),
+            orders_ahead,
+        })
+    }
+}
+
+/// Depth-limited snapshot of the book, aggregated per price level.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderBook {
+    /// The `(price, size)` bid levels in UI units, best (highest) price first.
+    pub bids: Vec<(f64, f64)>,
+    /// The `(price, size)` ask levels in UI units, best (lowest) price first.
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// Sums a best-first ladder into at most `depth` `(price, size)` levels in UI units.
+fn aggregate_levels(ladder: &[Order], market: &Market, depth: usize) -> Vec<(f64, f64)> {
+    let mut levels: Vec<(u64, u64)> = Vec::new();
+    for order in ladder {
+        match levels.last_mut() {
+            Some((price, quantity)) if *price == order.price => *quantity += order.quantity,
+            _ if levels.len() == depth => break,
+            _ => levels.push((order.price, order.quantity)),
+        }
+    }
+
+    levels
+        .into_iter()
+        .map(|(price, quantity)| {
+            (
+                market.price_lots_to_number(price),
+                market.base_size_lots_to_number(quantity),
+            )
+        })
+        .collect()
+}
+
+/// A resting order read from the bids or asks slab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct Order {
+    /// The order id, encoding the price and the sequence number.
+    pub order_id: u128,
+    /// The limit price in lots.
+    pub price: u64,
+    /// The remaining quantity in base lots.
+    pub quantity: u64,
+    /// The open orders account owning the order.
+    pub owner: Pubkey,
+    /// The client order id of the order.
+    pub client_order_id: u64,
+}
+
+/// Position of a resting order within its price level.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueuePosition {
+    /// The total size resting ahead of the order, in base UI units.
+    pub size_ahead_ui: f64,
+    /// The number of orders resting ahead of the order.
+    pub orders_ahead: usize,
 }
 
 #[derive(Clone, Default, BorshDeserialize, BorshSerialize, Debug)]
@@ -210,8 +631,809 @@ pub struct OpenOrdersCacheEntry {
     pub ts: u128,
 }
 
+impl OpenOrdersCacheEntry {
+    /// Creates an entry for `open_orders` stamped with the current time.
+    pub fn new(open_orders: OpenOrders) -> Self {
+        Self {
+            open_orders,
+            ts: get_unix_millis(),
+        }
+    }
+
+    /// Returns `true` if the entry is younger than `cache_duration_ms` at time `now` (unix ms).
+    pub fn is_fresh(&self, now: u128, cache_duration_ms: u128) -> bool {
+        now.saturating_sub(self.ts) < cache_duration_ms
+    }
+}
+
+/// Serializes an open orders cache so it can be persisted across restarts.
+pub fn dump_open_orders_cache(cache: &HashMap<Pubkey, OpenOrdersCacheEntry>) -> Vec<u8> {
+    cache
+        .try_to_vec()
+        .expect("serializing into a Vec never fails")
+}
+
+/// Restores an open orders cache previously produced by [`dump_open_orders_cache`].
+///
+/// Entries keep their original timestamps, so stale ones are refetched on the next lookup.
+pub fn load_open_orders_cache(bytes: &[u8]) -> Result<HashMap<Pubkey, OpenOrdersCacheEntry>> {
+    Ok(HashMap::try_from_slice(bytes)?)
+}
+
 #[derive(Debug)]
 pub enum OrderReturnType {
     Instructions(Vec<Instruction>),
     Signature(Signature),
 }
+
+/// Clamps the size of a reduce-only order to the owner's current base position.
+///
+/// The V1 program has no native reduce-only flag, so this is enforced client-side: an ask can
+/// only sell down a long position and a bid can only buy back a short one, in both cases net of
+/// the orders already resting on that side.
+///
+/// # Arguments
+///
+/// * `side` - The side of the order.
+/// * `size` - The requested order size in base UI units.
+/// * `position` - The signed base position of the owner in UI units; negative when short.
+/// * `resting` - The base size of the owner's orders already resting on `side`, in UI units.
+///
+/// # Returns
+///
+/// The clamped order size, or an error if the order would increase exposure.
+pub fn clamp_reduce_only(side: Side, size: f64, position: f64, resting: f64) -> Result<f64> {
+    let reducible = match side {
+        Side::Ask => position,
+        Side::Bid => -position,
+    } - resting;
+    if reducible <= 0.0 {
+        anyhow::bail!(
+            "reduce-only {:?} would increase exposure: position {}, resting {}",
+            side,
+            position,
+            resting
+        );
+    }
+
+    Ok(size.min(reducible))
+}
+
+/// Generates a client order id inside the namespace of the given prefix.
+///
+/// The prefix occupies the high 32 bits of the id, partitioning the id space between strategies
+/// sharing the same open orders account, while the low 32 bits come from `id`. A zero prefix
+/// disables namespacing and keeps all 64 bits of `id`.
+///
+/// # Arguments
+///
+/// * `prefix` - The namespace prefix of the strategy, or 0 for none.
+/// * `id` - The id to place in the namespace, only its low 32 bits are kept under a prefix.
+///
+/// # Returns
+///
+/// The namespaced client order id.
+pub fn namespaced_client_order_id(prefix: u32, id: u64) -> u64 {
+    if prefix == 0 {
+        return id;
+    }
+    ((prefix as u64) << 32) | (id & u32::MAX as u64)
+}
+
+/// Returns `true` if the client order id belongs to the namespace of the given prefix.
+///
+/// Every id belongs to the zero prefix, which disables namespacing.
+pub fn client_order_id_in_namespace(prefix: u32, client_order_id: u64) -> bool {
+    prefix == 0 || (client_order_id >> 32) as u32 == prefix
+}
+
+/// Recovers the side of an order from its 128-bit order id.
+///
+/// The high 64 bits of an id hold the price and the low 64 bits the sequence number of the
+/// order, which the matching engine stores complemented for bids so that earlier bids sort
+/// first. Sequence numbers never reach `2^63`, so the top bit of the low half is set for bids
+/// only.
+///
+/// # Arguments
+///
+/// * `order_id` - The order id.
+///
+/// # Returns
+///
+/// The side the order rests on.
+pub fn order_id_side(order_id: u128) -> Side {
+    if (order_id as u64) >> 63 == 1 {
+        Side::Bid
+    } else {
+        Side::Ask
+    }
+}
+
+/// An order sent to the market, with the client order id it can be tracked and cancelled by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlacedOrder {
+    /// The signature of the transaction that placed the order.
+    pub signature: Signature,
+    /// The client order id attached to the order.
+    pub client_order_id: u64,
+}
+
+/// A taker order sent to the market, with what it filled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TakerOrder {
+    /// The signature and client order id of the order.
+    pub placed: PlacedOrder,
+    /// The outcome of the order.
+    pub result: TakerResult,
+}
+
+/// Outcome of a taker (IOC or market) order.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TakerResult {
+    /// The base quantity filled, in UI units.
+    pub filled_base: f64,
+    /// The quote quantity exchanged for the filled base, in UI units.
+    pub filled_quote: f64,
+    /// The average fill price, zero when nothing was filled.
+    pub avg_price: f64,
+    /// The requested base quantity that was not filled and got cancelled, in UI units.
+    pub cancelled_base: f64,
+}
+
+impl TakerResult {
+    /// Reconstructs the outcome of a taker order from the owner's token balance deltas.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the taker order.
+    /// * `requested_base` - The base quantity requested by the order, in UI units.
+    /// * `base_delta` - The change of the owner's base balance, in UI units.
+    /// * `quote_delta` - The change of the owner's quote balance, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// The `TakerResult`, with zeros for the filled amounts if the order did not fill.
+    pub fn from_balance_deltas(
+        side: Side,
+        requested_base: f64,
+        base_delta: f64,
+        quote_delta: f64,
+    ) -> Self {
+        let (filled_base, filled_quote) = match side {
+            Side::Bid => (base_delta, -quote_delta),
+            Side::Ask => (-base_delta, quote_delta),
+        };
+        let filled_base = filled_base.max(0.0);
+        let filled_quote = filled_quote.max(0.0);
+        let avg_price = if filled_base > 0.0 {
+            filled_quote / filled_base
+        } else {
+            0.0
+        };
+
+        Self {
+            filled_base,
+            filled_quote,
+            avg_price,
+            cancelled_base: (requested_base - filled_base).max(0.0),
+        }
+    }
+
+    /// Reconstructs the outcome of a taker order from the owner's wallet deltas and the change
+    /// of its open orders account.
+    ///
+    /// A deposit into the open orders account moves funds out of the wallet without trading
+    /// them, so the free and locked open orders balances are added back before attributing the
+    /// deltas to fills.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the taker order.
+    /// * `requested_base` - The base quantity requested by the order, in UI units.
+    /// * `wallet_deltas` - The change of the owner's base and quote token balances, in UI units.
+    /// * `before` - The open orders balances before the order.
+    /// * `after` - The open orders balances after the order.
+    ///
+    /// # Returns
+    ///
+    /// The `TakerResult`, with zeros for the filled amounts if the order did not fill.
+    pub fn from_account_deltas(
+        side: Side,
+        requested_base: f64,
+        wallet_deltas: (f64, f64),
+        before: &Balances,
+        after: &Balances,
+    ) -> Self {
+        let base_delta = wallet_deltas.0 + (after.base_free + after.base_locked)
+            - (before.base_free + before.base_locked);
+        let quote_delta = wallet_deltas.1 + (after.quote_free + after.quote_locked)
+            - (before.quote_free + before.quote_locked);
+
+        Self::from_balance_deltas(side, requested_base, base_delta, quote_delta)
+    }
+}
+
+/// Expected outcome of a limit order simulated against the cached book.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimResult {
+    /// The base quantity that would fill immediately, in UI units.
+    pub filled_qty: f64,
+    /// The volume-weighted price of the fills, or zero if nothing fills.
+    pub avg_price: f64,
+    /// The taker fee charged on the fills, in UI quote units.
+    pub fee: f64,
+    /// The base quantity that would rest on the book, in UI units.
+    pub resting_qty: f64,
+}
+
+/// Cost preview of a prospective taker order, shown before it is submitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TakerPreview {
+    /// The average fill price before fees, zero when nothing would fill.
+    pub avg_price: f64,
+    /// The quote quantity exchanged for the filled base before fees, in UI units.
+    pub quote_cost: f64,
+    /// The taker fee charged on `quote_cost`, in UI units.
+    pub fee: f64,
+    /// The quote paid including the fee for a bid, or received net of the fee for an ask.
+    pub total_cost: f64,
+    /// The base quantity that would fill, in UI units.
+    pub filled_qty: f64,
+}
+
+/// Reads the free (settleable) native base and quote balances of a raw open orders account.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+///
+/// # Returns
+///
+/// A tuple of `(native_coin_free, native_pc_free)`, or an error if the data is not an open orders
+/// account.
+pub fn open_orders_free_balances(data: &[u8]) -> Result<(u64, u64)> {
+    if data.len() != OPEN_ORDERS_ACCOUNT_LEN {
+        anyhow::bail!("invalid open orders account size: {} bytes", data.len());
+    }
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+    Ok((
+        u64_at(OPEN_ORDERS_NATIVE_COIN_FREE_OFFSET),
+        u64_at(OPEN_ORDERS_NATIVE_PC_FREE_OFFSET),
+    ))
+}
+
+/// Free and locked balances of an open orders account, in UI units, with its live orders.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Balances {
+    /// The base that can be settled.
+    pub base_free: f64,
+    /// The base tied up in resting asks.
+    pub base_locked: f64,
+    /// The quote that can be settled.
+    pub quote_free: f64,
+    /// The quote tied up in resting bids.
+    pub quote_locked: f64,
+    /// The ids of the orders in the used slots of the account, in slot order.
+    pub order_ids: Vec<u128>,
+}
+
+/// Reads the free and locked balances and the live order ids of a raw open orders account.
+///
+/// The locked balances are the account totals minus the free balances.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+/// * `market` - The market of the open orders account, used to convert to UI units.
+///
+/// # Returns
+///
+/// The balances, or an error if the data is not an open orders account.
+pub fn open_orders_balances(data: &[u8], market: &Market) -> Result<Balances> {
+    let (coin_free, pc_free) = open_orders_free_balances(data)?;
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let coin_total = u64_at(OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET);
+    let pc_total = u64_at(OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET);
+    let base_d_factor = 10f64.powi(market.coin_decimals as i32);
+    let quote_d_factor = 10f64.powi(market.pc_decimals as i32);
+
+    Ok(Balances {
+        base_free: coin_free as f64 / base_d_factor,
+        base_locked: coin_total.saturating_sub(coin_free) as f64 / base_d_factor,
+        quote_free: pc_free as f64 / quote_d_factor,
+        quote_locked: pc_total.saturating_sub(pc_free) as f64 / quote_d_factor,
+        order_ids: open_orders_order_ids(data)?,
+    })
+}
+
+/// Tells whether a raw open orders account holds no orders and no funds, so it can be closed.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+///
+/// # Returns
+///
+/// `true` if the base and quote totals are zero and no slot is used, or an error if the data is
+/// not an open orders account.
+pub fn open_orders_is_empty(data: &[u8]) -> Result<bool> {
+    let live_orders = open_orders_live_orders(data)?;
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+    Ok(live_orders.is_empty()
+        && u64_at(OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET) == 0
+        && u64_at(OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET) == 0)
+}
+
+/// Returns the size of an open orders account holding `order_capacity` order slots.
+///
+/// The V1 program has a single open orders layout of [`OPEN_ORDERS_SLOTS`] slots and rejects
+/// accounts of any other size, so larger capacities cannot be requested.
+///
+/// # Arguments
+///
+/// * `order_capacity` - The number of order slots the account must hold.
+///
+/// # Returns
+///
+/// The account size in bytes, or an error if the program has no layout with that capacity.
+pub fn open_orders_account_space(order_capacity: usize) -> Result<usize> {
+    if order_capacity != OPEN_ORDERS_SLOTS {
+        anyhow::bail!(
+            "unsupported open orders capacity: {} orders, the program only supports {}",
+            order_capacity,
+            OPEN_ORDERS_SLOTS
+        );
+    }
+
+    Ok(OPEN_ORDERS_ACCOUNT_LEN)
+}
+
+/// Counts the free order slots of a raw open orders account.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+///
+/// # Returns
+///
+/// The number of free slots, or an error if the account size does not match a supported
+/// capacity.
+pub fn free_order_slots(data: &[u8]) -> Result<usize> {
+    if data.len() != open_orders_account_space(OPEN_ORDERS_SLOTS)? {
+        anyhow::bail!("invalid open orders account size: {} bytes", data.len());
+    }
+    let free_slot_bits = u128::from_le_bytes(
+        data[OPEN_ORDERS_FREE_SLOT_BITS_OFFSET..OPEN_ORDERS_FREE_SLOT_BITS_OFFSET + 16]
+            .try_into()
+            .unwrap(),
+    );
+
+    Ok(free_slot_bits.count_ones() as usize)
+}
+
+/// Reads the order ids held in the used slots of a raw open orders account.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+///
+/// # Returns
+///
+/// The order ids of the used slots in slot order, or an error if the data is not an open orders
+/// account.
+pub fn open_orders_order_ids(data: &[u8]) -> Result<Vec<u128>> {
+    Ok(open_orders_live_orders(data)?
+        .into_iter()
+        .map(|(order_id, _)| order_id)
+        .collect())
+}
+
+/// Reads the live orders held in the used slots of a raw open orders account with their sides.
+///
+/// This does not need the bids and asks accounts, so it still works when the book cannot be
+/// parsed or the local order list was lost.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+///
+/// # Returns
+///
+/// The `(order_id, side)` of every used slot in slot order, or an error if the data is not an
+/// open orders account.
+pub fn open_orders_live_orders(data: &[u8]) -> Result<Vec<(u128, Side)>> {
+    if data.len() != OPEN_ORDERS_ACCOUNT_LEN {
+        anyhow::bail!("invalid open orders account size: {} bytes", data.len());
+    }
+    let u128_at =
+        |offset: usize| u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap());
+
+    let free_slot_bits = u128_at(OPEN_ORDERS_FREE_SLOT_BITS_OFFSET);
+    let is_bid_bits = u128_at(OPEN_ORDERS_IS_BID_BITS_OFFSET);
+    Ok((0..OPEN_ORDERS_SLOTS)
+        .filter(|slot| free_slot_bits & (1u128 << slot) == 0)
+        .map(|slot| {
+            let side = if is_bid_bits & (1u128 << slot) != 0 {
+                Side::Bid
+            } else {
+                Side::Ask
+            };
+            (u128_at(OPEN_ORDERS_ORDERS_OFFSET + slot * 16), side)
+        })
+        .collect())
+}
+
+/// Sums the free native base and quote balances of several raw open orders accounts.
+///
+/// # Returns
+///
+/// A tuple of `(base, pc)` totals, or an error if any account is not an open orders account.
+pub fn aggregate_free_balances<'a>(
+    accounts: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<(u64, u64)> {
+    accounts
+        .into_iter()
+        .try_fold((0u64, 0u64), |(base, pc), data| {
+            let (coin_free, pc_free) = open_orders_free_balances(data)?;
+            Ok((base + coin_free, pc + pc_free))
+        })
+}
+
+/// Description of an order to place, in UI units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderSpec {
+    /// The side of the order.
+    pub side: Side,
+    /// The limit price of the order.
+    pub price: f64,
+    /// The size of the order in base units.
+    pub size: f64,
+}
+
+/// Mint of wrapped SOL (WSOL), the SPL token form of native SOL.
+pub const WSOL_MINT: Pubkey = solana_sdk::pubkey!("So11111111111111111111111111111111111111112");
+
+/// Mainnet mint of USD Coin (USDC).
+pub const USDC_MINT: Pubkey = solana_sdk::pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+
+/// Mainnet mint of Tether USD (USDT).
+pub const USDT_MINT: Pubkey = solana_sdk::pubkey!("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
+
+/// Resolves a well-known token symbol, case-insensitively, to its canonical mainnet mint.
+///
+/// # Arguments
+///
+/// * `symbol` - The token symbol, one of `USDC`, `USDT`, `WSOL` or `SOL`.
+///
+/// # Returns
+///
+/// The mint, or `None` if the symbol is not a well-known one.
+pub fn known_mint(symbol: &str) -> Option<Pubkey> {
+    match symbol.to_ascii_uppercase().as_str() {
+        "USDC" => Some(USDC_MINT),
+        "USDT" => Some(USDT_MINT),
+        "WSOL" | "SOL" => Some(WSOL_MINT),
+        _ => None,
+    }
+}
+
+/// Wrapping needed around a sequence of trades on a market with a WSOL leg, in lamports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolPlan {
+    /// Lamports to wrap into WSOL before the first trade.
+    pub wrap: u64,
+    /// Lamports of WSOL to unwrap after the last trade, leaving the starting WSOL balance intact.
+    pub unwrap: u64,
+}
+
+/// Plans the minimal WSOL wrap and final unwrap needed to execute `trades` in order.
+///
+/// Each trade is assumed to fill completely at its limit price. Spending trades are charged the
+/// taker fee of `fee_tier` on top of their notional and proceeds are credited net of it, so the
+/// plan errs on the side of wrapping too much rather than too little.
+///
+/// # Arguments
+///
+/// * `market` - The market the trades are placed on.
+/// * `fee_tier` - The fee tier of the trades.
+/// * `trades` - The intended trades, in execution order.
+/// * `wsol_balance` - The current WSOL balance, in lamports.
+///
+/// # Returns
+///
+/// The plan, or an all-zero plan if neither leg of the market is WSOL.
+pub fn sol_plan(
+    market: &Market,
+    fee_tier: FeeTier,
+    trades: &[OrderSpec],
+    wsol_balance: u64,
+) -> SolPlan {
+    let sol_is_base = market.base_mint == WSOL_MINT;
+    if !sol_is_base && market.quote_mint != WSOL_MINT {
+        return SolPlan::default();
+    }
+
+    let mut balance = wsol_balance as i128;
+    let mut lowest = balance;
+    for trade in trades {
+        let base_lots = market.base_size_number_to_lots(trade.size) as i128;
+        let base_native = base_lots * market.coin_lot_size as i128;
+        let notional = (base_lots
+            * market.price_number_to_lots(trade.price) as i128
+            * market.pc_lot_size as i128)
+            .min(u64::MAX as i128);
+        let fee = taker_fee(fee_tier, notional as u64) as i128;
+
+        balance += match (sol_is_base, trade.side) {
+            (true, Side::Bid) => base_native,
+            (true, Side::Ask) => -base_native,
+            (false, Side::Bid) => -(notional + fee),
+            (false, Side::Ask) => notional - fee,
+        };
+        lowest = lowest.min(balance);
+    }
+
+    let wrap = (-lowest).max(0);
+    let unwrap = (balance + wrap - wsol_balance as i128).max(0);
+
+    SolPlan {
+        wrap: wrap.min(u64::MAX as i128) as u64,
+        unwrap: unwrap.min(u64::MAX as i128) as u64,
+    }
+}
+
+/// Derives the seeded open orders account of `owner` on `market`.
+///
+/// Seeded accounts are created with `create_account_with_seed`, so no extra signer is needed.
+pub fn open_orders_seeded_address(market: &Market, owner: &Pubkey) -> Result<(Pubkey, String)> {
+    let seed = market.market_address.to_string()[..32].to_string();
+    let address = Pubkey::create_with_seed(owner, &seed, &market.program_id)?;
+    Ok((address, seed))
+}
+
+/// Fetches all open orders accounts of `owner` on `market`.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The RPC client used to query the program accounts.
+/// * `market` - The market the open orders accounts belong to.
+/// * `owner` - The owner of the open orders accounts.
+///
+/// # Returns
+///
+/// A `Result` containing the addresses and raw accounts of the owner's open orders accounts.
+pub async fn fetch_open_orders_accounts(
+    rpc_client: &Rpc,
+    market: &Market,
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let filters = vec![
+        RpcFilterType::DataSize(OPEN_ORDERS_ACCOUNT_LEN as u64),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            OPEN_ORDERS_MARKET_OFFSET,
+            market.market_address.to_bytes().to_vec(),
+        )),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            OPEN_ORDERS_OWNER_OFFSET,
+            owner.to_bytes().to_vec(),
+        )),
+    ];
+
+    rpc_client
+        .fetch_program_accounts(&market.program_id, filters)
+        .await
+}
+
+/// Looks up an open orders account `owner` already has on `market`.
+///
+/// The seeded account is checked first, as it costs a single account read; otherwise the
+/// first account found by [`fetch_open_orders_accounts`] is returned.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The RPC client used to query the accounts.
+/// * `market` - The market the open orders account belongs to.
+/// * `owner` - The owner of the open orders account.
+///
+/// # Returns
+///
+/// A `Result` containing the address of an existing open orders account, or `None` if the
+/// owner has none on this market.
+pub async fn find_existing_open_orders_account(
+    rpc_client: &Rpc,
+    market: &Market,
+    owner: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    let (seeded, _) = open_orders_seeded_address(market, owner)?;
+    if let Some(account) = rpc_client.get_account(&seeded).await? {
+        if account.owner == market.program_id {
+            return Ok(Some(seeded));
+        }
+    }
+
+    Ok(fetch_open_orders_accounts(rpc_client, market, owner)
+        .await?
+        .first()
+        .map(|(address, _)| *address))
+}
+
+/// Builds the instructions creating and initializing a seeded open orders account.
+///
+/// # Arguments
+///
+/// * `market` - The market the open orders account is created for.
+/// * `owner` - The owner and payer of the open orders account.
+/// * `lamports` - The rent exempt balance of an open orders account.
+///
+/// # Returns
+///
+/// The address of the open orders account and the `create_account_with_seed` and
+/// `init_open_orders` instructions.
+pub fn create_open_orders_instructions(
+    market: &Market,
+    owner: &Pubkey,
+    lamports: u64,
+) -> Result<(Pubkey, Vec<Instruction>)> {
+    let (address, seed) = open_orders_seeded_address(market, owner)?;
+
+    let create_ix = solana_sdk::system_instruction::create_account_with_seed(
+        owner,
+        &address,
+        owner,
+        &seed,
+        lamports,
+        OPEN_ORDERS_ACCOUNT_LEN as u64,
+        &market.program_id,
+    );
+    let init_ix = openbook_dex::instruction::init_open_orders(
+        &market.program_id,
+        &address,
+        owner,
+        &market.market_address,
+        None,
+    )?;
+
+    Ok((address, vec![create_ix, init_ix]))
+}
+
+/// Decodes a stream of raw open orders account updates into `OpenOrders` states.
+///
+/// Each update is applied on top of `open_orders` with [`OpenOrders::update_from_account`];
+/// malformed updates are skipped.
+///
+/// # Arguments
+///
+/// * `updates` - The raw account data of each update.
+/// * `open_orders` - The state the updates are applied to.
+/// * `market` - The market of the open orders account.
+///
+/// # Returns
+///
+/// A stream yielding the updated state for every valid update.
+pub fn open_orders_updates(
+    updates: impl Stream<Item = Vec<u8>>,
+    open_orders: OpenOrders,
+    market: Market,
+) -> impl Stream<Item = OpenOrders> {
+    updates.filter_map(move |data| {
+        let mut open_orders = open_orders.clone();
+        let updated = open_orders.update_from_account(&data, &market);
+        future::ready(updated.ok().map(|_| open_orders))
+    })
+}
+
+/// Decodes a stream of raw open orders account updates into `Balances`.
+///
+/// Each update is decoded with [`open_orders_balances`]; malformed updates are skipped.
+///
+/// # Arguments
+///
+/// * `updates` - The raw account data of each update.
+/// * `market` - The market of the open orders account.
+///
+/// # Returns
+///
+/// A stream yielding the balances and live order ids of every valid update.
+pub fn open_orders_balance_updates(
+    updates: impl Stream<Item = Vec<u8>>,
+    market: Market,
+) -> impl Stream<Item = Balances> {
+    updates.filter_map(move |data| future::ready(open_orders_balances(&data, &market).ok()))
+}
+
+/// What to do with a resting quote to bring it to a target price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteAction {
+    /// The resting order already sits at the target price; keep it and its queue priority.
+    Keep,
+    /// The resting order is at another price and must be cancelled and replaced.
+    Replace,
+    /// There is no resting order; place a new one.
+    Place,
+}
+
+/// Decides whether a resting quote needs to be cancel-replaced to reach `target_price_lots`.
+///
+/// # Arguments
+///
+/// * `resting` - The current resting order on the quoted side, if any.
+/// * `target_price_lots` - The target price, in price lots.
+///
+/// # Returns
+///
+/// `QuoteAction::Keep` if the resting order already matches the target price within a tick.
+pub fn quote_action(resting: Option<&Order>, target_price_lots: u64) -> QuoteAction {
+    match resting {
+        Some(order) if order.price == target_price_lots => QuoteAction::Keep,
+        Some(_) => QuoteAction::Replace,
+        None => QuoteAction::Place,
+    }
+}
+
+/// Drains a bids or asks slab into a ladder of orders, best price first.
+///
+/// Orders at the same price are yielded in time priority. The slab is emptied in the process.
+///
+/// # Arguments
+///
+/// * `slab` - The slab to drain.
+/// * `side` - The side of the slab, `Side::Bid` yielding the highest prices first.
+///
+/// # Returns
+///
+/// The orders of the slab, best first.
+pub fn drain_slab(slab: &mut Slab, side: Side) -> Vec<Order> {
+    let mut ladder = Vec::new();
+    loop {
+        let node = match side {
+            Side::Bid => slab.remove_max(),
+            Side::Ask => slab.remove_min(),
+        };
+        match node {
+            Some(node) => ladder.push(Order {
+                order_id: node.order_id(),
+                price: node.price().get(),
+                quantity: node.quantity(),
+                owner: Pubkey::from(u64_slice_to_pubkey(node.owner())),
+                client_order_id: node.client_order_id(),
+            }),
+            None => break,
+        }
+    }
+    ladder
+}
+
+/// Decodes the raw data of a bids or asks account into a ladder of orders, best price first.
+///
+/// # Arguments
+///
+/// * `data` - The raw account data, including the `serum` and `padding` markers.
+/// * `side` - The side of the account.
+///
+/// # Returns
+///
+/// The orders resting in the account.
+///
+/// # Errors
+///
+/// Returns `OpenBookError::InvalidSlabAccount` if the data is not a slab header followed by at
+/// least one whole node, wrapped in the account markers.
+pub fn parse_slab_orders(data: &[u8], side: Side) -> Result<Vec<Order>> {
+    let framing = ACCOUNT_HEAD_PADDING + SLAB_ACCOUNT_FLAGS_LEN + ACCOUNT_TAIL_PADDING;
+    let nodes_len = data.len().saturating_sub(framing + SLAB_HEADER_LEN);
+    if data.len() < framing + SLAB_HEADER_LEN + SLAB_NODE_LEN
+        || nodes_len % SLAB_NODE_LEN != 0
+        || &data[..ACCOUNT_HEAD_PADDING] != b"serum"
+    {
+        return Err(OpenBookError::InvalidSlabAccount(data.len()).into());
+    }
+
+    let mut buf = data
+        [ACCOUNT_HEAD_PADDING + SLAB_ACCOUNT_FLAGS_LEN..data.len() - ACCOUNT_TAIL_PADDING]
+        .to_vec();
+    let slab = Slab::new(&mut buf);
+
+    Ok(drain_slab(slab, side))
+}
@@ -0,0 +1,76 @@
+//! This module contains the inventory and average entry price tracking built from fills.
+
+use openbook_dex::matching::Side;
+
+/// A fill of one of the owner's orders, in UI units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fill {
+    /// The side of the owner's order.
+    pub side: Side,
+    /// The execution price.
+    pub price: f64,
+    /// The filled size in base units.
+    pub size: f64,
+    /// The fee paid in quote units; negative for a rebate.
+    pub fee: f64,
+}
+
+/// A running position folded from fills.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Position {
+    /// The base inventory; negative when short.
+    pub base: f64,
+    /// The quote inventory, net of fees.
+    pub quote: f64,
+    /// The average entry price of the open base inventory, zero when flat.
+    pub avg_entry_price: f64,
+    /// The PnL realized by fills reducing the position, in quote units, before fees.
+    pub realized_pnl: f64,
+}
+
+impl Position {
+    /// Applies a single fill to the position.
+    ///
+    /// Fills adding to the position move the average entry price; fills reducing it realize PnL
+    /// against the average entry price, which stays unchanged. A fill flipping the position
+    /// opens the remainder at the fill price.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill` - The fill to apply.
+    pub fn apply(&mut self, fill: &Fill) {
+        let signed_size = match fill.side {
+            Side::Bid => fill.size,
+            Side::Ask => -fill.size,
+        };
+        self.quote -= signed_size * fill.price + fill.fee;
+
+        let new_base = self.base + signed_size;
+        if self.base == 0.0 || self.base.signum() == signed_size.signum() {
+            self.avg_entry_price =
+                (self.avg_entry_price * self.base + fill.price * signed_size) / new_base;
+        } else {
+            let closed = signed_size.abs().min(self.base.abs());
+            self.realized_pnl += closed * (fill.price - self.avg_entry_price) * self.base.signum();
+
+            if new_base == 0.0 {
+                self.avg_entry_price = 0.0;
+            } else if new_base.signum() != self.base.signum() {
+                self.avg_entry_price = fill.price;
+            }
+        }
+        self.base = new_base;
+    }
+}
+
+/// Folds a sequence of fills into `position`, in order.
+///
+/// # Arguments
+///
+/// * `position` - The position to update.
+/// * `fills` - The fills to apply, oldest first.
+pub fn apply_fills(position: &mut Position, fills: &[Fill]) {
+    for fill in fills {
+        position.apply(fill);
+    }
+}
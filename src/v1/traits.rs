@@ -67,6 +67,7 @@ pub trait OpenOrdersT {
     /// * `connection` - The RPC client for interacting with the Solana blockchain.
     /// * `keypair` - The keypair of the wallet owner, used for signing the transaction.
     /// * `market_account` - The public key of the market associated with the open orders account.
+    /// * `order_capacity` - The number of order slots the account must hold.
     ///
     /// # Returns
     ///
@@ -74,12 +75,15 @@ pub trait OpenOrdersT {
     ///
     /// # Errors
     ///
-    /// Returns a `Box<dyn Error>` if there is an error during the RPC call or transaction creation.
+    /// Returns a `Box<dyn Error>` if there is an error during the RPC call or transaction creation,
+    /// if the program has no account layout with `order_capacity` slots, or if the transaction
+    /// is not confirmed.
     async fn make_create_account_transaction(
         &mut self,
         connection: &Rpc,
         program_id: Pubkey,
         keypair: &Keypair,
         market_account: Pubkey,
+        order_capacity: usize,
     ) -> Result<Pubkey, Error>;
 }
@@ -1259,11 +1259,7 @@ impl OBClient {
     }
 
     pub async fn get_token_balance(&self, ata: &Pubkey) -> Result<f64> {
-        let r = self
-            .rpc_client
-            .inner()
-            .get_token_account_balance(&ata)
-            .await?;
+        let r = self.rpc_client.fetch_token_account_balance(&ata).await?;
         Ok(r.ui_amount.unwrap())
     }
 
@@ -0,0 +1,64 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use openbook::pubkey::Pubkey;
+use openbook::v1::events::{EVENT_FLAG_FILL, EVENT_LEN};
+use openbook::v1::market::Market;
+use openbook_dex::critbit::{LeafNode, Slab};
+use openbook_dex::fees::FeeTier;
+
+/// A market with 9 base and 6 quote decimals, trading base lots of 0.001 for quote lots of one
+/// native unit, like SOL/USDC.
+pub fn test_market() -> Market {
+    Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        coin_lot_size: 1_000_000,
+        pc_lot_size: 1,
+        ..Default::default()
+    }
+}
+
+/// Builds bids or asks account data holding one order of `owner` per price, wrapped in the
+/// `serum` and `padding` markers.
+pub fn slab_account_bytes(owner: &Pubkey, prices: &[u64]) -> Vec<u8> {
+    let owner_bytes = owner.to_bytes();
+    let owner_words: [u64; 4] = std::array::from_fn(|i| {
+        u64::from_le_bytes(owner_bytes[i * 8..(i + 1) * 8].try_into().unwrap())
+    });
+
+    let mut slab_bytes = vec![0u8; 32 + 72 * 8];
+    let slab = Slab::new(&mut slab_bytes);
+    for (seq, price) in prices.iter().enumerate() {
+        let key = ((*price as u128) << 64) | (!(seq as u64) as u128);
+        let leaf = LeafNode::new(0, key, owner_words, 10, FeeTier::Base, seq as u64);
+        slab.insert_leaf(&leaf).unwrap();
+    }
+
+    let mut data = b"serum".to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&slab_bytes);
+    data.extend_from_slice(b"padding");
+    data
+}
+
+/// Builds event queue account data of `capacity` events holding one fill per owner from
+/// `head`, wrapped in the `serum` and `padding` markers.
+pub fn event_queue_bytes(capacity: usize, head: u64, seq_num: u64, owners: &[Pubkey]) -> Vec<u8> {
+    let mut data = b"serum".to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&head.to_le_bytes());
+    data.extend_from_slice(&(owners.len() as u64).to_le_bytes());
+    data.extend_from_slice(&seq_num.to_le_bytes());
+
+    let mut buf = vec![0u8; capacity * EVENT_LEN];
+    for (i, owner) in owners.iter().enumerate() {
+        let idx = (head as usize + i) % capacity;
+        let event = &mut buf[idx * EVENT_LEN..(idx + 1) * EVENT_LEN];
+        event[0] = EVENT_FLAG_FILL;
+        event[48..80].copy_from_slice(&owner.to_bytes());
+    }
+    data.extend_from_slice(&buf);
+    data.extend_from_slice(b"padding");
+    data
+}
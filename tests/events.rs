@@ -0,0 +1,133 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::{event_queue_bytes, test_market};
+use openbook::matching::Side;
+use openbook::pubkey::Pubkey;
+use openbook::v1::events::{
+    Event, EventQueue, FillEvent, EVENT_FLAG_BID, EVENT_FLAG_FILL, EVENT_FLAG_MAKER, EVENT_LEN,
+};
+use openbook::v1::market::Market;
+
+#[test]
+fn test_events_from_mid_queue_skips_earlier_events() {
+    let owners = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    let data = event_queue_bytes(4, 3, 10, &owners);
+
+    let queue = EventQueue::parse(&data).unwrap();
+    assert_eq!(queue.head_seq_num(), 7);
+    assert_eq!(queue.events.len(), 3);
+
+    let remaining: Vec<_> = queue.events_from(8).collect();
+    assert_eq!(remaining.len(), 2);
+    assert_eq!(remaining[0].seq_num, 8);
+    assert_eq!(remaining[0].owner, owners[1]);
+    assert_eq!(remaining[1].owner, owners[2]);
+    assert!(remaining.iter().all(|e| e.is_fill()));
+}
+
+#[test]
+fn test_event_parse_rejects_short_data() {
+    let owner = Pubkey::new_unique();
+    let mut data = vec![0u8; EVENT_LEN];
+    data[0] = EVENT_FLAG_FILL;
+    data[48..80].copy_from_slice(&owner.to_bytes());
+
+    let event = Event::parse(3, &data).unwrap();
+    assert_eq!(event.seq_num, 3);
+    assert_eq!(event.owner, owner);
+    assert!(event.is_fill());
+
+    assert!(Event::parse(3, &data[..EVENT_LEN - 1]).is_err());
+    assert!(Event::parse(3, &[]).is_err());
+}
+
+#[test]
+fn test_event_queue_at_capacity_is_full() {
+    let owners = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let full = EventQueue::parse(&event_queue_bytes(2, 1, 5, &owners)).unwrap();
+    assert!(full.is_full());
+
+    let partial = EventQueue::parse(&event_queue_bytes(3, 1, 5, &owners)).unwrap();
+    assert!(!partial.is_full());
+}
+
+#[test]
+fn test_fill_events_are_converted_to_ui_units() {
+    let market = test_market();
+
+    let maker_bid = Event {
+        event_flags: EVENT_FLAG_FILL | EVENT_FLAG_BID | EVENT_FLAG_MAKER,
+        native_qty_released: 500_000_000,
+        native_qty_paid: 1_000_000,
+        order_id: (2_000u128 << 64) | (!7u64 as u128),
+        client_order_id: 42,
+        ..Default::default()
+    };
+    assert_eq!(
+        maker_bid.fill(&market),
+        Some(FillEvent {
+            side: Side::Bid,
+            price: 2.0,
+            size: 0.5,
+            client_order_id: 42,
+            maker: true,
+        })
+    );
+
+    // The taker swept two levels; its fee was deducted from the quote it received.
+    let taker_ask = Event {
+        event_flags: EVENT_FLAG_FILL,
+        native_qty_paid: 1_000_000_000,
+        native_qty_released: 2_098_000,
+        native_fee_or_rebate: 2_000,
+        order_id: 1_900u128 << 64,
+        ..Default::default()
+    };
+    let fill = taker_ask.fill(&market).unwrap();
+    assert_eq!(fill.side, Side::Ask);
+    assert!(!fill.maker);
+    assert_eq!(fill.size, 1.0);
+    assert!((fill.price - 2.1).abs() < 1e-9);
+
+    assert_eq!(Event::default().fill(&market), None);
+}
+
+#[test]
+fn test_fills_keep_only_the_owner_events() {
+    let owner = Pubkey::new_unique();
+    let owners = [owner, Pubkey::new_unique(), owner];
+    let queue = EventQueue::parse(&event_queue_bytes(4, 0, 3, &owners)).unwrap();
+
+    let fills = queue.fills(&owner, &Market::default());
+    assert_eq!(fills.len(), 2);
+    assert!(fills
+        .iter()
+        .all(|fill| fill.side == Side::Ask && !fill.maker));
+}
+
+#[test]
+fn test_crank_accounts_are_deduplicated_and_sorted_within_the_limit() {
+    let mut first = [0u8; 32];
+    first[0] = 2;
+    let mut second = [0u8; 32];
+    second[8] = 1;
+    let mut skipped = [0u8; 32];
+    skipped[0] = 1;
+    let (first, second, skipped) = (
+        Pubkey::new_from_array(first),
+        Pubkey::new_from_array(second),
+        Pubkey::new_from_array(skipped),
+    );
+    let data = event_queue_bytes(8, 0, 4, &[first, second, first, skipped]);
+
+    let queue = EventQueue::parse(&data).unwrap();
+    // `second` has the smaller first word, so it sorts ahead despite its larger first byte.
+    assert_eq!(queue.crank_accounts(3), vec![second, first]);
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::slab_account_bytes;
+use openbook::matching::Side;
+use openbook::pubkey::Pubkey;
+use openbook::v1::geyser::{GeyserAdapter, MarketUpdate};
+use openbook::v1::market::Market;
+
+#[test]
+fn test_bids_update_emits_book_update() {
+    let market = Market {
+        market_address: Pubkey::new_unique(),
+        bids_address: Pubkey::new_unique(),
+        asks_address: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    let adapter = GeyserAdapter::new(market.clone());
+    let owner = Pubkey::new_unique();
+    let data = slab_account_bytes(&owner, &[100, 102, 101]);
+
+    let update = adapter
+        .on_account_update(&market.bids_address, 77, &data)
+        .unwrap();
+    match update {
+        Some(MarketUpdate::Book { slot, side, orders }) => {
+            assert_eq!(slot, 77);
+            assert_eq!(side, Side::Bid);
+            assert_eq!(
+                orders.iter().map(|order| order.price).collect::<Vec<_>>(),
+                vec![102, 101, 100]
+            );
+            assert!(orders.iter().all(|order| order.owner == owner));
+        }
+        other => panic!("expected a book update, got {:?}", other),
+    }
+
+    let unrelated = adapter
+        .on_account_update(&Pubkey::new_unique(), 77, &data)
+        .unwrap();
+    assert!(unrelated.is_none());
+}
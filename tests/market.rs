@@ -0,0 +1,592 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{slab_account_bytes, test_market};
+use openbook::account::Account;
+use openbook::error::OpenBookError;
+use openbook::matching::Side;
+use openbook::pubkey::Pubkey;
+use openbook::rpc::Rpc;
+use openbook::rpc_client::RpcClient;
+use openbook::utils::create_account_info_from_account;
+use openbook::v1::market::{
+    ensure_market_account, mint_decimals, FixedPrice, Market, RegistryEntry,
+};
+use openbook::v1::orders::{parse_slab_orders, OpenOrders};
+use openbook::v1::traits::MarketInfo;
+use openbook_dex::state::gen_vault_signer_key;
+use serde_json::json;
+use solana_client::rpc_request::RpcRequest;
+use spl_associated_token_account::get_associated_token_address;
+use std::collections::HashMap;
+
+#[test]
+fn test_market_parameters_reflect_loaded_market() {
+    let market = Market {
+        program_id: Pubkey::new_unique(),
+        market_address: Pubkey::new_unique(),
+        coin_decimals: 9,
+        pc_decimals: 6,
+        coin_lot_size: 1_000_000,
+        pc_lot_size: 10,
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        vault_signer_key: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        request_queue: Pubkey::new_unique(),
+        bids_address: Pubkey::new_unique(),
+        asks_address: Pubkey::new_unique(),
+        ..Default::default()
+    };
+
+    let params = market.parameters();
+    assert_eq!(params.market_address, market.market_address);
+    assert_eq!(params.coin_lot_size, 1_000_000);
+    assert_eq!(params.pc_lot_size, 10);
+    assert_eq!(params.coin_decimals, 9);
+    assert_eq!(params.pc_decimals, 6);
+    assert_eq!(params.base_mint, market.base_mint);
+    assert_eq!(params.quote_mint, market.quote_mint);
+    assert_eq!(params.coin_vault, market.coin_vault);
+    assert_eq!(params.pc_vault, market.pc_vault);
+    assert_eq!(params.event_queue, market.event_queue);
+    assert_eq!(params.bids_address, market.bids_address);
+    assert_eq!(params.asks_address, market.asks_address);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_market_parameters_json_round_trip() {
+    let market = Market {
+        program_id: Pubkey::new_unique(),
+        market_address: Pubkey::new_unique(),
+        coin_decimals: 9,
+        pc_decimals: 6,
+        coin_lot_size: 1_000_000,
+        pc_lot_size: 10,
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    let params = market.parameters();
+
+    let json = serde_json::to_string(&params).unwrap();
+    let decoded: openbook::v1::market::MarketParameters = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, params);
+}
+
+#[test]
+fn test_owner_atas_are_distinct_and_derived_from_mints() {
+    let market = Market {
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    let owner = Pubkey::new_unique();
+
+    let (base_ata, quote_ata) = market.owner_atas(&owner);
+    assert_ne!(base_ata, quote_ata);
+    assert_eq!(
+        base_ata,
+        get_associated_token_address(&owner, &market.base_mint)
+    );
+    assert_eq!(
+        quote_ata,
+        get_associated_token_address(&owner, &market.quote_mint)
+    );
+}
+
+#[test]
+fn test_fixed_price_is_exact_where_f64_rounds() {
+    let market = test_market();
+    assert_eq!(market.price_lots_to_fixed(2_100).rational(), (21, 10));
+
+    let unit_market = Market {
+        coin_lot_size: 1,
+        pc_lot_size: 1,
+        ..Default::default()
+    };
+    let lots = (1u64 << 53) + 1;
+    let price = unit_market.price_lots_to_fixed(lots);
+    assert_eq!(
+        price,
+        FixedPrice {
+            lots,
+            numerator: 1,
+            denominator: 1
+        }
+    );
+    assert_eq!(price.rational(), (lots as u128, 1));
+    assert_ne!(unit_market.price_lots_to_number(lots) as u64, lots);
+
+    let open_orders = OpenOrders {
+        open_bids: vec![((lots as u128) << 64) | 1],
+        ..Default::default()
+    };
+    assert_eq!(open_orders.open_bids_fixed(&unit_market), vec![price]);
+    assert!(open_orders.open_asks_fixed(&unit_market).is_empty());
+}
+
+/// Serializes a v1 `MarketState` (376 bytes) wrapped in the `serum` and `padding` markers.
+fn market_state_bytes(own_address: &Pubkey, bids: &Pubkey, program_id: &Pubkey) -> Vec<u8> {
+    let nonce = (0u64..)
+        .find(|nonce| gen_vault_signer_key(*nonce, own_address, program_id).is_ok())
+        .unwrap();
+    let mut state = vec![0u8; 376];
+    // Initialized | Market
+    state[0..8].copy_from_slice(&3u64.to_le_bytes());
+    state[8..40].copy_from_slice(&own_address.to_bytes());
+    state[40..48].copy_from_slice(&nonce.to_le_bytes());
+    state[280..312].copy_from_slice(&bids.to_bytes());
+    state[344..352].copy_from_slice(&1_000_000u64.to_le_bytes());
+    state[352..360].copy_from_slice(&1u64.to_le_bytes());
+
+    let mut data = b"serum".to_vec();
+    data.extend_from_slice(&state);
+    data.extend_from_slice(b"padding");
+    data
+}
+
+#[tokio::test]
+async fn test_load_rejects_market_state_of_another_address() {
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let bids = Pubkey::new_unique();
+    let mut market = Market {
+        program_id,
+        market_address,
+        ..Default::default()
+    };
+
+    let mut account = Account {
+        data: market_state_bytes(&Pubkey::new_unique(), &bids, &program_id),
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+    let err = market
+        .load_market_state_info(&account_info)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(OpenBookError::MarketAddressMismatch { expected, .. }) if *expected == market_address
+    ));
+    assert_eq!(market.bids_address, Pubkey::default());
+
+    let mut account = Account {
+        data: market_state_bytes(&market_address, &bids, &program_id),
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+    market.load_market_state_info(&account_info).await.unwrap();
+    assert_eq!(market.bids_address, bids);
+}
+
+#[tokio::test]
+async fn test_load_rejects_accounts_that_are_not_markets() {
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let mut market = Market {
+        program_id,
+        market_address,
+        ..Default::default()
+    };
+
+    let mut data = market_state_bytes(&market_address, &Pubkey::new_unique(), &program_id);
+    assert!(ensure_market_account(&market_address, &data).is_ok());
+    // Initialized | OpenOrders
+    data[5..13].copy_from_slice(&5u64.to_le_bytes());
+
+    let mut account = Account {
+        data,
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+    let err = market
+        .load_market_state_info(&account_info)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::NotAMarket(market_address))
+    );
+
+    let err = ensure_market_account(&market_address, &[0u8; 8]).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::NotAMarket(market_address))
+    );
+}
+
+#[test]
+fn test_parse_slab_orders_rejects_truncated_data() {
+    let data = slab_account_bytes(&Pubkey::default(), &[100, 101]);
+    assert_eq!(parse_slab_orders(&data, Side::Bid).unwrap().len(), 2);
+
+    let framed = |slab_len: usize| {
+        let mut framed = data[..5 + 8 + slab_len].to_vec();
+        framed.extend_from_slice(b"padding");
+        framed
+    };
+    // Shorter than the slab header, a header without nodes, and a partial last node.
+    for truncated in [framed(0), framed(20), framed(32), framed(32 + 72 * 8 - 10)] {
+        let err = parse_slab_orders(&truncated, Side::Bid).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OpenBookError>(),
+            Some(&OpenBookError::InvalidSlabAccount(truncated.len()))
+        );
+    }
+}
+
+fn archive_record(slot: u64, bids: &[u8], asks: &[u8]) -> Vec<u8> {
+    let mut record = slot.to_le_bytes().to_vec();
+    record.extend_from_slice(&(bids.len() as u32).to_le_bytes());
+    record.extend_from_slice(bids);
+    record.extend_from_slice(&(asks.len() as u32).to_le_bytes());
+    record.extend_from_slice(asks);
+    record
+}
+
+#[test]
+fn test_book_from_archive_reads_series() {
+    let market = Market {
+        bids_address: Pubkey::new_unique(),
+        asks_address: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    let mut archive = archive_record(
+        100,
+        &slab_account_bytes(&Pubkey::default(), &[98, 99]),
+        &slab_account_bytes(&Pubkey::default(), &[101, 103]),
+    );
+    archive.extend(archive_record(
+        105,
+        &slab_account_bytes(&Pubkey::default(), &[]),
+        &slab_account_bytes(&Pubkey::default(), &[102]),
+    ));
+
+    let series = market.book_from_archive(archive.as_slice()).unwrap();
+    assert_eq!(series.len(), 2);
+
+    let (slot, book) = &series[0];
+    assert_eq!(*slot, 100);
+    assert_eq!(book.slot, 100);
+    assert_eq!(book.max_bid, 99);
+    assert_eq!(book.min_ask, 101);
+    assert_eq!(book.bids_address, market.bids_address);
+    assert_eq!(
+        book.asks
+            .iter()
+            .map(|order| order.price)
+            .collect::<Vec<_>>(),
+        vec![101, 103]
+    );
+    assert_eq!(series[1].0, 105);
+    assert!(series[1].1.bids.is_empty());
+    assert_eq!(series[1].1.min_ask, 102);
+
+    // A truncated record is an error, not a silently shorter series.
+    assert!(market
+        .book_from_archive(&archive[..archive.len() - 3])
+        .is_err());
+    assert!(market.book_from_archive(&[0u8; 0][..]).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_registry_entry_overrides_sizes_and_survives_load() {
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let mut market = Market {
+        program_id,
+        market_address,
+        ..test_market()
+    };
+
+    market.apply_registry(RegistryEntry {
+        coin_lot_size: 100,
+        pc_lot_size: 10,
+        coin_decimals: 6,
+        pc_decimals: 6,
+    });
+    assert!(market.sizes_from_registry);
+    // 1 base is 10_000 base lots, and a price of 1.5 is 15 price lots.
+    assert_eq!(market.base_size_number_to_lots(1.0), 10_000);
+    assert_eq!(market.price_number_to_lots(1.5), 15);
+
+    // The market account reports a 1_000_000 / 1 lot size, which the registry takes over.
+    let mut account = Account {
+        data: market_state_bytes(&market_address, &Pubkey::new_unique(), &program_id),
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+    market.load_market_state_info(&account_info).await.unwrap();
+    assert_eq!(market.coin_lot_size, 100);
+    assert_eq!(market.pc_lot_size, 10);
+    assert_eq!(market.coin_decimals, 6);
+}
+
+#[test]
+fn test_check_book_addresses_rejects_tampered_market_info() {
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let bids = Pubkey::new_unique();
+    let mut account = Account {
+        data: market_state_bytes(&market_address, &bids, &program_id),
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+
+    // `market_state_bytes` leaves the asks address zeroed.
+    let mut market = Market {
+        program_id,
+        market_address,
+        bids_address: bids,
+        ..Default::default()
+    };
+    market.check_book_addresses(&account_info).unwrap();
+
+    let tampered = Pubkey::new_unique();
+    market.bids_address = tampered;
+    let err = market.check_book_addresses(&account_info).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::BookAddressMismatch {
+            expected: bids,
+            found: tampered,
+        })
+    );
+}
+
+#[test]
+fn test_registry_flag_is_not_part_of_the_borsh_layout() {
+    let mut market = Market {
+        market_address: Pubkey::new_unique(),
+        ..test_market()
+    };
+    let bytes = market.try_to_vec().unwrap();
+
+    market.sizes_from_registry = true;
+    assert_eq!(market.try_to_vec().unwrap(), bytes);
+
+    let restored = Market::try_from_slice(&bytes).unwrap();
+    assert!(!restored.sizes_from_registry);
+    assert_eq!(restored.parameters(), market.parameters());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_market_json_round_trip() {
+    let market = Market {
+        program_id: Pubkey::new_unique(),
+        market_address: Pubkey::new_unique(),
+        account_flags: 3,
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        bids_address: Pubkey::new_unique(),
+        asks_address: Pubkey::new_unique(),
+        events_authority: Pubkey::new_unique(),
+        sizes_from_registry: true,
+        ..test_market()
+    };
+
+    let json = serde_json::to_string(&market).unwrap();
+    let restored: Market = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.parameters(), market.parameters());
+    assert_eq!(restored.account_flags, market.account_flags);
+    assert_eq!(restored.events_authority, market.events_authority);
+    assert_eq!(restored.sizes_from_registry, market.sizes_from_registry);
+}
+
+#[tokio::test]
+async fn test_load_rejects_market_owned_by_another_program() {
+    let program_id = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let data = market_state_bytes(&market_address, &Pubkey::new_unique(), &program_id);
+    let mocks = HashMap::from([(
+        RpcRequest::GetAccountInfo,
+        json!({
+            "context": { "slot": 1 },
+            "value": {
+                "lamports": 1,
+                "data": [openbook::bs58::encode(&data).into_string(), "base58"],
+                "owner": owner.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            },
+        }),
+    )]);
+    let rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+    let mut market = Market {
+        program_id,
+        market_address,
+        ..Default::default()
+    };
+
+    let err = market.load(&rpc_client).await.unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::InvalidProgramOwner {
+            expected: program_id,
+            found: owner,
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_load_takes_mints_and_atas_from_market_state() {
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let coin_mint = Pubkey::new_unique();
+    let pc_mint = Pubkey::new_unique();
+    let mut market = Market {
+        program_id,
+        market_address,
+        base_mint: market_address,
+        quote_mint: market_address,
+        ..Default::default()
+    };
+
+    let mut data = market_state_bytes(&market_address, &Pubkey::new_unique(), &program_id);
+    // `coin_mint` and `pc_mint` follow the own address and the vault signer nonce.
+    data[5 + 48..5 + 80].copy_from_slice(&coin_mint.to_bytes());
+    data[5 + 80..5 + 112].copy_from_slice(&pc_mint.to_bytes());
+    let mut account = Account {
+        data,
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+    market.load_market_state_info(&account_info).await.unwrap();
+
+    assert_eq!(market.base_mint, coin_mint);
+    assert_eq!(market.quote_mint, pc_mint);
+    assert_eq!(market.coin_mint(), coin_mint);
+    assert_eq!(market.pc_mint(), pc_mint);
+    assert_eq!(
+        market.owner_atas(&owner),
+        (
+            get_associated_token_address(&owner, &coin_mint),
+            get_associated_token_address(&owner, &pc_mint),
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_load_derives_vault_signer_from_stored_nonce() {
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    // Skip the first valid nonce so a guessed key would not match the stored one.
+    let mut valid = (0u64..).filter_map(|nonce| {
+        gen_vault_signer_key(nonce, &market_address, &program_id)
+            .ok()
+            .map(|key| (nonce, key))
+    });
+    let (_, first_key) = valid.next().unwrap();
+    let (nonce, key) = valid.next().unwrap();
+    let mut market = Market {
+        program_id,
+        market_address,
+        vault_signer_key: first_key,
+        ..Default::default()
+    };
+
+    let mut data = market_state_bytes(&market_address, &Pubkey::new_unique(), &program_id);
+    data[5 + 40..5 + 48].copy_from_slice(&nonce.to_le_bytes());
+    let mut account = Account {
+        data,
+        owner: program_id,
+        ..Default::default()
+    };
+    let account_info =
+        create_account_info_from_account(&mut account, &market_address, &program_id, false, false);
+    market.load_market_state_info(&account_info).await.unwrap();
+
+    assert_eq!(market.vault_signer_key, key);
+}
+
+/// Mocks a `getMultipleAccounts` answer holding one SPL mint account per entry of `decimals`.
+fn mint_accounts_rpc(decimals: &[u8]) -> Rpc {
+    let accounts: Vec<_> = decimals
+        .iter()
+        .map(|decimals| {
+            let mut data = vec![0u8; 82];
+            data[44] = *decimals;
+            // is_initialized
+            data[45] = 1;
+            json!({
+                "lamports": 1,
+                "data": [openbook::bs58::encode(&data).into_string(), "base58"],
+                "owner": Pubkey::new_unique().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            })
+        })
+        .collect();
+    Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        HashMap::from([(
+            RpcRequest::GetMultipleAccounts,
+            json!({ "context": { "slot": 1 }, "value": accounts }),
+        )]),
+    ))
+}
+
+#[tokio::test]
+async fn test_decimals_are_read_from_the_mints() {
+    let market = || Market {
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        coin_lot_size: 1_000,
+        pc_lot_size: 1,
+        ..Default::default()
+    };
+
+    // A 6-decimal base quoted in USDT (6 decimals).
+    let mut usdt_market = market();
+    usdt_market
+        .load_mint_decimals(&mint_accounts_rpc(&[6, 6]))
+        .await
+        .unwrap();
+    assert_eq!((usdt_market.coin_decimals, usdt_market.pc_decimals), (6, 6));
+
+    // A 9-decimal base quoted in a 6-decimal token.
+    let mut sol_market = market();
+    sol_market
+        .load_mint_decimals(&mint_accounts_rpc(&[9, 6]))
+        .await
+        .unwrap();
+    assert_eq!((sol_market.coin_decimals, sol_market.pc_decimals), (9, 6));
+
+    assert_eq!(usdt_market.base_size_lots_to_number(1_000), 1.0);
+    assert_eq!(sol_market.base_size_lots_to_number(1_000), 0.001);
+    assert_ne!(
+        usdt_market.price_lots_to_number(1_000),
+        sol_market.price_lots_to_number(1_000)
+    );
+
+    assert!(mint_decimals(&[0u8; 165]).is_err());
+}
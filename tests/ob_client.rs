@@ -0,0 +1,2041 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::{event_queue_bytes, test_market};
+use openbook::account::Account;
+use openbook::error::{MarketError, OpenBookError};
+use openbook::keypair::Keypair;
+use openbook::matching::{OrderType, Side};
+use openbook::pubkey::Pubkey;
+use openbook::rpc::Rpc;
+use openbook::rpc_client::RpcClient;
+use openbook::signature::{Signature, Signer};
+use openbook::utils::create_account_info_from_account;
+use openbook::v1::events::{
+    Event, EventQueue, EventQueueFullPolicy, EVENT_FLAG_BID, EVENT_FLAG_FILL, EVENT_FLAG_MAKER,
+    EVENT_FLAG_OUT,
+};
+use openbook::v1::market::{maker_rebate_rate, taker_fee, taker_fee_rate, Market};
+use openbook::v1::ob_client::{OBClient, OBClientBuilder, MAX_CANCELS_PER_TRANSACTION};
+use openbook::v1::orders::{
+    client_order_id_in_namespace, open_orders_live_orders, open_orders_seeded_address,
+    OpenOrdersCacheEntry, Order, OrderReturnType, OrderSpec, OPEN_ORDERS_ACCOUNT_LEN, USDC_MINT,
+    WSOL_MINT,
+};
+use openbook_dex::critbit::{LeafNode, Slab};
+use openbook_dex::fees::{msrm_token, srm_token, FeeTier};
+use openbook_dex::instruction::{MarketInstruction, NewOrderInstructionV3, SelfTradeBehavior};
+use serde_json::json;
+use solana_account_decoder::{UiAccountData, UiAccountEncoding};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::instruction::Instruction;
+use std::cell::RefMut;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+fn mock_client() -> OBClient {
+    OBClient {
+        owner: Arc::new(Keypair::new()),
+        rpc_client: Rpc::new(RpcClient::new_mock("succeeds".to_string())),
+        quote_ata: OnceLock::from(Pubkey::new_unique()),
+        base_ata: OnceLock::from(Pubkey::new_unique()),
+        open_orders: Default::default(),
+        market_info: Default::default(),
+        open_orders_cache: HashMap::new(),
+        client_id_prefix: 0,
+        crossed_book_guard: false,
+        min_fee_balance: None,
+        market_info_cache: Arc::new(Mutex::new(None)),
+        event_queue_full_policy: EventQueueFullPolicy::default(),
+        pre_simulate: false,
+        pre_simulation_cache: Arc::new(Mutex::new(None)),
+        fee_discount_account: None,
+        fee_tier: FeeTier::Base,
+        crank_payer: None,
+        read_only: false,
+        base_position_target: 0.0,
+    }
+}
+
+#[test]
+fn test_process_bids_drains_writable_slab() {
+    let program_id = Pubkey::new_unique();
+    let bids_address = Pubkey::new_unique();
+    let mut account = Account {
+        data: vec![0u8; 32 + 72 * 8],
+        owner: program_id,
+        ..Default::default()
+    };
+
+    let mut ob_client = mock_client();
+    ob_client.open_orders.oo_key = Pubkey::new_unique();
+    let oo_owner = ob_client.open_orders.oo_key.to_bytes();
+    let owner: [u64; 4] = std::array::from_fn(|i| {
+        u64::from_le_bytes(oo_owner[i * 8..(i + 1) * 8].try_into().unwrap())
+    });
+
+    let bids_info =
+        create_account_info_from_account(&mut account, &bids_address, &program_id, false, true);
+    assert!(bids_info.is_writable);
+    assert!(!bids_info.is_signer);
+
+    let mut bids = RefMut::map(bids_info.data.borrow_mut(), |data| Slab::new(data));
+    for (seq, price) in [(0u64, 100u64), (1, 102), (2, 101)] {
+        let key = ((price as u128) << 64) | (!seq as u128);
+        let leaf = LeafNode::new(0, key, owner, 10, FeeTier::Base, seq);
+        bids.insert_leaf(&leaf).unwrap();
+    }
+
+    let (open_bids, open_bids_prices, max_bid, ladder) = ob_client.process_bids(&mut bids).unwrap();
+    assert_eq!(max_bid, 102);
+    assert_eq!(open_bids.len(), 3);
+    assert_eq!(open_bids_prices.len(), 3);
+    assert_eq!(
+        ladder.iter().map(|order| order.price).collect::<Vec<_>>(),
+        vec![102, 101, 100]
+    );
+    assert!(bids.find_max().is_none());
+}
+
+#[test]
+fn test_process_bids_and_asks_prices_follow_market_lot_sizes() {
+    let markets = [
+        // SOL/USDC-like: 9 base decimals, 6 quote decimals.
+        test_market(),
+        // A market with coarser lots and equal decimals.
+        Market {
+            coin_decimals: 6,
+            pc_decimals: 6,
+            coin_lot_size: 100_000,
+            pc_lot_size: 10,
+            ..Default::default()
+        },
+    ];
+
+    for market in markets {
+        let program_id = Pubkey::new_unique();
+        let mut ob_client = mock_client();
+        ob_client.market_info = market;
+        ob_client.open_orders.oo_key = Pubkey::new_unique();
+        let oo_owner = ob_client.open_orders.oo_key.to_bytes();
+        let owner: [u64; 4] = std::array::from_fn(|i| {
+            u64::from_le_bytes(oo_owner[i * 8..(i + 1) * 8].try_into().unwrap())
+        });
+        let price_lots = ob_client.market_info.price_number_to_lots(2.5);
+
+        let slab_account = || Account {
+            data: vec![0u8; 32 + 72 * 8],
+            owner: program_id,
+            ..Default::default()
+        };
+        let mut bids_account = slab_account();
+        let mut asks_account = slab_account();
+        let bids_address = Pubkey::new_unique();
+        let asks_address = Pubkey::new_unique();
+        let bids_info = create_account_info_from_account(
+            &mut bids_account,
+            &bids_address,
+            &program_id,
+            false,
+            true,
+        );
+        let asks_info = create_account_info_from_account(
+            &mut asks_account,
+            &asks_address,
+            &program_id,
+            false,
+            true,
+        );
+
+        let key = ((price_lots as u128) << 64) | 1;
+        let leaf = LeafNode::new(0, key, owner, 10, FeeTier::Base, 1);
+        let mut bids = RefMut::map(bids_info.data.borrow_mut(), |data| Slab::new(data));
+        bids.insert_leaf(&leaf).unwrap();
+        let mut asks = RefMut::map(asks_info.data.borrow_mut(), |data| Slab::new(data));
+        asks.insert_leaf(&leaf).unwrap();
+
+        let (_, open_bids_prices, _, _) = ob_client.process_bids(&mut bids).unwrap();
+        let (_, open_asks_prices, _, _) = ob_client.process_asks(&mut asks).unwrap();
+        assert_eq!(open_bids_prices, vec![2.5]);
+        assert_eq!(open_asks_prices, vec![2.5]);
+    }
+}
+
+#[tokio::test]
+async fn test_maintain_quote_keeps_order_at_unchanged_price() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let oo_key = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = oo_key;
+    ob_client.open_orders.bids = vec![Order {
+        order_id: (2_100u128 << 64) | 7,
+        price: ob_client.market_info.price_number_to_lots(2.1),
+        quantity: 1_000,
+        owner: oo_key,
+        client_order_id: 1,
+    }];
+
+    let result = ob_client
+        .maintain_quote(Side::Bid, 2.1, 1.0, true)
+        .await
+        .unwrap();
+    assert!(result.is_none());
+
+    let result = ob_client
+        .maintain_quote(Side::Bid, 2.2, 1.0, false)
+        .await
+        .unwrap();
+    match result {
+        Some(OrderReturnType::Instructions(instructions)) => assert_eq!(instructions.len(), 2),
+        other => panic!("expected a cancel-replace, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_ensure_fee_balance_rejects_low_balance_signer() {
+    // The mock RPC reports a balance of 50 lamports for every account.
+    let mut ob_client = mock_client();
+    ob_client.ensure_fee_balance(50).await.unwrap();
+
+    let err = ob_client.ensure_fee_balance(5_000).await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::InsufficientSol {
+            have: 50,
+            need: 5_000
+        })
+    );
+
+    ob_client.min_fee_balance = Some(5_000);
+    ob_client.market_info = test_market();
+    let err = ob_client
+        .place_limit_order(2.0, Side::Bid, 0.0, true, 2.0)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(OpenBookError::InsufficientSol { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_every_order_entry_point_enforces_the_placement_guards() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let resting = Order {
+        order_id: (2_000u128 << 64) | !1u64 as u128,
+        price: 2_000,
+        quantity: 1_000,
+        owner: ob_client.open_orders.oo_key,
+        ..Default::default()
+    };
+    ob_client.open_orders.bids = vec![resting];
+    ob_client.open_orders.max_bid = 2_200;
+    ob_client.open_orders.min_ask = 2_100;
+    ob_client.crossed_book_guard = true;
+
+    let bid = OrderSpec {
+        side: Side::Bid,
+        price: 2.0,
+        size: 1.0,
+    };
+    let ask = OrderSpec {
+        side: Side::Ask,
+        price: 2.3,
+        size: 1.0,
+    };
+    let placements = |ob_client: &OBClient| {
+        let ob_client = ob_client.clone();
+        async move {
+            vec![
+                ob_client
+                    .place_limit_order(2.0, Side::Bid, 0.0, true, 2.0)
+                    .await
+                    .map(|_| ()),
+                ob_client
+                    .place_order(
+                        Side::Bid,
+                        2_000,
+                        1_000,
+                        OrderType::Limit,
+                        SelfTradeBehavior::AbortTransaction,
+                        1,
+                    )
+                    .await
+                    .map(|_| ()),
+                ob_client.place_limit_bid(2_000, 1_000).await.map(|_| ()),
+                ob_client.place_limit_ask(2_300, 1_000).await.map(|_| ()),
+                ob_client
+                    .place_ioc_order(Side::Bid, 2.2, 2.0)
+                    .await
+                    .map(|_| ()),
+                ob_client.market_buy(2_000_000).await.map(|_| ()),
+                ob_client.market_sell(1_000).await.map(|_| ()),
+                ob_client
+                    .place_and_settle(
+                        Side::Ask,
+                        2_300,
+                        1_000,
+                        OrderType::ImmediateOrCancel,
+                        SelfTradeBehavior::DecrementTake,
+                        2,
+                    )
+                    .await
+                    .map(|_| ()),
+                ob_client.quote_both_sides(bid, ask).await.map(|_| ()),
+                ob_client
+                    .maintain_quote(Side::Ask, 2.3, 1.0, true)
+                    .await
+                    .map(|_| ()),
+                ob_client
+                    .replace_order(resting.order_id, bid)
+                    .await
+                    .map(|_| ()),
+                ob_client
+                    .reduce_order(resting.order_id, Side::Bid, 0.5, true)
+                    .await
+                    .map(|_| ()),
+            ]
+        }
+    };
+
+    for result in placements(&ob_client).await {
+        assert_eq!(
+            result.unwrap_err().downcast_ref::<OpenBookError>(),
+            Some(&OpenBookError::CrossedBook)
+        );
+    }
+
+    // The mock RPC reports a balance of 50 lamports for every account.
+    ob_client.crossed_book_guard = false;
+    ob_client.min_fee_balance = Some(5_000);
+    for result in placements(&ob_client).await {
+        assert!(matches!(
+            result.unwrap_err().downcast_ref::<OpenBookError>(),
+            Some(OpenBookError::InsufficientSol { .. })
+        ));
+    }
+
+    // A reduction to zero is a plain cancel and is not held back by the placement checks.
+    assert!(ob_client
+        .reduce_order(resting.order_id, Side::Bid, 0.0, true)
+        .await
+        .is_ok());
+}
+
+#[test]
+fn test_my_orders_by_level_aggregates_same_price() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let oo_key = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = oo_key;
+    let order = |price: u64, quantity: u64, owner: Pubkey| Order {
+        order_id: (price as u128) << 64,
+        price,
+        quantity,
+        owner,
+        client_order_id: 0,
+    };
+    ob_client.open_orders.bids = vec![
+        order(2_100, 1_000, oo_key),
+        order(2_100, 500, oo_key),
+        order(2_100, 700, Pubkey::new_unique()),
+        order(2_000, 2_000, oo_key),
+    ];
+
+    let levels = ob_client.my_orders_by_level(Side::Bid);
+    assert_eq!(levels, vec![(2.1, 1.5, 2), (2.0, 2.0, 1)]);
+    assert!(ob_client.my_orders_by_level(Side::Ask).is_empty());
+}
+
+#[tokio::test]
+async fn test_reduce_order_cancels_and_places_smaller_size() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let oo_key = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = oo_key;
+    let order_id = (2_100u128 << 64) | 7;
+    ob_client.open_orders.asks = vec![Order {
+        order_id,
+        price: 2_100,
+        quantity: 2_000,
+        owner: oo_key,
+        client_order_id: 1,
+    }];
+
+    let instructions = match ob_client
+        .reduce_order(order_id, Side::Ask, 0.5, false)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        other => panic!("expected instructions, got {:?}", other),
+    };
+    assert_eq!(instructions.len(), 2);
+
+    match MarketInstruction::unpack(&instructions[0].data) {
+        Some(MarketInstruction::CancelOrderV2(cancel)) => {
+            assert_eq!(cancel.side, Side::Ask);
+            assert_eq!(cancel.order_id, order_id);
+        }
+        other => panic!("expected a cancel, got {:?}", other),
+    }
+    match MarketInstruction::unpack(&instructions[1].data) {
+        Some(MarketInstruction::NewOrderV3(new_order)) => {
+            assert_eq!(new_order.side, Side::Ask);
+            assert_eq!(new_order.limit_price.get(), 2_100);
+            assert_eq!(new_order.max_coin_qty.get(), 500);
+        }
+        other => panic!("expected a new order, got {:?}", other),
+    }
+
+    assert!(ob_client
+        .reduce_order(order_id, Side::Ask, 2.0, false)
+        .await
+        .is_err());
+    assert!(ob_client
+        .reduce_order(order_id, Side::Bid, 0.5, false)
+        .await
+        .is_err());
+}
+
+#[test]
+fn test_crank_and_settle_instructions_compose_both_steps() {
+    let mut ob_client = mock_client();
+    let oo_key = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = oo_key;
+    let event = |seq_num: u64, owner: Pubkey| Event {
+        seq_num,
+        owner,
+        ..Default::default()
+    };
+    let event_queue = EventQueue {
+        events: vec![event(0, other), event(1, oo_key), event(2, other)],
+        ..Default::default()
+    };
+
+    let instructions = ob_client
+        .crank_and_settle_instructions(&event_queue, (0, 0), 10)
+        .unwrap();
+    assert_eq!(instructions.len(), 2);
+    match MarketInstruction::unpack(&instructions[0].data) {
+        Some(MarketInstruction::ConsumeEvents(limit)) => assert_eq!(limit, 10),
+        other => panic!("expected a consume events, got {:?}", other),
+    }
+    let cranked: Vec<Pubkey> = instructions[0]
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    assert!(cranked.contains(&oo_key));
+    assert!(cranked.contains(&other));
+    assert!(matches!(
+        MarketInstruction::unpack(&instructions[1].data),
+        Some(MarketInstruction::SettleFunds)
+    ));
+
+    // Nothing of ours to crank, but free funds to settle.
+    let instructions = ob_client
+        .crank_and_settle_instructions(&event_queue, (5, 0), 1)
+        .unwrap();
+    assert_eq!(instructions.len(), 1);
+    assert!(matches!(
+        MarketInstruction::unpack(&instructions[0].data),
+        Some(MarketInstruction::SettleFunds)
+    ));
+
+    // Empty queue and empty balances.
+    assert!(ob_client
+        .crank_and_settle_instructions(&EventQueue::default(), (0, 0), 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_mark_price_blends_mid_and_trade_vwap() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let event = |event_flags: u8, price_lots: u64, paid: u64, released: u64| Event {
+        event_flags,
+        order_id: (price_lots as u128) << 64,
+        native_qty_paid: paid,
+        native_qty_released: released,
+        ..Default::default()
+    };
+    let event_queue = EventQueue {
+        events: vec![
+            // Maker bid filled for 1.0 base at 2.0.
+            event(
+                EVENT_FLAG_FILL | EVENT_FLAG_MAKER | EVENT_FLAG_BID,
+                2_000,
+                2_000_000,
+                1_000_000_000,
+            ),
+            // The taker side of the same trade is not counted twice.
+            event(EVENT_FLAG_FILL, 2_000, 1_000_000_000, 2_000_000),
+            // Maker ask filled for 3.0 base at 2.3.
+            event(
+                EVENT_FLAG_FILL | EVENT_FLAG_MAKER,
+                2_300,
+                3_000_000_000,
+                6_900_000,
+            ),
+            event(EVENT_FLAG_OUT | EVENT_FLAG_BID, 1_900, 0, 0),
+        ],
+        ..Default::default()
+    };
+    let trades = event_queue.recent_trades();
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[1].base_native, 3_000_000_000);
+
+    // No book and no trades.
+    assert_eq!(ob_client.mark_price(&[], 0.5), None);
+
+    let vwap = ob_client.mark_price(&trades, 0.5).unwrap();
+    assert!((vwap - 2.225).abs() < 1e-9);
+
+    ob_client.open_orders.max_bid = 2_000;
+    ob_client.open_orders.min_ask = 2_200;
+    let mid = ob_client.mark_price(&[], 0.5).unwrap();
+    assert!((mid - 2.1).abs() < 1e-9);
+
+    let mark = ob_client.mark_price(&trades, 0.5).unwrap();
+    assert!((mark - 2.1625).abs() < 1e-9);
+    let mark = ob_client.mark_price(&trades, 0.0).unwrap();
+    assert!((mark - 2.1).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_book_slot_lag_against_current_slot() {
+    let mocks = HashMap::from([(RpcRequest::GetSlot, json!(1_000_000))]);
+    let mut ob_client = mock_client();
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+    ob_client.open_orders.slot = 250;
+
+    assert_eq!(ob_client.book_slot(), 250);
+    assert_eq!(ob_client.book_slot_lag().await.unwrap(), 999_750);
+}
+
+#[test]
+fn test_cancels_built_from_open_orders_account_slots() {
+    let bid_id = (100u128 << 64) | 1;
+    let ask_id = (120u128 << 64) | 2;
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    // Slots 0 and 5 are used, slot 0 holds a bid.
+    let free_slot_bits = !(1u128 | (1u128 << 5));
+    data[109..125].copy_from_slice(&free_slot_bits.to_le_bytes());
+    data[125..141].copy_from_slice(&1u128.to_le_bytes());
+    data[141..157].copy_from_slice(&bid_id.to_le_bytes());
+    data[141 + 5 * 16..141 + 6 * 16].copy_from_slice(&ask_id.to_le_bytes());
+
+    let orders = open_orders_live_orders(&data).unwrap();
+    assert_eq!(orders, vec![(bid_id, Side::Bid), (ask_id, Side::Ask)]);
+
+    let ob_client = mock_client();
+    let instructions = ob_client.cancel_order_instructions(&orders).unwrap();
+    let cancels: Vec<(Side, u128)> = instructions
+        .iter()
+        .map(|ix| match MarketInstruction::unpack(&ix.data) {
+            Some(MarketInstruction::CancelOrderV2(cancel)) => (cancel.side, cancel.order_id),
+            other => panic!("expected a cancel, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(cancels, vec![(Side::Bid, bid_id), (Side::Ask, ask_id)]);
+}
+
+fn decode_new_order(ix: &Instruction) -> NewOrderInstructionV3 {
+    match MarketInstruction::unpack(&ix.data) {
+        Some(MarketInstruction::NewOrderV3(new_order)) => new_order,
+        other => panic!("expected a new order, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_quote_ceiling_pads_only_orders_that_can_take() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+
+    let ix = ob_client
+        .new_order_instruction_lots(Side::Bid, 2_100, 1_000, OrderType::PostOnly)
+        .unwrap()
+        .unwrap();
+    let new_order = decode_new_order(&ix);
+    assert_eq!(new_order.order_type, OrderType::PostOnly);
+    // A post-only order never pays a fee, so its ceiling is the 2.1 quote notional.
+    assert_eq!(new_order.max_native_pc_qty_including_fees.get(), 2_100_000);
+
+    // Limit and IOC orders can take, so they are padded by the taker fee of the client's tier.
+    for order_type in [OrderType::Limit, OrderType::ImmediateOrCancel] {
+        let instructions = ob_client
+            .place_and_settle_instructions(
+                Side::Bid,
+                2_100,
+                1_000,
+                order_type,
+                SelfTradeBehavior::AbortTransaction,
+                1,
+            )
+            .unwrap();
+        let new_order = decode_new_order(&instructions[0]);
+        assert_eq!(new_order.order_type, order_type);
+        assert_eq!(
+            new_order.max_native_pc_qty_including_fees.get(),
+            2_100_000 + taker_fee(FeeTier::Base, 2_100_000)
+        );
+    }
+
+    // A fee discount lowers the padding to the discounted tier's taker fee.
+    ob_client.fee_tier = FeeTier::MSRM;
+    assert_eq!(
+        ob_client.max_native_pc_qty(OrderType::Limit, 2_100, 1_000),
+        2_100_000 + taker_fee(FeeTier::MSRM, 2_100_000)
+    );
+    assert!(taker_fee(FeeTier::MSRM, 2_100_000) < taker_fee(FeeTier::Base, 2_100_000));
+}
+
+#[test]
+fn test_ioc_orders_cap_quote_at_exact_budget() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+
+    let ix = ob_client
+        .new_taker_order_instruction(Side::Bid, 2.2, 2.1)
+        .unwrap()
+        .unwrap();
+    let new_order = decode_new_order(&ix);
+    assert_eq!(new_order.order_type, OrderType::ImmediateOrCancel);
+    assert_eq!(new_order.limit_price.get(), 2_200);
+    assert_eq!(new_order.max_coin_qty.get(), 954);
+    assert_eq!(new_order.max_native_pc_qty_including_fees.get(), 2_100_000);
+
+    assert!(ob_client
+        .new_taker_order_instruction(Side::Bid, 2.2, 0.0)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_would_self_cross_owner_bid() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let oo_key = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = oo_key;
+    let order = |price: u64, owner: Pubkey| Order {
+        order_id: (price as u128) << 64,
+        price,
+        quantity: 1_000,
+        owner,
+        client_order_id: 0,
+    };
+    ob_client.open_orders.bids = vec![order(2_100, Pubkey::new_unique()), order(2_000, oo_key)];
+
+    assert!(ob_client.would_self_cross(Side::Ask, 2.0));
+    assert!(ob_client.would_self_cross(Side::Ask, 1.9));
+    // Only someone else's bid rests at 2.1.
+    assert!(!ob_client.would_self_cross(Side::Ask, 2.05));
+    // The owner has no asks for a bid to cross.
+    assert!(!ob_client.would_self_cross(Side::Bid, 5.0));
+}
+
+#[test]
+fn test_quote_both_sides_packs_bid_and_ask_in_one_transaction() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let bid = OrderSpec {
+        side: Side::Bid,
+        price: 2.0,
+        size: 1.0,
+    };
+    let ask = OrderSpec {
+        side: Side::Ask,
+        price: 2.2,
+        size: 1.5,
+    };
+
+    let instructions = ob_client.quote_both_sides_instructions(bid, ask).unwrap();
+    assert_eq!(instructions.len(), 2);
+    let orders: Vec<NewOrderInstructionV3> = instructions.iter().map(decode_new_order).collect();
+    assert_eq!(orders[0].side, Side::Bid);
+    assert_eq!(orders[0].limit_price.get(), 2_000);
+    assert_eq!(orders[1].side, Side::Ask);
+    assert_eq!(orders[1].max_coin_qty.get(), 1_500);
+    assert!(orders
+        .iter()
+        .all(|order| order.self_trade_behavior == SelfTradeBehavior::CancelProvide));
+
+    assert!(ob_client.quote_both_sides_instructions(ask, bid).is_err());
+}
+
+#[tokio::test]
+async fn test_place_limit_ask_debits_base_account() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+
+    let ix = ob_client
+        .new_order_instruction_lots(Side::Ask, 2_100, 1_500, OrderType::PostOnly)
+        .unwrap()
+        .unwrap();
+    let new_order = decode_new_order(&ix);
+    assert_eq!(new_order.side, Side::Ask);
+    assert_eq!(new_order.max_coin_qty.get(), 1_500);
+    // The order payer is the seventh account of `new_order`.
+    assert_eq!(ix.accounts[6].pubkey, ob_client.base_ata());
+
+    assert!(ob_client.place_limit_ask(2_100, 1_500).await.is_ok());
+    assert!(ob_client.place_limit_ask(2_100, 0).await.is_err());
+}
+
+#[tokio::test]
+async fn test_cached_market_info_refreshes_only_when_stale() {
+    let ob_client = mock_client();
+    let cached = Market {
+        coin_lot_size: 7,
+        ..Default::default()
+    };
+    *ob_client.market_info_cache.lock().await = Some((Instant::now(), cached));
+
+    // The mock RPC has no market account, so any refresh would fail.
+    for _ in 0..2 {
+        let market = ob_client
+            .cached_market_info(Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(market.coin_lot_size, 7);
+    }
+
+    assert!(ob_client.cached_market_info(Duration::ZERO).await.is_err());
+}
+
+#[tokio::test]
+async fn test_place_order_accepts_any_order_type_and_self_trade_behavior() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+
+    for (order_type, self_trade) in [
+        (OrderType::Limit, SelfTradeBehavior::DecrementTake),
+        (
+            OrderType::ImmediateOrCancel,
+            SelfTradeBehavior::CancelProvide,
+        ),
+        (OrderType::PostOnly, SelfTradeBehavior::AbortTransaction),
+    ] {
+        let placed = ob_client
+            .place_order(Side::Bid, 2_100, 1_500, order_type, self_trade, 42)
+            .await
+            .unwrap();
+        assert_eq!(placed.client_order_id, 42);
+    }
+
+    ob_client.client_id_prefix = 7;
+    let placed = ob_client.place_limit_bid(2_100, 1_500).await.unwrap();
+    assert!(client_order_id_in_namespace(7, placed.client_order_id));
+    assert!(ob_client.place_limit_bid(0, 1_500).await.is_err());
+}
+
+#[tokio::test]
+async fn test_cancel_order_by_client_id_uses_the_client_id_instruction() {
+    let ob_client = mock_client();
+
+    let ix = ob_client
+        .cancel_order_by_client_id_instruction(0xdead_beef)
+        .unwrap();
+    assert!(matches!(
+        MarketInstruction::unpack(&ix.data),
+        Some(MarketInstruction::CancelOrderByClientIdV2(0xdead_beef))
+    ));
+
+    assert!(ob_client
+        .cancel_order_by_client_id(0xdead_beef)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_pre_simulation_is_reused_for_the_same_account_set() {
+    let ob_client = mock_client();
+
+    let bid = ob_client
+        .new_order_instruction_lots(Side::Bid, 2_100, 1_500, OrderType::Limit)
+        .unwrap()
+        .unwrap();
+    let cheaper_bid = ob_client
+        .new_order_instruction_lots(Side::Bid, 2_000, 1_000, OrderType::Limit)
+        .unwrap()
+        .unwrap();
+    let ask = ob_client
+        .new_order_instruction_lots(Side::Ask, 2_100, 1_500, OrderType::Limit)
+        .unwrap()
+        .unwrap();
+
+    assert!(ob_client.pre_simulate_order(&[bid]).await.unwrap());
+    assert!(!ob_client.pre_simulate_order(&[cheaper_bid]).await.unwrap());
+    // An ask is paid from another account, so it is simulated again.
+    assert!(ob_client.pre_simulate_order(&[ask]).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_cancel_order_accepts_ids_beyond_u64() {
+    let ob_client = mock_client();
+    let order_id = (2_100u128 << 64) | 7;
+    assert!(order_id > u64::MAX as u128);
+
+    let ixs = ob_client
+        .cancel_order_instructions(&[(order_id, Side::Ask)])
+        .unwrap();
+    match MarketInstruction::unpack(&ixs[0].data) {
+        Some(MarketInstruction::CancelOrderV2(cancel)) => assert_eq!(cancel.order_id, order_id),
+        other => panic!("expected a cancel, got {:?}", other),
+    }
+    assert!(ob_client.cancel_order(order_id).await.is_ok());
+    assert!(ob_client
+        .cancel_order_with_side(order_id, Side::Ask)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_dump_accounts_includes_every_market_account() {
+    let account = json!({
+        "lamports": 1,
+        "data": ["AQID", "base64"],
+        "owner": Pubkey::new_unique().to_string(),
+        "executable": false,
+        "rentEpoch": 0,
+        "space": 3,
+    });
+    let mut value = vec![account; 7];
+    value.push(serde_json::Value::Null);
+    let mocks = HashMap::from([(
+        RpcRequest::GetMultipleAccounts,
+        json!({ "context": { "slot": 42 }, "value": value }),
+    )]);
+
+    let mut ob_client = mock_client();
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+    ob_client.market_info = Market {
+        market_address: Pubkey::new_unique(),
+        bids_address: Pubkey::new_unique(),
+        asks_address: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        request_queue: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    ob_client.open_orders.oo_key = Pubkey::new_unique();
+
+    let dump = ob_client.dump_accounts().await.unwrap();
+    assert_eq!(dump.slot, 42);
+
+    let market = &ob_client.market_info;
+    let expected = [
+        ("market", market.market_address),
+        ("bids", market.bids_address),
+        ("asks", market.asks_address),
+        ("event_queue", market.event_queue),
+        ("request_queue", market.request_queue),
+        ("coin_vault", market.coin_vault),
+        ("pc_vault", market.pc_vault),
+        ("open_orders", ob_client.open_orders.oo_key),
+    ];
+    assert_eq!(dump.accounts.len(), expected.len());
+    for (dumped, (name, address)) in dump.accounts.iter().zip(expected) {
+        assert_eq!(dumped.name, name);
+        assert_eq!(dumped.address, address);
+    }
+
+    let bids = dump.accounts[1].account.as_ref().unwrap();
+    assert_eq!(
+        bids.data,
+        UiAccountData::Binary("AQID".to_string(), UiAccountEncoding::Base64)
+    );
+    assert!(dump.accounts[7].account.is_none());
+}
+
+#[tokio::test]
+async fn test_load_bids_asks_info_rejects_stale_and_short_reads() {
+    let account = json!({
+        "lamports": 1,
+        "data": ["", "base64"],
+        "owner": Pubkey::new_unique().to_string(),
+        "executable": false,
+        "rentEpoch": 0,
+        "space": 0,
+    });
+    let load = |slot: u64, value: Vec<serde_json::Value>| {
+        let mut ob_client = mock_client();
+        ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+            "succeeds".to_string(),
+            HashMap::from([(
+                RpcRequest::GetMultipleAccounts,
+                json!({ "context": { "slot": slot }, "value": value }),
+            )]),
+        ));
+        ob_client.open_orders.slot = 100;
+        ob_client
+    };
+
+    // A node lagging behind the last loaded book is never used to rebuild it.
+    let mut ob_client = load(42, vec![account.clone(); 3]);
+    assert!(ob_client.load_bids_asks_info().await.is_err());
+    assert_eq!(ob_client.book_slot(), 100);
+
+    // A response missing an account is an error, not a panic.
+    let mut ob_client = load(120, vec![account.clone(); 2]);
+    let err = ob_client.load_bids_asks_info().await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("expected 3 market accounts, got 2"));
+    assert_eq!(ob_client.book_slot(), 100);
+
+    let mut ob_client = load(120, vec![account.clone(), serde_json::Value::Null, account]);
+    let err = ob_client.load_bids_asks_info().await.unwrap_err();
+    assert!(err.to_string().contains("market account not found"));
+}
+
+#[tokio::test]
+async fn test_cancel_all_orders_returns_one_signature_per_transaction() {
+    let mut ob_client = mock_client();
+    assert!(ob_client.cancel_all_orders().await.unwrap().is_empty());
+
+    ob_client.open_orders.open_bids = (0..20u64).map(|i| (100u128 << 64) | !i as u128).collect();
+    ob_client.open_orders.open_asks = (0..20u64).map(|i| (120u128 << 64) | i as u128).collect();
+
+    let orders: Vec<(u128, Side)> = ob_client
+        .open_orders
+        .open_bids
+        .iter()
+        .map(|id| (*id, Side::Bid))
+        .chain(
+            ob_client
+                .open_orders
+                .open_asks
+                .iter()
+                .map(|id| (*id, Side::Ask)),
+        )
+        .collect();
+    let transactions =
+        ob_client.cancel_transactions(ob_client.cancel_order_instructions(&orders).unwrap());
+    assert!(transactions.len() >= orders.len() / MAX_CANCELS_PER_TRANSACTION);
+    assert!(transactions
+        .iter()
+        .all(|group| group.len() <= MAX_CANCELS_PER_TRANSACTION));
+
+    let signatures = ob_client.cancel_all_orders().await.unwrap();
+    assert_eq!(signatures.len(), transactions.len());
+}
+
+#[tokio::test]
+async fn test_msrm_fee_discount_maps_to_top_tier_and_is_passed_to_new_orders() {
+    let discount_account = Pubkey::new_unique();
+    let mut token_account = vec![0u8; 165];
+    token_account[0..32].copy_from_slice(&msrm_token::ID.to_bytes());
+    token_account[64..72].copy_from_slice(&1u64.to_le_bytes());
+    let mocks = HashMap::from([(
+        RpcRequest::GetAccountInfo,
+        json!({
+            "context": { "slot": 1 },
+            "value": {
+                "lamports": 1,
+                "data": [openbook::bs58::encode(&token_account).into_string(), "base58"],
+                "owner": Pubkey::new_unique().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": 165,
+            },
+        }),
+    )]);
+
+    let mut ob_client = mock_client();
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+
+    let tier = ob_client
+        .set_fee_discount_account(discount_account)
+        .await
+        .unwrap();
+    assert!(matches!(tier, FeeTier::MSRM));
+    assert!(matches!(ob_client.fee_tier, FeeTier::MSRM));
+    assert_eq!(ob_client.fee_discount_account, Some(discount_account));
+
+    let ix = ob_client
+        .new_order_instruction_lots(Side::Bid, 2_100, 1_500, OrderType::Limit)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ix.accounts.len(), 13);
+    assert_eq!(ix.accounts[12].pubkey, discount_account);
+
+    let market = &ob_client.market_info;
+    assert!(matches!(
+        market.fee_discount_tier(&srm_token::ID, 0).unwrap(),
+        FeeTier::Base
+    ));
+    let err = market
+        .fee_discount_tier(&Pubkey::new_unique(), 1)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(OpenBookError::InvalidFeeDiscountMint(_))
+    ));
+}
+
+#[test]
+fn test_break_even_price_covers_round_trip_fees() {
+    let mut ob_client = mock_client();
+    let rebate = maker_rebate_rate(FeeTier::Base);
+    let fee = taker_fee_rate(FeeTier::Base);
+    assert!(fee > rebate);
+
+    // A maker buy of 1 at 100 costs 100 * (1 - rebate); a taker sell at p returns p * (1 - fee).
+    let long_exit = ob_client.break_even_price(100.0, Side::Bid);
+    assert!(long_exit > 100.0);
+    assert!((long_exit * (1.0 - fee) - 100.0 * (1.0 - rebate)).abs() < 1e-9);
+
+    // A maker sell of 1 at 100 returns 100 * (1 + rebate); a taker buy at p costs p * (1 + fee).
+    let short_exit = ob_client.break_even_price(100.0, Side::Ask);
+    assert!(short_exit < 100.0);
+    assert!((short_exit * (1.0 + fee) - 100.0 * (1.0 + rebate)).abs() < 1e-9);
+
+    // A better fee tier needs a smaller move to break even.
+    ob_client.fee_tier = FeeTier::MSRM;
+    assert!(ob_client.break_even_price(100.0, Side::Bid) < long_exit);
+}
+
+#[test]
+fn test_best_prices_are_converted_to_ui_units() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    assert_eq!(ob_client.best_bid(), None);
+    assert_eq!(ob_client.best_ask(), None);
+    assert_eq!(ob_client.mid_price(), None);
+
+    ob_client.open_orders.max_bid = 2_000;
+    assert_eq!(ob_client.best_bid(), Some(2.0));
+    assert_eq!(ob_client.mid_price(), None);
+
+    ob_client.open_orders.min_ask = 2_200;
+    assert_eq!(ob_client.best_ask(), Some(2.2));
+    assert!((ob_client.mid_price().unwrap() - 2.1).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_consume_events_transaction_is_signed_by_crank_payer() {
+    let mut ob_client = mock_client();
+    let crank_payer = Keypair::new();
+    let crank_pubkey = crank_payer.pubkey();
+    ob_client.set_crank_payer(crank_payer);
+
+    let tx = ob_client
+        .consume_events_transaction(vec![Pubkey::new_unique()], 10)
+        .await
+        .unwrap();
+
+    assert_eq!(tx.message.account_keys[0], crank_pubkey);
+    assert_eq!(tx.message.header.num_required_signatures, 1);
+    assert!(tx.is_signed());
+    assert!(tx.verify().is_ok());
+    assert!(!tx.message.account_keys.contains(&ob_client.owner.pubkey()));
+}
+
+#[tokio::test]
+async fn test_fresh_open_orders_cache_entry_skips_the_rpc() {
+    let mut ob_client = mock_client();
+    // Every request to this client fails, so a successful lookup must be served from the cache.
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock("fails".to_string()));
+    let owner = Pubkey::new_unique();
+    let mut cached = ob_client.open_orders.clone();
+    cached.oo_key = Pubkey::new_unique();
+    ob_client
+        .open_orders_cache
+        .insert(owner, OpenOrdersCacheEntry::new(cached.clone()));
+
+    for _ in 0..2 {
+        let open_orders = ob_client
+            .find_open_orders_accounts_for_owner(owner, 60_000)
+            .await
+            .unwrap();
+        assert_eq!(open_orders.oo_key, cached.oo_key);
+    }
+
+    // Once the entry is older than the TTL the accounts are fetched again.
+    std::thread::sleep(Duration::from_millis(2));
+    assert!(ob_client
+        .find_open_orders_accounts_for_owner(owner, 1)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_mint_address_resolves_symbols_and_held_mints() {
+    let mut ob_client = mock_client();
+    assert_eq!(ob_client.get_mint_address("USDC").await.unwrap(), USDC_MINT);
+    assert_eq!(ob_client.get_mint_address("wsol").await.unwrap(), WSOL_MINT);
+
+    let err = ob_client.get_mint_address("DOGE").await.unwrap_err();
+    assert!(matches!(
+        err,
+        MarketError::OpenBook(OpenBookError::UnknownMintSymbol(symbol)) if symbol == "DOGE"
+    ));
+
+    let mint = Pubkey::new_unique();
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        HashMap::from([(
+            RpcRequest::GetTokenAccountsByOwner,
+            json!({ "context": { "slot": 1 }, "value": [] }),
+        )]),
+    ));
+    let err = ob_client
+        .get_mint_address(&mint.to_string())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, MarketError::MintNotFound(found) if found == mint));
+}
+
+#[tokio::test]
+async fn test_settle_balance_to_targets_the_given_wallets() {
+    let ob_client = mock_client();
+    let coin_wallet = Pubkey::new_unique();
+    let pc_wallet = Pubkey::new_unique();
+
+    let instructions = match ob_client
+        .settle_balance_to(coin_wallet, pc_wallet, false)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        other => panic!("expected instructions, got {:?}", other),
+    };
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(instructions[0].accounts[5].pubkey, coin_wallet);
+    assert_eq!(instructions[0].accounts[6].pubkey, pc_wallet);
+
+    let default = match ob_client.settle_balance(false).await.unwrap() {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        other => panic!("expected instructions, got {:?}", other),
+    };
+    assert_eq!(default[0].accounts[5].pubkey, ob_client.base_ata());
+    assert_eq!(default[0].accounts[6].pubkey, ob_client.quote_ata());
+}
+
+#[tokio::test]
+async fn test_builder_requires_a_market_id() {
+    let err = OBClientBuilder::new()
+        .rpc_client(Rpc::new(RpcClient::new_mock("fails".to_string())))
+        .owner(Keypair::new())
+        .open_orders_key(Pubkey::new_unique())
+        .build()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("market id"));
+
+    let market_id = Pubkey::new_unique();
+    let builder = OBClientBuilder::new()
+        .rpc_client(Rpc::new(RpcClient::new_mock("fails".to_string())))
+        .market_id(market_id);
+    assert!(format!("{:?}", builder).contains(&market_id.to_string()));
+    assert!(builder.build().await.is_err());
+}
+
+#[tokio::test]
+async fn test_builder_requires_an_owner_unless_read_only() {
+    let err = OBClientBuilder::new()
+        .rpc_client(Rpc::new(RpcClient::new_mock("succeeds".to_string())))
+        .market_id(Pubkey::new_unique())
+        .build()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("owner keypair"));
+
+    // A read-only client gets past the owner check and fails on the missing market instead.
+    let err = OBClientBuilder::new()
+        .rpc_client(Rpc::new(RpcClient::new_mock("fails".to_string())))
+        .market_id(Pubkey::new_unique())
+        .read_only(true)
+        .build()
+        .await
+        .unwrap_err();
+    assert!(!err.to_string().contains("owner keypair"));
+}
+
+#[tokio::test]
+async fn test_read_only_client_refuses_to_sign() {
+    let mut ob_client = mock_client();
+    ob_client.read_only = true;
+
+    for err in [
+        ob_client.cancel_orders(false).await.unwrap_err(),
+        ob_client.settle_balance(false).await.unwrap_err(),
+        ob_client.cancel_order(1).await.unwrap_err(),
+        ob_client
+            .place_limit_order(10.0, Side::Bid, 0.0, false, 1.0)
+            .await
+            .unwrap_err(),
+    ] {
+        assert_eq!(
+            err.downcast_ref::<OpenBookError>(),
+            Some(&OpenBookError::ReadOnly)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_build_transactions_are_unsigned_and_paid_by_the_owner() {
+    let ob_client = mock_client();
+    let owner = ob_client.owner.pubkey();
+
+    let cancel = ob_client
+        .build_cancel_transaction(&[(1, Side::Bid)])
+        .await
+        .unwrap();
+    let settle = ob_client.build_settle_transaction().await.unwrap();
+
+    for transaction in [cancel, settle] {
+        assert_eq!(transaction.message.account_keys[0], owner);
+        assert!(!transaction.is_signed());
+        assert_ne!(transaction.message.recent_blockhash, Default::default());
+    }
+}
+
+#[test]
+fn test_replace_order_instructions_cancel_then_place() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = test_market();
+    let old_order_id = (1u128 << 64) | 42;
+
+    let instructions = ob_client
+        .replace_order_instructions(
+            old_order_id,
+            OrderSpec {
+                side: Side::Ask,
+                price: 1.5,
+                size: 2.0,
+            },
+        )
+        .unwrap();
+    assert_eq!(instructions.len(), 2);
+
+    match MarketInstruction::unpack(&instructions[0].data) {
+        Some(MarketInstruction::CancelOrderV2(cancel)) => assert_eq!(cancel.order_id, old_order_id),
+        other => panic!("expected a cancel, got {:?}", other),
+    }
+    let order = decode_new_order(&instructions[1]);
+    assert_eq!(order.side, Side::Ask);
+    assert_eq!(order.max_coin_qty.get(), 2_000);
+}
+
+#[test]
+fn test_market_order_instructions_take_and_settle() {
+    let ob_client = mock_client();
+
+    let buy = ob_client
+        .market_order_instructions(Side::Bid, 5_000_000, 7)
+        .unwrap();
+    assert_eq!(buy.len(), 2);
+    let order = decode_new_order(&buy[0]);
+    assert_eq!(order.side, Side::Bid);
+    assert_eq!(order.limit_price.get(), u64::MAX);
+    assert_eq!(order.max_native_pc_qty_including_fees.get(), 5_000_000);
+    assert_eq!(order.order_type, OrderType::ImmediateOrCancel);
+    assert_eq!(order.self_trade_behavior, SelfTradeBehavior::DecrementTake);
+    assert_eq!(order.client_order_id, 7);
+    assert!(matches!(
+        MarketInstruction::unpack(&buy[1].data),
+        Some(MarketInstruction::SettleFunds)
+    ));
+
+    let sell = ob_client
+        .market_order_instructions(Side::Ask, 3, 8)
+        .unwrap();
+    let order = decode_new_order(&sell[0]);
+    assert_eq!(order.limit_price.get(), 1);
+    assert_eq!(order.max_coin_qty.get(), 3);
+
+    assert!(ob_client
+        .market_order_instructions(Side::Ask, 0, 9)
+        .is_err());
+}
+
+#[test]
+fn test_place_and_settle_appends_settle_funds() {
+    let ob_client = mock_client();
+
+    let instructions = ob_client
+        .place_and_settle_instructions(
+            Side::Ask,
+            100,
+            5,
+            OrderType::ImmediateOrCancel,
+            SelfTradeBehavior::DecrementTake,
+            11,
+        )
+        .unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(decode_new_order(&instructions[0]).client_order_id, 11);
+    assert!(matches!(
+        MarketInstruction::unpack(&instructions[1].data),
+        Some(MarketInstruction::SettleFunds)
+    ));
+    assert_eq!(instructions[1].accounts[5].pubkey, ob_client.base_ata());
+    assert_eq!(instructions[1].accounts[6].pubkey, ob_client.quote_ata());
+}
+
+#[tokio::test]
+async fn test_with_open_orders_account_switches_accounts() {
+    let mut ob_client = mock_client();
+    let owner = ob_client.owner.pubkey();
+    ob_client.open_orders_cache.insert(
+        owner,
+        OpenOrdersCacheEntry::new(ob_client.open_orders.clone()),
+    );
+    let oo_key = Pubkey::new_unique();
+
+    let mut ob_client = ob_client.with_open_orders_account(oo_key);
+    assert_eq!(ob_client.open_orders.oo_key, oo_key);
+    assert_eq!(
+        ob_client.open_orders_cache[&owner].open_orders.oo_key,
+        oo_key
+    );
+
+    ob_client.read_only = true;
+    let err = ob_client.create_open_orders_account().await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::ReadOnly)
+    );
+
+    // The mock RPC echoes the fee payer's signature of the transaction it is sent.
+    ob_client.read_only = false;
+    let (account, signature) = ob_client.create_open_orders_account().await.unwrap();
+    assert_ne!(account, oo_key);
+    assert_ne!(signature, Signature::default());
+    assert_eq!(ob_client.open_orders.oo_key, oo_key);
+
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock("fails".to_string()));
+    assert!(ob_client.create_open_orders_account().await.is_err());
+}
+
+#[tokio::test]
+async fn test_place_limit_order_prices_from_market_lot_sizes() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = Market {
+        coin_decimals: 6,
+        pc_decimals: 6,
+        coin_lot_size: 100_000,
+        pc_lot_size: 10,
+        ..Default::default()
+    };
+    ob_client.open_orders.max_bid = ob_client.market_info.price_number_to_lots(25.0);
+
+    let instructions = match ob_client
+        .place_limit_order(100.0, Side::Bid, 5.0, false, 0.0)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        other => panic!("expected instructions, got {:?}", other),
+    };
+    let order = decode_new_order(&instructions[0]);
+    assert_eq!(
+        order.limit_price.get(),
+        ob_client.market_info.price_number_to_lots(20.0)
+    );
+    assert_eq!(
+        order.max_coin_qty.get(),
+        ob_client.market_info.base_size_number_to_lots(5.0)
+    );
+}
+
+#[test]
+fn test_with_skip_preflight_overrides_only_the_clone() {
+    let ob_client = mock_client();
+    assert!(!ob_client.rpc_client.send_config().skip_preflight);
+
+    let skipping = ob_client.clone().with_skip_preflight(true);
+    assert!(skipping.rpc_client.send_config().skip_preflight);
+    assert!(!ob_client.rpc_client.send_config().skip_preflight);
+}
+
+#[tokio::test]
+async fn test_permissioned_crank_is_signed_by_the_events_authority() {
+    let mut ob_client = mock_client();
+    let authority = Keypair::new();
+    ob_client.market_info.events_authority = authority.pubkey();
+    let open_orders_accounts = vec![Pubkey::new_unique()];
+
+    assert!(ob_client
+        .consume_events_permissioned_instruction(open_orders_accounts.clone(), 5)
+        .await
+        .is_err());
+
+    ob_client.set_crank_payer(authority);
+    let (confirmed, _) = ob_client
+        .consume_events_permissioned_instruction(open_orders_accounts, 5)
+        .await
+        .unwrap();
+    assert!(confirmed);
+}
+
+#[tokio::test]
+async fn test_build_match_orders_transaction_carries_the_match_instruction() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = Market {
+        program_id: Pubkey::new_unique(),
+        market_address: Pubkey::new_unique(),
+        request_queue: Pubkey::new_unique(),
+        bids_address: Pubkey::new_unique(),
+        asks_address: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        ..Default::default()
+    };
+
+    let tx = ob_client.build_match_orders_transaction(7).await.unwrap();
+
+    // Paid for and signed by the owner alone.
+    assert_eq!(tx.message.account_keys[0], ob_client.owner.pubkey());
+    assert_eq!(tx.message.header.num_required_signatures, 1);
+    assert_ne!(tx.signatures[0], Signature::default());
+    assert_eq!(tx.message.instructions.len(), 1);
+    let compiled = &tx.message.instructions[0];
+    assert_eq!(
+        tx.message.account_keys[compiled.program_id_index as usize],
+        ob_client.market_info.program_id
+    );
+    assert!(matches!(
+        MarketInstruction::unpack(&compiled.data),
+        Some(MarketInstruction::MatchOrders(7))
+    ));
+    let accounts: Vec<Pubkey> = compiled
+        .accounts
+        .iter()
+        .map(|index| tx.message.account_keys[*index as usize])
+        .collect();
+    assert_eq!(
+        accounts,
+        vec![
+            ob_client.market_info.market_address,
+            ob_client.market_info.request_queue,
+            ob_client.market_info.event_queue,
+            ob_client.market_info.bids_address,
+            ob_client.market_info.asks_address,
+            ob_client.market_info.coin_vault,
+            ob_client.market_info.pc_vault,
+        ]
+    );
+    assert!(tx.verify().is_ok());
+}
+
+#[tokio::test]
+async fn test_consume_events_transaction_is_signed_by_the_owner_by_default() {
+    let ob_client = mock_client();
+
+    let tx = ob_client
+        .consume_events_transaction(vec![Pubkey::new_unique()], 10)
+        .await
+        .unwrap();
+
+    assert_eq!(tx.message.account_keys[0], ob_client.owner.pubkey());
+    assert_ne!(tx.message.recent_blockhash, Default::default());
+    assert!(tx.verify().is_ok());
+}
+
+/// Builds a client whose token accounts are the owner's ATAs, against a mock RPC answering the
+/// token account and mint lookup with `accounts`.
+fn token_accounts_client(accounts: [bool; 4]) -> OBClient {
+    let account = json!({
+        "lamports": 1,
+        "data": ["", "base64"],
+        "owner": Pubkey::new_unique().to_string(),
+        "executable": false,
+        "rentEpoch": 0,
+        "space": 0
+    });
+    let values: Vec<_> = accounts
+        .iter()
+        .map(|exists| {
+            if *exists {
+                account.clone()
+            } else {
+                json!(null)
+            }
+        })
+        .collect();
+    let mocks = HashMap::from([(
+        RpcRequest::GetMultipleAccounts,
+        json!({ "context": { "slot": 1 }, "value": values }),
+    )]);
+
+    let mut ob_client = mock_client();
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+    ob_client.market_info = Market {
+        market_address: Pubkey::new_unique(),
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    // Derive the owner's ATAs from the mints above.
+    ob_client.base_ata = OnceLock::new();
+    ob_client.quote_ata = OnceLock::new();
+    ob_client
+}
+
+#[test]
+fn test_ata_accessors_derive_and_memoize_the_owners_atas() {
+    let mut ob_client = token_accounts_client([true, true, true, true]);
+    let owner = ob_client.owner.pubkey();
+    let base_mint = ob_client.market_info.base_mint;
+    let quote_mint = ob_client.market_info.quote_mint;
+
+    let base_ata = ob_client.base_ata();
+    let quote_ata = ob_client.quote_ata();
+    assert_eq!(
+        base_ata,
+        spl_associated_token_account::get_associated_token_address(&owner, &base_mint)
+    );
+    assert_eq!(
+        quote_ata,
+        spl_associated_token_account::get_associated_token_address(&owner, &quote_mint)
+    );
+    assert_ne!(base_ata, quote_ata);
+
+    // Derived once, the ATAs no longer follow the market's mints.
+    ob_client.market_info.base_mint = Pubkey::new_unique();
+    assert_eq!(ob_client.base_ata(), base_ata);
+
+    let explicit = Pubkey::new_unique();
+    ob_client.quote_ata = OnceLock::from(explicit);
+    assert_eq!(ob_client.quote_ata(), explicit);
+}
+
+#[tokio::test]
+async fn test_missing_token_account_instructions_create_the_clients_atas() {
+    // The base ATA is missing, the quote ATA and both mints exist.
+    let ob_client = token_accounts_client([false, true, true, true]);
+    let owner = ob_client.owner.pubkey();
+
+    let instructions = ob_client
+        .missing_token_account_instructions()
+        .await
+        .unwrap();
+    assert_eq!(instructions.len(), 1);
+    let ix = &instructions[0];
+    assert_eq!(ix.program_id, spl_associated_token_account::id());
+    // payer, associated account, wallet, mint
+    assert_eq!(ix.accounts[0].pubkey, owner);
+    assert_eq!(ix.accounts[1].pubkey, ob_client.base_ata());
+    assert_eq!(ix.accounts[2].pubkey, owner);
+    assert_eq!(ix.accounts[3].pubkey, ob_client.market_info.base_mint);
+    assert!(ix
+        .accounts
+        .iter()
+        .all(|meta| meta.pubkey != ob_client.market_info.market_address));
+
+    let existing = token_accounts_client([true, true, true, true]);
+    assert!(existing
+        .missing_token_account_instructions()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_missing_token_account_instructions_skip_explicit_accounts_and_missing_mints() {
+    // An explicitly set token account is not an ATA and cannot be created.
+    let mut explicit = token_accounts_client([false, false, true, true]);
+    explicit.quote_ata = OnceLock::from(Pubkey::new_unique());
+    let instructions = explicit.missing_token_account_instructions().await.unwrap();
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(instructions[0].accounts[1].pubkey, explicit.base_ata());
+
+    let no_mint = token_accounts_client([true, false, true, false]);
+    let err = no_mint
+        .missing_token_account_instructions()
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::MintNotFound(no_mint.market_info.quote_mint))
+    );
+}
+
+#[tokio::test]
+async fn test_create_missing_token_accounts_sends_the_missing_atas() {
+    let ob_client = token_accounts_client([false, false, true, true]);
+    assert_eq!(
+        ob_client.create_missing_token_accounts().await.unwrap(),
+        (ob_client.base_ata(), ob_client.quote_ata())
+    );
+
+    let mut failing = token_accounts_client([false, false, true, true]);
+    failing.rpc_client = Rpc::new(RpcClient::new_mock("fails".to_string()));
+    assert!(failing.create_missing_token_accounts().await.is_err());
+}
+
+#[tokio::test]
+async fn test_run_crank_returns_once_the_queue_is_empty() {
+    let data = event_queue_bytes(4, 0, 0, &[]);
+    let mocks = HashMap::from([(
+        RpcRequest::GetAccountInfo,
+        json!({
+            "context": { "slot": 1 },
+            "value": {
+                "lamports": 1,
+                "data": [openbook::bs58::encode(&data).into_string(), "base58"],
+                "owner": Pubkey::new_unique().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            },
+        }),
+    )]);
+
+    let mut ob_client = mock_client();
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+
+    let signatures = ob_client
+        .run_crank(Duration::from_millis(1), 10)
+        .await
+        .unwrap();
+    assert!(signatures.is_empty());
+}
+
+#[test]
+fn test_crank_instruction_consumes_the_queued_events() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = Market {
+        program_id: Pubkey::new_unique(),
+        market_address: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    let (first, second, beyond_limit) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let event = |seq_num: u64, owner: Pubkey| Event {
+        seq_num,
+        owner,
+        ..Default::default()
+    };
+    let event_queue = EventQueue {
+        events: vec![
+            event(1, first),
+            event(2, second),
+            event(3, first),
+            event(4, beyond_limit),
+        ],
+        ..Default::default()
+    };
+
+    let ix = ob_client
+        .crank_instruction(&event_queue, 3)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ix.program_id, ob_client.market_info.program_id);
+    assert!(matches!(
+        MarketInstruction::unpack(&ix.data),
+        Some(MarketInstruction::ConsumeEvents(3))
+    ));
+    // The deduplicated open orders accounts of the first `limit` events, then the market
+    // accounts.
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(accounts.len(), 6);
+    let mut cranked = accounts[..2].to_vec();
+    cranked.sort();
+    let mut expected = vec![first, second];
+    expected.sort();
+    assert_eq!(cranked, expected);
+    assert_eq!(
+        accounts[2..],
+        [
+            ob_client.market_info.market_address,
+            ob_client.market_info.event_queue,
+            ob_client.market_info.coin_vault,
+            ob_client.market_info.pc_vault,
+        ]
+    );
+    assert!(ix.accounts.iter().all(|meta| meta.is_writable));
+
+    assert!(ob_client
+        .crank_instruction(&EventQueue::default(), 3)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_consume_events_from_covers_the_events_queued_before_start_seq() {
+    let ob_client = mock_client();
+    let (earlier, target, later) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let event = |seq_num: u64, owner: Pubkey| Event {
+        seq_num,
+        owner,
+        ..Default::default()
+    };
+    let event_queue = EventQueue {
+        events: vec![
+            event(5, earlier),
+            event(6, earlier),
+            event(7, target),
+            event(8, later),
+        ],
+        ..Default::default()
+    };
+
+    let (ix, next_seq) = ob_client
+        .consume_events_from_instruction(&event_queue, 7, 1)
+        .unwrap()
+        .unwrap();
+    match MarketInstruction::unpack(&ix.data) {
+        Some(MarketInstruction::ConsumeEvents(limit)) => assert_eq!(limit, 3),
+        other => panic!("expected a consume events, got {:?}", other),
+    }
+    let cranked: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert!(cranked.contains(&earlier));
+    assert!(cranked.contains(&target));
+    assert!(!cranked.contains(&later));
+    assert_eq!(next_seq, 8);
+
+    // The limit is capped by the queued events.
+    let (ix, next_seq) = ob_client
+        .consume_events_from_instruction(&event_queue, 7, 10)
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        MarketInstruction::unpack(&ix.data),
+        Some(MarketInstruction::ConsumeEvents(4))
+    ));
+    assert_eq!(next_seq, 9);
+
+    // Nothing queued at or after the start sequence number.
+    assert!(ob_client
+        .consume_events_from_instruction(&event_queue, 9, 10)
+        .unwrap()
+        .is_none());
+}
+
+fn enter_market_client() -> OBClient {
+    let mut ob_client = mock_client();
+    ob_client.market_info = Market {
+        program_id: Pubkey::new_unique(),
+        market_address: Pubkey::new_unique(),
+        ..test_market()
+    };
+    ob_client
+}
+
+fn enter_market_instructions(groups: Vec<OrderReturnType>) -> Vec<Instruction> {
+    groups
+        .into_iter()
+        .flat_map(|group| match group {
+            OrderReturnType::Instructions(instructions) => instructions,
+            other => panic!("expected instructions, got {:?}", other),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_enter_market_creates_the_seeded_open_orders_account() {
+    let mut ob_client = enter_market_client();
+    let owner = ob_client.owner.pubkey();
+    let (seeded_oo_key, _) = open_orders_seeded_address(&ob_client.market_info, &owner).unwrap();
+    let first_order = OrderSpec {
+        side: Side::Bid,
+        price: 2.1,
+        size: 1.0,
+    };
+
+    let groups = ob_client.enter_market(first_order, false).await.unwrap();
+    assert_eq!(groups.len(), 1);
+    let instructions = enter_market_instructions(groups);
+
+    // Open orders creation and initialization, both ATAs, then the order.
+    assert_eq!(instructions.len(), 5);
+    assert_eq!(ob_client.open_orders.oo_key, seeded_oo_key);
+    assert_eq!(instructions[0].accounts[1].pubkey, seeded_oo_key);
+    assert_eq!(instructions[1].program_id, ob_client.market_info.program_id);
+    assert_eq!(decode_new_order(&instructions[4]).side, Side::Bid);
+    assert_eq!(instructions[4].accounts[1].pubkey, seeded_oo_key);
+}
+
+#[tokio::test]
+async fn test_enter_market_reuses_an_existing_seeded_open_orders_account() {
+    let mut ob_client = enter_market_client();
+    let owner = ob_client.owner.pubkey();
+    let (seeded_oo_key, _) = open_orders_seeded_address(&ob_client.market_info, &owner).unwrap();
+    let data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    let mocks = HashMap::from([(
+        RpcRequest::GetAccountInfo,
+        json!({
+            "context": { "slot": 1 },
+            "value": {
+                "lamports": 1,
+                "data": [openbook::bs58::encode(&data).into_string(), "base58"],
+                "owner": ob_client.market_info.program_id.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            },
+        }),
+    )]);
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+    let first_order = OrderSpec {
+        side: Side::Ask,
+        price: 2.1,
+        size: 1.0,
+    };
+
+    let groups = ob_client.enter_market(first_order, false).await.unwrap();
+    let instructions = enter_market_instructions(groups);
+
+    // Only the missing ATAs are created before the order.
+    assert_eq!(instructions.len(), 3);
+    assert_eq!(ob_client.open_orders.oo_key, seeded_oo_key);
+    assert_eq!(instructions[2].accounts[1].pubkey, seeded_oo_key);
+}
+
+fn full_event_queue_mocks() -> HashMap<RpcRequest, serde_json::Value> {
+    let data = event_queue_bytes(2, 0, 2, &[Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    HashMap::from([(
+        RpcRequest::GetAccountInfo,
+        json!({
+            "context": { "slot": 1 },
+            "value": {
+                "lamports": 1,
+                "data": [openbook::bs58::encode(&data).into_string(), "base58"],
+                "owner": Pubkey::new_unique().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            },
+        }),
+    )])
+}
+
+#[tokio::test]
+async fn test_order_placement_applies_the_full_event_queue_policy() {
+    let mut ob_client = enter_market_client();
+    ob_client.event_queue_full_policy = EventQueueFullPolicy::Error;
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        full_event_queue_mocks(),
+    ));
+
+    let err = ob_client
+        .place_limit_order(2.1, Side::Bid, 0.0, true, 2.1)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::EventQueueFull)
+    );
+
+    // The crank policy consumes the head events, then the order goes through.
+    ob_client.event_queue_full_policy = EventQueueFullPolicy::Crank;
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        full_event_queue_mocks(),
+    ));
+    let placed = ob_client
+        .place_limit_order(2.1, Side::Bid, 0.0, true, 2.1)
+        .await
+        .unwrap();
+    assert!(matches!(placed, Some(OrderReturnType::Signature(_))));
+}
+
+#[tokio::test]
+async fn test_cancels_by_client_id_stay_inside_the_namespace() {
+    let mut ob_client = mock_client();
+    ob_client.client_id_prefix = 7;
+    let foreign = (8u64 << 32) | 1;
+
+    let err = ob_client
+        .cancel_order_by_client_id_instruction(foreign)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::ClientOrderIdOutsideNamespace {
+            client_order_id: foreign,
+            prefix: 7,
+        })
+    );
+    let err = ob_client
+        .cancel_client_id_from(foreign, &ob_client.open_orders, false)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(OpenBookError::ClientOrderIdOutsideNamespace { .. })
+    ));
+
+    assert!(ob_client
+        .cancel_order_by_client_id_instruction((7u64 << 32) | 1)
+        .is_ok());
+}
+
+fn reduce_only_mocks(
+    wallet_base: f64,
+    coin_free: u64,
+    coin_total: u64,
+) -> HashMap<RpcRequest, serde_json::Value> {
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    data[77..85].copy_from_slice(&coin_free.to_le_bytes());
+    data[85..93].copy_from_slice(&coin_total.to_le_bytes());
+    data[109..125].copy_from_slice(&u128::MAX.to_le_bytes());
+
+    HashMap::from([
+        (
+            RpcRequest::GetTokenAccountBalance,
+            json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "amount": ((wallet_base * 1e9) as u64).to_string(),
+                    "decimals": 9,
+                    "uiAmount": wallet_base,
+                    "uiAmountString": wallet_base.to_string(),
+                },
+            }),
+        ),
+        (
+            RpcRequest::GetAccountInfo,
+            json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "lamports": 1,
+                    "data": [openbook::bs58::encode(&data).into_string(), "base58"],
+                    "owner": Pubkey::new_unique().to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                    "space": data.len(),
+                },
+            }),
+        ),
+    ])
+}
+
+#[tokio::test]
+async fn test_place_reduce_only_order_nets_open_orders_and_resting_orders() {
+    let mut ob_client = enter_market_client();
+
+    // 1.0 base in the wallet, 0.5 free and 0.5 locked in resting asks: 1.5 left to sell.
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        reduce_only_mocks(1.0, 500_000_000, 1_000_000_000),
+    ));
+    let placed = ob_client
+        .place_reduce_only_order(Side::Ask, 5.0, 2.0, false)
+        .await
+        .unwrap();
+    let Some(OrderReturnType::Instructions(instructions)) = placed else {
+        panic!("expected instructions, got {:?}", placed);
+    };
+    let order = decode_new_order(&instructions[0]);
+    assert_eq!(order.side, Side::Ask);
+    assert_eq!(order.max_coin_qty.get(), 1_500);
+
+    // Below a 3.0 base target the client is short 1.0 and a bid buys it back.
+    ob_client.base_position_target = 3.0;
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        reduce_only_mocks(1.0, 500_000_000, 1_000_000_000),
+    ));
+    let placed = ob_client
+        .place_reduce_only_order(Side::Bid, 5.0, 2.0, false)
+        .await
+        .unwrap();
+    let Some(OrderReturnType::Instructions(instructions)) = placed else {
+        panic!("expected instructions, got {:?}", placed);
+    };
+    assert_eq!(decode_new_order(&instructions[0]).max_coin_qty.get(), 1_000);
+
+    // An ask while short would increase exposure.
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        reduce_only_mocks(1.0, 500_000_000, 1_000_000_000),
+    ));
+    assert!(ob_client
+        .place_reduce_only_order(Side::Ask, 1.0, 2.0, false)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_plan_sol_management_treats_a_missing_wsol_ata_as_empty() {
+    let mut ob_client = mock_client();
+    ob_client.market_info = Market {
+        quote_mint: WSOL_MINT,
+        coin_decimals: 6,
+        pc_decimals: 9,
+        coin_lot_size: 1_000,
+        pc_lot_size: 1,
+        ..Default::default()
+    };
+    let trades = [OrderSpec {
+        side: Side::Bid,
+        price: 2.0,
+        size: 1.0,
+    }];
+
+    let plan = ob_client.plan_sol_management(&trades).await.unwrap();
+    assert_eq!(plan.wrap, 2_000_000_000);
+}
+
+#[tokio::test]
+async fn test_find_or_create_ata_tells_a_missing_mint_from_rpc_failures() {
+    let mut ob_client = mock_client();
+    let mint = Pubkey::new_unique();
+
+    let err = ob_client
+        .find_or_create_associated_token_account(&mint)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, MarketError::MintNotFound(found) if found == mint));
+
+    ob_client.rpc_client = Rpc::new(RpcClient::new_mock("fails".to_string()));
+    let err = ob_client
+        .find_or_create_associated_token_account(&mint)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, MarketError::Rpc(_)));
+}
+
+#[cfg(feature = "ws")]
+#[tokio::test]
+async fn test_subscriptions_fail_without_a_reachable_websocket() {
+    // The mock RPC has no websocket endpoint, so every subscription errors instead of panicking.
+    let ob_client = mock_client();
+
+    assert!(ob_client.subscribe_open_orders().await.is_err());
+    assert!(ob_client.subscribe_balances().await.is_err());
+    assert!(ob_client.subscribe_order_book(5).await.is_err());
+}
@@ -0,0 +1,707 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::test_market;
+use futures_util::{stream, StreamExt};
+use openbook::error::OpenBookError;
+use openbook::keypair::Keypair;
+use openbook::matching::Side;
+use openbook::pubkey::Pubkey;
+use openbook::rpc::Rpc;
+use openbook::rpc_client::RpcClient;
+use openbook::utils::get_unix_millis;
+use openbook::v1::market::{taker_fee, taker_fee_rate, Market};
+use openbook::v1::orders::{
+    aggregate_free_balances, clamp_reduce_only, client_order_id_in_namespace,
+    dump_open_orders_cache, free_order_slots, load_open_orders_cache, namespaced_client_order_id,
+    open_orders_account_space, open_orders_balance_updates, open_orders_balances,
+    open_orders_free_balances, open_orders_is_empty, open_orders_order_ids, open_orders_updates,
+    order_id_side, quote_action, sol_plan, Balances, OpenOrders, OpenOrdersCacheEntry, Order,
+    OrderBook, OrderSpec, QuoteAction, SimResult, SolPlan, TakerPreview, TakerResult,
+    OPEN_ORDERS_ACCOUNT_LEN, OPEN_ORDERS_SLOTS, WSOL_MINT,
+};
+use openbook::v1::traits::OpenOrdersT;
+use openbook_dex::fees::FeeTier;
+use std::collections::HashMap;
+
+#[test]
+fn test_reduce_only_ask_is_clamped_to_position() {
+    assert_eq!(clamp_reduce_only(Side::Ask, 5.0, 2.0, 0.0).unwrap(), 2.0);
+    assert_eq!(clamp_reduce_only(Side::Ask, 1.0, 2.0, 0.0).unwrap(), 1.0);
+    // Resting asks already sell part of the position.
+    assert_eq!(clamp_reduce_only(Side::Ask, 5.0, 2.0, 1.5).unwrap(), 0.5);
+}
+
+#[test]
+fn test_reduce_only_bid_is_clamped_to_short_position() {
+    assert_eq!(clamp_reduce_only(Side::Bid, 5.0, -2.0, 0.0).unwrap(), 2.0);
+    assert_eq!(clamp_reduce_only(Side::Bid, 5.0, -2.0, 0.5).unwrap(), 1.5);
+}
+
+#[test]
+fn test_reduce_only_rejects_increasing_exposure() {
+    assert!(clamp_reduce_only(Side::Bid, 1.0, 2.0, 0.0).is_err());
+    assert!(clamp_reduce_only(Side::Ask, 1.0, 0.0, 0.0).is_err());
+    assert!(clamp_reduce_only(Side::Ask, 1.0, 2.0, 2.0).is_err());
+    assert!(clamp_reduce_only(Side::Bid, 1.0, -1.0, 1.0).is_err());
+}
+
+#[test]
+fn test_queue_position_at_same_price_level() {
+    let market = Market {
+        coin_decimals: 9,
+        coin_lot_size: 1_000_000,
+        ..Default::default()
+    };
+    let owner = Pubkey::new_unique();
+    let order = |order_id, price, quantity| Order {
+        order_id,
+        price,
+        quantity,
+        owner,
+        client_order_id: 0,
+    };
+    let open_orders = OpenOrders {
+        bids: vec![
+            order(1, 101, 500),
+            order(2, 100, 1_000),
+            order(3, 100, 2_000),
+            order(4, 100, 3_000),
+            order(5, 99, 4_000),
+        ],
+        ..Default::default()
+    };
+
+    let position = open_orders.queue_position(4, &market).unwrap();
+    assert_eq!(position.orders_ahead, 2);
+    assert!((position.size_ahead_ui - 3.0).abs() < 1e-9);
+
+    let first = open_orders.queue_position(2, &market).unwrap();
+    assert_eq!(first.orders_ahead, 0);
+    assert_eq!(first.size_ahead_ui, 0.0);
+
+    assert!(open_orders.queue_position(42, &market).is_none());
+}
+
+#[test]
+fn test_generated_client_ids_carry_prefix() {
+    let prefix = 0xABCD;
+    for id in [0, 1, u64::MAX, 0x1234_5678_9ABC_DEF0] {
+        let client_order_id = namespaced_client_order_id(prefix, id);
+        assert_eq!(client_order_id >> 32, prefix as u64);
+        assert!(client_order_id_in_namespace(prefix, client_order_id));
+        assert!(!client_order_id_in_namespace(prefix + 1, client_order_id));
+    }
+
+    // Without a prefix the whole id is kept and every id is in the namespace.
+    assert_eq!(namespaced_client_order_id(0, u64::MAX), u64::MAX);
+    assert!(client_order_id_in_namespace(0, (prefix as u64) << 32));
+}
+
+#[test]
+fn test_taker_result_from_balance_deltas() {
+    let partial = TakerResult::from_balance_deltas(Side::Bid, 10.0, 4.0, -400.0);
+    assert_eq!(partial.filled_base, 4.0);
+    assert_eq!(partial.filled_quote, 400.0);
+    assert_eq!(partial.avg_price, 100.0);
+    assert_eq!(partial.cancelled_base, 6.0);
+
+    let sold = TakerResult::from_balance_deltas(Side::Ask, 2.0, -2.0, 199.0);
+    assert_eq!(sold.filled_base, 2.0);
+    assert_eq!(sold.avg_price, 99.5);
+    assert_eq!(sold.cancelled_base, 0.0);
+
+    let unfilled = TakerResult::from_balance_deltas(Side::Bid, 5.0, 0.0, 0.0);
+    assert_eq!(unfilled.filled_base, 0.0);
+    assert_eq!(unfilled.filled_quote, 0.0);
+    assert_eq!(unfilled.avg_price, 0.0);
+    assert_eq!(unfilled.cancelled_base, 5.0);
+}
+
+#[test]
+fn test_taker_result_counts_unsettled_funds_as_not_traded() {
+    // An unsettled IOC bid deposits its whole 500 quote budget, fills 4 base for 400 quote and
+    // leaves the fill and the unused 100 quote free in the open orders account.
+    let before = Balances {
+        quote_free: 20.0,
+        ..Default::default()
+    };
+    let after = Balances {
+        base_free: 4.0,
+        quote_free: 120.0,
+        ..Default::default()
+    };
+    let bid = TakerResult::from_account_deltas(Side::Bid, 10.0, (0.0, -500.0), &before, &after);
+    assert_eq!(bid.filled_base, 4.0);
+    assert_eq!(bid.filled_quote, 400.0);
+    assert_eq!(bid.avg_price, 100.0);
+    assert_eq!(bid.cancelled_base, 6.0);
+
+    // A settled order leaves the open orders account as it was, so only the wallet counts.
+    let sold = TakerResult::from_account_deltas(Side::Ask, 2.0, (-2.0, 199.0), &before, &before);
+    assert_eq!(
+        sold,
+        TakerResult::from_balance_deltas(Side::Ask, 2.0, -2.0, 199.0)
+    );
+}
+
+#[test]
+fn test_open_orders_cache_round_trip_honors_staleness() {
+    let fresh_owner = Pubkey::new_unique();
+    let stale_owner = Pubkey::new_unique();
+    let now = 1_000_000;
+
+    let mut cache = HashMap::new();
+    cache.insert(
+        fresh_owner,
+        OpenOrdersCacheEntry {
+            open_orders: OpenOrders {
+                oo_key: Pubkey::new_unique(),
+                max_bid: 42,
+                ..Default::default()
+            },
+            ts: now - 1_000,
+        },
+    );
+    cache.insert(
+        stale_owner,
+        OpenOrdersCacheEntry {
+            open_orders: OpenOrders::default(),
+            ts: now - 60_000,
+        },
+    );
+
+    let restored = load_open_orders_cache(&dump_open_orders_cache(&cache)).unwrap();
+    assert_eq!(restored.len(), 2);
+
+    let fresh = &restored[&fresh_owner];
+    assert_eq!(
+        fresh.open_orders.oo_key,
+        cache[&fresh_owner].open_orders.oo_key
+    );
+    assert_eq!(fresh.open_orders.max_bid, 42);
+    assert!(fresh.is_fresh(now, 5_000));
+    assert!(!restored[&stale_owner].is_fresh(now, 5_000));
+
+    assert!(load_open_orders_cache(&[1, 2, 3]).is_err());
+}
+
+#[test]
+fn test_crossed_book_guard_triggers() {
+    let crossed = OpenOrders {
+        max_bid: 101,
+        min_ask: 100,
+        ..Default::default()
+    };
+    assert!(crossed.is_book_crossed());
+    let err = crossed.ensure_book_not_crossed().unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::CrossedBook)
+    );
+
+    let locked = OpenOrders {
+        max_bid: 100,
+        min_ask: 100,
+        ..Default::default()
+    };
+    assert!(locked.is_book_crossed());
+
+    let healthy = OpenOrders {
+        max_bid: 99,
+        min_ask: 100,
+        ..Default::default()
+    };
+    assert!(!healthy.is_book_crossed());
+    assert!(healthy.ensure_book_not_crossed().is_ok());
+
+    let one_sided = OpenOrders {
+        max_bid: 99,
+        ..Default::default()
+    };
+    assert!(!one_sided.is_book_crossed());
+}
+
+#[test]
+fn test_find_client_order_resolves_side_from_cached_book() {
+    let oo_key = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let order = |order_id, owner, client_order_id| Order {
+        order_id,
+        price: 100,
+        quantity: 1,
+        owner,
+        client_order_id,
+    };
+    let book = OpenOrders {
+        bids: vec![order(1, other, 7), order(2, oo_key, 8)],
+        asks: vec![order(3, oo_key, 7)],
+        ..Default::default()
+    };
+
+    let (side, found) = book.find_client_order(&oo_key, 7).unwrap();
+    assert_eq!(side, Side::Ask);
+    assert_eq!(found.order_id, 3);
+
+    let (side, found) = book.find_client_order(&oo_key, 8).unwrap();
+    assert_eq!(side, Side::Bid);
+    assert_eq!(found.order_id, 2);
+
+    let err = book.find_client_order(&oo_key, 9).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::ClientOrderIdNotFound(9))
+    );
+}
+
+#[test]
+fn test_aggregate_free_balances_sums_accounts() {
+    let account = |coin_free: u64, pc_free: u64| {
+        let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+        data[77..85].copy_from_slice(&coin_free.to_le_bytes());
+        data[93..101].copy_from_slice(&pc_free.to_le_bytes());
+        data
+    };
+    let first = account(1_000, 25);
+    let second = account(500, 75);
+
+    assert_eq!(open_orders_free_balances(&first).unwrap(), (1_000, 25));
+    assert_eq!(
+        aggregate_free_balances([first.as_slice(), second.as_slice()]).unwrap(),
+        (1_500, 100)
+    );
+    assert_eq!(aggregate_free_balances([]).unwrap(), (0, 0));
+    assert!(aggregate_free_balances([&first[..100]]).is_err());
+}
+
+#[test]
+fn test_open_orders_balances_splits_free_and_locked() {
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    data[77..85].copy_from_slice(&1_500_000_000u64.to_le_bytes());
+    data[85..93].copy_from_slice(&2_000_000_000u64.to_le_bytes());
+    data[93..101].copy_from_slice(&3_000_000u64.to_le_bytes());
+    data[101..109].copy_from_slice(&10_000_000u64.to_le_bytes());
+    data[109..125].copy_from_slice(&(u128::MAX - 1).to_le_bytes());
+    data[141..157].copy_from_slice(&42u128.to_le_bytes());
+    let market = Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        open_orders_balances(&data, &market).unwrap(),
+        Balances {
+            base_free: 1.5,
+            base_locked: 0.5,
+            quote_free: 3.0,
+            quote_locked: 7.0,
+            order_ids: vec![42],
+        }
+    );
+    assert!(open_orders_balances(&data[..100], &market).is_err());
+}
+
+#[test]
+fn test_open_orders_is_empty_requires_no_funds() {
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    data[109..125].copy_from_slice(&u128::MAX.to_le_bytes());
+    assert!(open_orders_is_empty(&data).unwrap());
+
+    data[101..109].copy_from_slice(&1u64.to_le_bytes());
+    assert!(!open_orders_is_empty(&data).unwrap());
+    assert!(open_orders_is_empty(&data[..100]).is_err());
+}
+
+#[tokio::test]
+async fn test_open_orders_updates_decode_mock_account_updates() {
+    let market = Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        ..Default::default()
+    };
+    let account = |coin_total: u64, pc_total: u64| {
+        let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+        data[85..93].copy_from_slice(&coin_total.to_le_bytes());
+        data[101..109].copy_from_slice(&pc_total.to_le_bytes());
+        data
+    };
+    let oo_key = Pubkey::new_unique();
+    let initial = OpenOrders {
+        oo_key,
+        ..Default::default()
+    };
+
+    let updates = stream::iter(vec![
+        account(2_000_000_000, 0),
+        vec![0u8; 12],
+        account(500_000_000, 3_000_000),
+    ]);
+    let states: Vec<OpenOrders> = open_orders_updates(updates, initial, market)
+        .collect()
+        .await;
+
+    assert_eq!(states.len(), 2);
+    assert!(states.iter().all(|state| state.oo_key == oo_key));
+    assert_eq!(states[0].base_total, 2.0);
+    assert_eq!(states[0].quote_total, 0.0);
+    assert_eq!(states[1].base_total, 0.5);
+    assert_eq!(states[1].quote_total, 3.0);
+}
+
+#[tokio::test]
+async fn test_open_orders_balance_updates_skip_malformed_updates() {
+    let market = Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        ..Default::default()
+    };
+    let account = |coin_free: u64, order_id: Option<u128>| {
+        let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+        data[77..85].copy_from_slice(&coin_free.to_le_bytes());
+        data[85..93].copy_from_slice(&coin_free.to_le_bytes());
+        let free_slot_bits = match order_id {
+            Some(order_id) => {
+                data[141..157].copy_from_slice(&order_id.to_le_bytes());
+                u128::MAX - 1
+            }
+            None => u128::MAX,
+        };
+        data[109..125].copy_from_slice(&free_slot_bits.to_le_bytes());
+        data
+    };
+
+    let updates = stream::iter(vec![
+        account(0, Some(7)),
+        vec![0u8; 12],
+        account(1_000_000_000, None),
+    ]);
+    let balances: Vec<Balances> = open_orders_balance_updates(updates, market).collect().await;
+
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances[0].order_ids, vec![7]);
+    assert_eq!(balances[1].base_free, 1.0);
+    assert!(balances[1].order_ids.is_empty());
+}
+
+#[test]
+fn test_quote_action() {
+    let resting = Order {
+        price: 2_100,
+        ..Default::default()
+    };
+    assert_eq!(quote_action(Some(&resting), 2_100), QuoteAction::Keep);
+    assert_eq!(quote_action(Some(&resting), 2_101), QuoteAction::Replace);
+    assert_eq!(quote_action(None, 2_100), QuoteAction::Place);
+}
+
+#[test]
+fn test_locked_balances_from_resting_orders() {
+    let market = Market {
+        coin_lot_size: 1_000_000,
+        pc_lot_size: 10,
+        ..Default::default()
+    };
+    let oo_key = Pubkey::new_unique();
+    let order = |price, quantity, owner| Order {
+        price,
+        quantity,
+        owner,
+        ..Default::default()
+    };
+    let book = OpenOrders {
+        bids: vec![
+            order(210, 5, oo_key),
+            order(209, 100, Pubkey::new_unique()),
+            order(200, 2, oo_key),
+        ],
+        asks: vec![order(215, 3, oo_key), order(220, 4, Pubkey::new_unique())],
+        ..Default::default()
+    };
+
+    let (base_locked, quote_locked) = book.locked_balances(&oo_key, &market);
+    assert_eq!(base_locked, 3 * 1_000_000);
+    assert_eq!(quote_locked, (210 * 5 + 200 * 2) * 10);
+}
+
+#[test]
+fn test_owns_order_checks_book_and_open_orders_slots() {
+    let market = Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        ..Default::default()
+    };
+    let resting_id = (100u128 << 64) | 1;
+    let slot_only_id = (99u128 << 64) | 2;
+    let foreign_id = (101u128 << 64) | 3;
+
+    // Every slot is free except slot 3, which holds an order that already left the book.
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    data[109..125].copy_from_slice(&(!(1u128 << 3)).to_le_bytes());
+    data[141 + 3 * 16..141 + 4 * 16].copy_from_slice(&slot_only_id.to_le_bytes());
+    assert_eq!(open_orders_order_ids(&data).unwrap(), vec![slot_only_id]);
+    assert!(open_orders_order_ids(&data[..100]).is_err());
+
+    let mut open_orders = OpenOrders {
+        open_bids: vec![resting_id],
+        ..Default::default()
+    };
+    open_orders.update_from_account(&data, &market).unwrap();
+
+    assert!(open_orders.owns_order(resting_id));
+    assert!(open_orders.owns_order(slot_only_id));
+    assert!(!open_orders.owns_order(foreign_id));
+}
+
+#[test]
+fn test_open_orders_account_space_matches_supported_capacity() {
+    assert_eq!(
+        open_orders_account_space(OPEN_ORDERS_SLOTS).unwrap(),
+        OPEN_ORDERS_ACCOUNT_LEN
+    );
+    assert_eq!(OPEN_ORDERS_ACCOUNT_LEN, 3228);
+    // The V1 program has no larger layout.
+    assert!(open_orders_account_space(2 * OPEN_ORDERS_SLOTS).is_err());
+
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_LEN];
+    data[109..125].copy_from_slice(&(!0u128 << 3).to_le_bytes());
+    assert_eq!(free_order_slots(&data).unwrap(), OPEN_ORDERS_SLOTS - 3);
+    assert!(free_order_slots(&[0u8; 2 * OPEN_ORDERS_ACCOUNT_LEN]).is_err());
+}
+
+#[test]
+fn test_sol_plan_nets_a_buy_then_sell_round_trip() {
+    let market = Market {
+        quote_mint: WSOL_MINT,
+        coin_decimals: 6,
+        pc_decimals: 9,
+        coin_lot_size: 1_000,
+        pc_lot_size: 1,
+        ..Default::default()
+    };
+    let trades = [
+        OrderSpec {
+            side: Side::Bid,
+            price: 2.0,
+            size: 1.0,
+        },
+        OrderSpec {
+            side: Side::Ask,
+            price: 2.5,
+            size: 1.0,
+        },
+    ];
+
+    // The buy spends 2 SOL and the sell returns 2.5 SOL, less taker fees, from 0.5 WSOL.
+    let buy_fee = taker_fee(FeeTier::Base, 2_000_000_000);
+    let sell_fee = taker_fee(FeeTier::Base, 2_500_000_000);
+    assert_eq!(
+        sol_plan(&market, FeeTier::Base, &trades, 500_000_000),
+        SolPlan {
+            wrap: 1_500_000_000 + buy_fee,
+            unwrap: 2_000_000_000 - sell_fee,
+        }
+    );
+
+    let no_sol = Market {
+        quote_mint: Pubkey::new_unique(),
+        ..market
+    };
+    assert_eq!(
+        sol_plan(&no_sol, FeeTier::Base, &trades, 0),
+        SolPlan::default()
+    );
+}
+
+#[test]
+fn test_order_id_side_is_decoded_from_the_sequence_bits() {
+    let seq_num = 42u64;
+    let bid_id = (2_100u128 << 64) | (!seq_num as u128);
+    let ask_id = (2_100u128 << 64) | seq_num as u128;
+
+    assert_eq!(order_id_side(bid_id), Side::Bid);
+    assert_eq!(order_id_side(ask_id), Side::Ask);
+}
+
+#[test]
+fn test_new_cache_entry_is_stamped_with_the_current_time() {
+    let before = get_unix_millis();
+    let entry = OpenOrdersCacheEntry::new(OpenOrders::default());
+    let after = get_unix_millis();
+
+    assert!(entry.ts >= before && entry.ts <= after);
+    assert_ne!(entry.ts, 123456789);
+    assert!(entry.is_fresh(after, 1_000));
+}
+
+#[test]
+fn test_order_book_aggregates_levels_up_to_depth() {
+    let market = test_market();
+    let order = |price: u64, quantity: u64| Order {
+        price,
+        quantity,
+        ..Default::default()
+    };
+    let open_orders = OpenOrders {
+        bids: vec![
+            order(2_100, 500),
+            order(2_100, 250),
+            order(2_000, 1_000),
+            order(1_900, 10),
+        ],
+        asks: vec![order(2_200, 100)],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        open_orders.order_book(&market, 2),
+        OrderBook {
+            bids: vec![(2.1, 0.75), (2.0, 1.0)],
+            asks: vec![(2.2, 0.1)],
+        }
+    );
+    assert_eq!(open_orders.order_book(&market, 0), OrderBook::default());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_order_book_serde_round_trip() {
+    let book = OrderBook {
+        bids: vec![(2.1, 0.75), (2.0, 1.0)],
+        asks: vec![(2.2, 0.1)],
+    };
+
+    let json = serde_json::to_value(&book).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "bids": [[2.1, 0.75], [2.0, 1.0]],
+            "asks": [[2.2, 0.1]],
+        })
+    );
+    let restored: OrderBook = serde_json::from_value(json).unwrap();
+    assert_eq!(restored, book);
+
+    let empty: OrderBook = serde_json::from_str(r#"{"bids":[],"asks":[]}"#).unwrap();
+    assert_eq!(empty, OrderBook::default());
+}
+
+#[test]
+fn test_preview_taker_walks_the_opposite_ladder() {
+    let market = test_market();
+    let order = |price: u64, quantity: u64| Order {
+        price,
+        quantity,
+        ..Default::default()
+    };
+    let open_orders = OpenOrders {
+        bids: vec![order(1_900, 200)],
+        asks: vec![order(2_000, 500), order(2_100, 1_000)],
+        ..Default::default()
+    };
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    let rate = taker_fee_rate(FeeTier::Base);
+
+    let buy = open_orders.preview_taker(&market, FeeTier::Base, Side::Bid, 1.0);
+    assert!(close(buy.filled_qty, 1.0));
+    assert!(close(buy.quote_cost, 2.05));
+    assert!(close(buy.avg_price, 2.05));
+    assert!(close(buy.fee, 2.05 * rate));
+    assert!(close(buy.total_cost, 2.05 * (1.0 + rate)));
+
+    let sell = open_orders.preview_taker(&market, FeeTier::Base, Side::Ask, 0.5);
+    assert!(close(sell.filled_qty, 0.2));
+    assert!(close(sell.quote_cost, 0.38));
+    assert!(close(sell.avg_price, 1.9));
+    assert!(close(sell.total_cost, 0.38 * (1.0 - rate)));
+
+    // A discounted tier pays a smaller fee on the same fills.
+    let discounted = open_orders.preview_taker(&market, FeeTier::MSRM, Side::Bid, 1.0);
+    assert!(discounted.fee < buy.fee);
+
+    assert_eq!(
+        OpenOrders::default().preview_taker(&market, FeeTier::Base, Side::Bid, 1.0),
+        TakerPreview::default()
+    );
+}
+
+#[test]
+fn test_simulate_order_stops_at_the_limit_price() {
+    let market = test_market();
+    let order = |price: u64, quantity: u64| Order {
+        price,
+        quantity,
+        ..Default::default()
+    };
+    let open_orders = OpenOrders {
+        bids: vec![order(1_900, 200), order(1_800, 300)],
+        asks: vec![order(2_000, 500), order(2_100, 1_000)],
+        ..Default::default()
+    };
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    let rate = taker_fee_rate(FeeTier::Base);
+
+    let buy = open_orders.simulate_order(&market, FeeTier::Base, Side::Bid, 2.0, 1.0);
+    assert!(close(buy.filled_qty, 0.5));
+    assert!(close(buy.avg_price, 2.0));
+    assert!(close(buy.fee, 1.0 * rate));
+    assert!(close(buy.resting_qty, 0.5));
+
+    let sell = open_orders.simulate_order(&market, FeeTier::Base, Side::Ask, 1.8, 0.4);
+    assert!(close(sell.filled_qty, 0.4));
+    assert!(close(sell.avg_price, 1.85));
+    assert!(close(sell.fee, 0.74 * rate));
+    assert!(close(sell.resting_qty, 0.0));
+
+    // Without a limit the same walk is the taker preview.
+    let preview = open_orders.preview_taker(&market, FeeTier::Base, Side::Ask, 0.4);
+    assert!(close(preview.avg_price, sell.avg_price));
+    assert!(close(preview.fee, sell.fee));
+
+    assert_eq!(
+        open_orders.simulate_order(&market, FeeTier::Base, Side::Bid, 1.5, 1.0),
+        SimResult {
+            filled_qty: 0.0,
+            avg_price: 0.0,
+            fee: 0.0,
+            resting_qty: 1.0,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_make_create_account_transaction_sends_through_rpc() {
+    let owner = Keypair::new();
+    let program_id = Pubkey::new_unique();
+    let market_address = Pubkey::new_unique();
+    let mut open_orders = OpenOrders::default();
+
+    let rpc_client = Rpc::new(RpcClient::new_mock("succeeds".to_string()));
+    let account = open_orders
+        .make_create_account_transaction(
+            &rpc_client,
+            program_id,
+            &owner,
+            market_address,
+            OPEN_ORDERS_SLOTS,
+        )
+        .await
+        .unwrap();
+    assert_ne!(account, Pubkey::default());
+    assert_eq!(open_orders.oo_key, account);
+
+    let rpc_client = Rpc::new(RpcClient::new_mock("fails".to_string()));
+    let mut open_orders = OpenOrders::default();
+    assert!(open_orders
+        .make_create_account_transaction(
+            &rpc_client,
+            program_id,
+            &owner,
+            market_address,
+            OPEN_ORDERS_SLOTS,
+        )
+        .await
+        .is_err());
+    assert_eq!(open_orders.oo_key, Pubkey::default());
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "v1")]
+
+use openbook::matching::Side;
+use openbook::v1::position::{apply_fills, Fill, Position};
+
+fn fill(side: Side, price: f64, size: f64) -> Fill {
+    Fill {
+        side,
+        price,
+        size,
+        fee: 0.0,
+    }
+}
+
+#[test]
+fn test_buy_then_partial_sell() {
+    let mut position = Position::default();
+    apply_fills(
+        &mut position,
+        &[
+            fill(Side::Bid, 2.0, 3.0),
+            fill(Side::Bid, 2.6, 1.0),
+            fill(Side::Ask, 3.0, 1.5),
+        ],
+    );
+
+    assert!((position.base - 2.5).abs() < 1e-9);
+    assert!((position.quote - (-6.0 - 2.6 + 4.5)).abs() < 1e-9);
+    assert!((position.avg_entry_price - 2.15).abs() < 1e-9);
+    assert!((position.realized_pnl - 1.5 * 0.85).abs() < 1e-9);
+}
+
+#[test]
+fn test_flip_and_close_reset_entry_price() {
+    let mut position = Position::default();
+    position.apply(&Fill {
+        side: Side::Bid,
+        price: 2.0,
+        size: 1.0,
+        fee: 0.01,
+    });
+    assert!((position.quote + 2.01).abs() < 1e-9);
+
+    position.apply(&fill(Side::Ask, 2.5, 3.0));
+    assert!((position.base + 2.0).abs() < 1e-9);
+    assert_eq!(position.avg_entry_price, 2.5);
+    assert!((position.realized_pnl - 0.5).abs() < 1e-9);
+
+    position.apply(&fill(Side::Bid, 2.0, 2.0));
+    assert_eq!(position.base, 0.0);
+    assert_eq!(position.avg_entry_price, 0.0);
+    assert!((position.realized_pnl - 1.5).abs() < 1e-9);
+}
@@ -1,7 +1,30 @@
+use openbook::commitment_config::{CommitmentConfig, CommitmentLevel};
+use openbook::error::OpenBookError;
+use openbook::keypair::Keypair;
 use openbook::pubkey::Pubkey;
-use openbook::rpc::Rpc;
+use openbook::rpc::{
+    compute_units_consumed, ensure_min_context_slot, fee_percentile, parse_retry_after,
+    rate_limit_error, sum_transaction_fees, with_rate_limit, Rpc, SendConfig,
+};
 use openbook::rpc_client::RpcClient;
 use openbook::signature::Signature;
+use serde_json::json;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::{RpcError, RpcRequest, RpcResponseErrorData, TokenAccountsFilter};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client_api::client_error::ErrorKind;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{
+    Encodable, EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding,
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_fetch_transaction() {
@@ -29,3 +52,535 @@ async fn test_fetch_multiple_accounts() {
     let result = rpc.fetch_multiple_accounts(&[Pubkey::default()]).await;
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_stale_context_slot_is_rejected() {
+    assert!(ensure_min_context_slot(100, Some(101)).is_err());
+    assert!(ensure_min_context_slot(101, Some(101)).is_ok());
+    assert!(ensure_min_context_slot(5, None).is_ok());
+}
+
+#[tokio::test]
+async fn test_simulate_compute_units_from_mock_response() {
+    let mut mocks = HashMap::new();
+    mocks.insert(
+        RpcRequest::SimulateTransaction,
+        json!({
+            "context": { "slot": 1 },
+            "value": { "err": null, "logs": [], "accounts": null, "unitsConsumed": 4242 }
+        }),
+    );
+    let rpc = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+
+    let units = rpc
+        .simulate_compute_units(&Pubkey::new_unique(), &[])
+        .await
+        .unwrap();
+    assert_eq!(units, 4242);
+}
+
+#[test]
+fn test_rate_limit_error_from_mocked_429_response() {
+    let err = ClientError::from(ErrorKind::RpcError(RpcError::RpcResponseError {
+        code: 429,
+        message: "Too many requests for a specific RPC call, retry after 2s".to_string(),
+        data: RpcResponseErrorData::Empty,
+    }));
+    assert_eq!(
+        rate_limit_error(&err),
+        Some(OpenBookError::RateLimited {
+            retry_after: Some(Duration::from_secs(2))
+        })
+    );
+
+    let typed = with_rate_limit(err);
+    assert!(matches!(
+        typed.downcast_ref::<OpenBookError>(),
+        Some(OpenBookError::RateLimited { .. })
+    ));
+
+    let err = ClientError::from(ErrorKind::RpcError(RpcError::RpcRequestError(
+        "HTTP status client error (429 Too Many Requests)".to_string(),
+    )));
+    assert_eq!(
+        rate_limit_error(&err),
+        Some(OpenBookError::RateLimited { retry_after: None })
+    );
+
+    let err = ClientError::from(ErrorKind::Custom("blockhash not found".to_string()));
+    assert_eq!(rate_limit_error(&err), None);
+}
+
+/// An RPC transport answering every request with a 429 error.
+struct RateLimitedSender;
+
+impl RpcSender for RateLimitedSender {
+    fn send<'life0, 'async_trait>(
+        &'life0 self,
+        _request: RpcRequest,
+        _params: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value, ClientError>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async {
+            Err(ErrorKind::RpcError(RpcError::RpcResponseError {
+                code: 429,
+                message: "Too many requests for a specific RPC call, retry after 2s".to_string(),
+                data: RpcResponseErrorData::Empty,
+            })
+            .into())
+        })
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "rate-limited".to_string()
+    }
+}
+
+#[tokio::test]
+async fn test_every_read_surfaces_rate_limits() {
+    let rpc = Rpc::new(RpcClient::new_sender(
+        RateLimitedSender,
+        RpcClientConfig::default(),
+    ));
+    let address = Pubkey::new_unique();
+    let rate_limited = |result: anyhow::Result<()>| {
+        assert_eq!(
+            result.unwrap_err().downcast_ref::<OpenBookError>(),
+            Some(&OpenBookError::RateLimited {
+                retry_after: Some(Duration::from_secs(2))
+            })
+        );
+    };
+
+    rate_limited(rpc.get_account(&address).await.map(|_| ()));
+    rate_limited(rpc.fetch_account(&address).await.map(|_| ()));
+    rate_limited(rpc.fetch_balance(&address).await.map(|_| ()));
+    rate_limited(rpc.fetch_slot().await.map(|_| ()));
+    rate_limited(rpc.fetch_rent_exemption(165).await.map(|_| ()));
+    rate_limited(rpc.fetch_token_account_balance(&address).await.map(|_| ()));
+    rate_limited(
+        rpc.fetch_token_accounts_by_owner(&address, TokenAccountsFilter::Mint(address))
+            .await
+            .map(|_| ()),
+    );
+    rate_limited(
+        rpc.fetch_recent_prioritization_fees(&[address])
+            .await
+            .map(|_| ()),
+    );
+    rate_limited(
+        rpc.fetch_transaction(&Signature::default())
+            .await
+            .map(|_| ()),
+    );
+    rate_limited(
+        rpc.fetch_signatures_for_address(&address, None, None)
+            .await
+            .map(|_| ()),
+    );
+    // The retried reads give up on a rate limit instead of retrying it.
+    rate_limited(rpc.fetch_multiple_accounts(&[address]).await.map(|_| ()));
+    rate_limited(
+        rpc.fetch_multiple_accounts_at_slot(&[address], None)
+            .await
+            .map(|_| ()),
+    );
+    rate_limited(
+        rpc.fetch_program_accounts(&address, vec![])
+            .await
+            .map(|_| ()),
+    );
+    rate_limited(rpc.total_fees_paid(&address, None).await.map(|_| ()));
+}
+
+#[test]
+fn test_parse_retry_after() {
+    assert_eq!(
+        parse_retry_after("Retry-After: 3"),
+        Some(Duration::from_secs(3))
+    );
+    assert_eq!(
+        parse_retry_after("retry after 500ms"),
+        Some(Duration::from_millis(500))
+    );
+    assert_eq!(parse_retry_after("too many requests"), None);
+}
+
+#[test]
+fn test_send_config_threads_max_retries() {
+    let send_config = SendConfig {
+        skip_preflight: true,
+        max_retries: Some(5),
+        ..Default::default()
+    };
+    let rpc = Rpc::new(RpcClient::new_mock("succeeds".to_string())).with_send_config(send_config);
+    assert_eq!(rpc.send_config(), &send_config);
+
+    let config = rpc
+        .send_config()
+        .rpc_send_config(CommitmentLevel::Confirmed);
+    assert_eq!(config.max_retries, Some(5));
+    assert!(config.skip_preflight);
+    assert_eq!(
+        config.preflight_commitment,
+        Some(CommitmentLevel::Confirmed)
+    );
+
+    let default_config = SendConfig::default().rpc_send_config(CommitmentLevel::Confirmed);
+    assert_eq!(default_config.max_retries, None);
+    assert!(!default_config.skip_preflight);
+}
+
+#[tokio::test]
+async fn test_snapshot_accounts_share_one_context_slot() {
+    let account = json!({
+        "lamports": 1,
+        "data": ["", "base64"],
+        "owner": "11111111111111111111111111111111",
+        "executable": false,
+        "rentEpoch": 0,
+        "space": 0
+    });
+    let mut mocks = HashMap::new();
+    mocks.insert(
+        RpcRequest::GetMultipleAccounts,
+        json!({
+            "context": { "slot": 42 },
+            "value": [account.clone(), account.clone(), account.clone(), account]
+        }),
+    );
+    let rpc = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+    // market, bids, asks and event queue
+    let pubkeys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+
+    let (slot, accounts) = rpc
+        .fetch_multiple_accounts_at_slot(&pubkeys, Some(40))
+        .await
+        .unwrap();
+    assert_eq!(slot, 42);
+    assert_eq!(accounts.len(), 4);
+    assert!(accounts.iter().all(|account| account.is_some()));
+}
+
+#[test]
+fn test_sum_transaction_fees() {
+    let owner = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let transaction = |payer: &Pubkey, fee: u64| -> EncodedConfirmedTransactionWithStatusMeta {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![
+                AccountMeta::new(other, false),
+                AccountMeta::new(owner, true),
+            ],
+        );
+        let transaction = Transaction::new_with_payer(&[ix], Some(payer));
+        serde_json::from_value(json!({
+            "slot": 1,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "transaction": transaction.encode(UiTransactionEncoding::Base64),
+                "meta": {
+                    "err": null,
+                    "status": { "Ok": null },
+                    "fee": fee,
+                    "preBalances": [],
+                    "postBalances": []
+                }
+            }
+        }))
+        .unwrap()
+    };
+    // The owner signs the third transaction but does not pay for it.
+    let transactions = vec![
+        transaction(&owner, 5_000),
+        transaction(&owner, 12_500),
+        transaction(&other, 5_000),
+    ];
+
+    assert_eq!(sum_transaction_fees(&owner, &transactions), 17_500);
+    assert_eq!(sum_transaction_fees(&other, &transactions), 5_000);
+    assert_eq!(sum_transaction_fees(&owner, &[]), 0);
+}
+
+#[test]
+fn test_fee_percentile() {
+    let fees = [40, 10, 30, 20];
+    assert_eq!(fee_percentile(&fees, 75), 30);
+    assert_eq!(fee_percentile(&fees, 100), 40);
+    assert_eq!(fee_percentile(&fees, 0), 10);
+    assert_eq!(fee_percentile(&[], 75), 0);
+}
+
+#[tokio::test]
+async fn test_with_priority_fee_applies_percentile_price() {
+    let fees = json!([
+        { "slot": 1, "prioritizationFee": 100 },
+        { "slot": 2, "prioritizationFee": 400 },
+        { "slot": 3, "prioritizationFee": 200 },
+        { "slot": 4, "prioritizationFee": 300 },
+    ]);
+    let mocks = HashMap::from([(RpcRequest::GetRecentPrioritizationFees, fees)]);
+    let rpc = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ))
+    .with_send_config(SendConfig {
+        priority_fee_percentile: Some(75),
+        ..Default::default()
+    });
+
+    let ix = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[],
+        vec![AccountMeta::new(Pubkey::new_unique(), false)],
+    );
+    let instructions = rpc.with_priority_fee(vec![ix.clone()]).await.unwrap();
+    assert_eq!(
+        instructions,
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_price(300),
+            ix.clone()
+        ]
+    );
+
+    // An explicit compute-unit price is left alone.
+    let unchanged = rpc.with_priority_fee(instructions.clone()).await.unwrap();
+    assert_eq!(unchanged, instructions);
+
+    let disabled = Rpc::new(RpcClient::new_mock("succeeds".to_string()));
+    assert_eq!(
+        disabled.with_priority_fee(vec![ix.clone()]).await.unwrap(),
+        vec![ix]
+    );
+}
+
+#[test]
+fn test_compute_units_consumed_from_confirmed_transaction() {
+    let transaction = |meta: serde_json::Value| -> EncodedConfirmedTransactionWithStatusMeta {
+        serde_json::from_value(json!({
+            "slot": 1,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "transaction": ["", "base64"],
+                "meta": meta
+            }
+        }))
+        .unwrap()
+    };
+    let meta = json!({
+        "err": null,
+        "status": { "Ok": null },
+        "fee": 5_000,
+        "preBalances": [],
+        "postBalances": [],
+        "computeUnitsConsumed": 23_456
+    });
+
+    assert_eq!(compute_units_consumed(&transaction(meta)), Some(23_456));
+
+    let meta = json!({
+        "err": null,
+        "status": { "Ok": null },
+        "fee": 5_000,
+        "preBalances": [],
+        "postBalances": []
+    });
+    assert_eq!(compute_units_consumed(&transaction(meta)), None);
+    assert_eq!(
+        compute_units_consumed(&transaction(serde_json::Value::Null)),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_sends_record_compute_units_when_a_limit_is_set() {
+    let rpc = |compute_unit_limit: Option<u32>| {
+        let mocks = HashMap::from([(
+            RpcRequest::GetTransaction,
+            json!({
+                "slot": 1,
+                "blockTime": 1_700_000_000,
+                "transaction": {
+                    "transaction": ["", "base64"],
+                    "meta": {
+                        "err": null,
+                        "status": { "Ok": null },
+                        "fee": 5_000,
+                        "preBalances": [],
+                        "postBalances": [],
+                        "computeUnitsConsumed": 23_456
+                    }
+                }
+            }),
+        )]);
+        Rpc::new(RpcClient::new_mock_with_mocks(
+            "succeeds".to_string(),
+            mocks,
+        ))
+        .with_send_config(SendConfig {
+            compute_unit_limit,
+            ..Default::default()
+        })
+    };
+    let ix = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[],
+        vec![AccountMeta::new(Pubkey::new_unique(), false)],
+    );
+
+    // The plain send path goes through `send_and_confirm_with_units` and records the units.
+    let limited = rpc(Some(200_000));
+    let (confirmed, _) = limited
+        .send_and_confirm(Keypair::new(), vec![ix.clone()])
+        .await
+        .unwrap();
+    assert!(confirmed);
+    assert_eq!(limited.clone().last_compute_units().await, Some(23_456));
+
+    // Without a limit the confirmed transaction is not fetched again.
+    let unlimited = rpc(None);
+    let (confirmed, _, units) = unlimited
+        .send_and_confirm_with_units(&[&Keypair::new()], vec![ix])
+        .await
+        .unwrap();
+    assert!(confirmed);
+    assert_eq!(units, None);
+    assert_eq!(unlimited.last_compute_units().await, None);
+}
+
+#[tokio::test]
+async fn test_with_commitment_overrides_the_client_commitment() {
+    let send_config = SendConfig {
+        skip_preflight: true,
+        ..SendConfig::default()
+    };
+    let rpc = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        HashMap::from([(
+            RpcRequest::GetAccountInfo,
+            json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "lamports": 7,
+                    "data": ["", "base58"],
+                    "owner": Pubkey::default().to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                    "space": 0,
+                },
+            }),
+        )]),
+    ))
+    .with_send_config(send_config);
+    assert_eq!(rpc.commitment(), rpc.inner().commitment());
+
+    let processed = rpc.clone().with_commitment(CommitmentConfig::processed());
+
+    assert_eq!(processed.commitment(), CommitmentConfig::processed());
+    assert_eq!(processed.send_config(), &send_config);
+    assert_eq!(rpc.commitment(), rpc.inner().commitment());
+    let account = processed
+        .fetch_account(&Pubkey::new_unique())
+        .await
+        .unwrap();
+    assert_eq!(account.lamports, 7);
+}
+
+#[tokio::test]
+async fn test_with_priority_fee_prepends_fixed_price_and_limit() {
+    let rpc = Rpc::new(RpcClient::new_mock("succeeds".to_string())).with_send_config(SendConfig {
+        priority_fee_percentile: Some(75),
+        priority_fee_micro_lamports: Some(5_000),
+        compute_unit_limit: Some(200_000),
+        ..Default::default()
+    });
+
+    let ix = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[],
+        vec![AccountMeta::new(Pubkey::new_unique(), false)],
+    );
+    let instructions = rpc.with_priority_fee(vec![ix.clone()]).await.unwrap();
+    assert_eq!(
+        instructions,
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_price(5_000),
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ix
+        ]
+    );
+
+    // Instructions carrying their own compute budget are not given a second one.
+    let unchanged = rpc.with_priority_fee(instructions.clone()).await.unwrap();
+    assert_eq!(unchanged, instructions);
+}
+
+#[tokio::test]
+async fn test_latest_blockhash_is_cached_for_the_ttl() {
+    let cached = Hash::new_unique();
+    let mocks = HashMap::from([(
+        RpcRequest::GetLatestBlockhash,
+        json!({
+            "context": { "slot": 1 },
+            "value": { "blockhash": cached.to_string(), "lastValidBlockHeight": 100 },
+        }),
+    )]);
+    let rpc = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ))
+    .with_send_config(SendConfig {
+        blockhash_ttl: Some(Duration::from_secs(60)),
+        ..Default::default()
+    });
+
+    // The mock answers with `cached` only once, so repeated hits must come from the cache.
+    assert_eq!(rpc.latest_blockhash().await.unwrap(), cached);
+    assert_eq!(rpc.latest_blockhash().await.unwrap(), cached);
+
+    let refreshed = rpc.refresh_blockhash().await.unwrap();
+    assert_ne!(refreshed, cached);
+    assert_eq!(rpc.latest_blockhash().await.unwrap(), refreshed);
+}
+
+#[tokio::test]
+async fn test_wait_for_confirmation_times_out_on_unknown_signature() {
+    let signature = Signature::new_unique();
+    let mocks = HashMap::from([(
+        RpcRequest::GetSignatureStatuses,
+        json!({"context": {"slot": 1}, "value": [null]}),
+    )]);
+    let rpc = Rpc::new(RpcClient::new_mock_with_mocks(
+        "succeeds".to_string(),
+        mocks,
+    ));
+
+    let err = rpc
+        .wait_for_confirmation(&signature, Duration::ZERO)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(&OpenBookError::ConfirmationTimeout(signature))
+    );
+
+    // The default mock reports every signature as finalized.
+    rpc.wait_for_confirmation(&signature, Duration::ZERO)
+        .await
+        .unwrap();
+}
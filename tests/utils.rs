@@ -0,0 +1,96 @@
+use openbook::pubkey::Pubkey;
+#[cfg(feature = "ws")]
+use openbook::utils::ws_url_from_rpc_url;
+use openbook::utils::{
+    create_associated_token_account_instruction, split_into_transactions, transaction_size,
+};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+#[test]
+fn test_create_associated_token_account_uses_idempotent_instruction() {
+    let wallet = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let token_program_id = Pubkey::new_unique();
+
+    let ix =
+        create_associated_token_account_instruction(&wallet, &wallet, &mint, &token_program_id);
+
+    assert_eq!(ix.program_id, spl_associated_token_account::id());
+    // `AssociatedTokenAccountInstruction::CreateIdempotent` is encoded as 1, `Create` as 0.
+    assert_eq!(ix.data, vec![1]);
+    assert_eq!(
+        ix.accounts[1].pubkey,
+        get_associated_token_address_with_program_id(&wallet, &mint, &token_program_id)
+    );
+}
+
+#[test]
+fn test_split_into_transactions_respects_packet_size() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let instructions: Vec<_> = (0..8)
+        .map(|_| {
+            let accounts = (0..8)
+                .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+                .collect();
+            Instruction::new_with_bytes(program_id, &[7; 200], accounts)
+        })
+        .collect();
+
+    let groups = split_into_transactions(instructions.clone(), &payer);
+    assert!(groups.len() > 1);
+    assert!(groups
+        .iter()
+        .all(|group| transaction_size(group, &payer) <= PACKET_DATA_SIZE));
+    assert_eq!(groups.concat(), instructions);
+}
+
+#[test]
+fn test_transaction_size_reserves_compute_budget_instructions() {
+    let payer = Pubkey::new_unique();
+    let ix = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[7; 32],
+        vec![AccountMeta::new(Pubkey::new_unique(), false)],
+    );
+    let with_budget = vec![
+        ComputeBudgetInstruction::set_compute_unit_price(1_000),
+        ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+        ix.clone(),
+    ];
+
+    assert_eq!(
+        transaction_size(&[ix], &payer),
+        transaction_size(&with_budget, &payer)
+    );
+}
+
+#[cfg(feature = "ws")]
+#[test]
+fn test_ws_url_from_rpc_url() {
+    assert_eq!(
+        ws_url_from_rpc_url("https://api.mainnet-beta.solana.com"),
+        "wss://api.mainnet-beta.solana.com"
+    );
+    // A validator serves pubsub on the port after its RPC port.
+    assert_eq!(
+        ws_url_from_rpc_url("http://127.0.0.1:8899"),
+        "ws://127.0.0.1:8900"
+    );
+    assert_eq!(
+        ws_url_from_rpc_url("https://rpc.example.com:443/path?key=1"),
+        "wss://rpc.example.com:444/path?key=1"
+    );
+    assert_eq!(
+        ws_url_from_rpc_url("http://localhost:8899?api-key=1"),
+        "ws://localhost:8900?api-key=1"
+    );
+    assert_eq!(
+        ws_url_from_rpc_url("https://rpc.example.com/path"),
+        "wss://rpc.example.com/path"
+    );
+    assert_eq!(ws_url_from_rpc_url("ws://localhost"), "ws://localhost");
+}